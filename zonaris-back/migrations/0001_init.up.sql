@@ -0,0 +1,88 @@
+-- Schema created by the unversioned startup script, the statements are idempotent so that
+-- databases created by it are adopted as they are.
+
+CREATE TABLE IF NOT EXISTS satellite
+(
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+
+    catnr BIGINT NULL DEFAULT NULL,
+
+    tle1 VARCHAR NULL DEFAULT NULL,
+    tle2 VARCHAR NULL DEFAULT NULL,
+    omm VARCHAR NULL DEFAULT NULL
+);
+
+-- satellites created before element sets could be sourced from OMM
+ALTER TABLE satellite
+    ALTER COLUMN tle1 DROP NOT NULL,
+    ALTER COLUMN tle2 DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS omm VARCHAR NULL DEFAULT NULL;
+
+CREATE TABLE IF NOT EXISTS instrument
+(
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS satellite_instrument
+(
+    id SERIAL PRIMARY KEY,
+    satellite_id INTEGER NOT NULL REFERENCES satellite,
+    instrument_id INTEGER NOT NULL REFERENCES instrument
+);
+
+CREATE TABLE IF NOT EXISTS ocean_color_mapping
+(
+    id SERIAL PRIMARY KEY,
+    satellite_instrument_id INTEGER NOT NULL REFERENCES satellite_instrument,
+    sensor_id INTEGER NOT NULL,
+    data_id INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS instrument_data
+(
+    id SERIAL PRIMARY KEY,
+    satellite_instrument_id INTEGER NOT NULL REFERENCES satellite_instrument,
+    path VARCHAR NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ground_station
+(
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    altitude DOUBLE PRECISION NOT NULL,
+
+    min_elevation DOUBLE PRECISION NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS satellite_element_set
+(
+    id SERIAL PRIMARY KEY,
+    satellite_id INTEGER NOT NULL REFERENCES satellite,
+    epoch TIMESTAMPTZ NOT NULL,
+
+    tle1 VARCHAR NULL DEFAULT NULL,
+    tle2 VARCHAR NULL DEFAULT NULL,
+    omm VARCHAR NULL DEFAULT NULL,
+
+    UNIQUE (satellite_id, epoch)
+);
+
+CREATE TABLE IF NOT EXISTS satellite_catalog
+(
+    id SERIAL PRIMARY KEY,
+    satellite_id INTEGER NOT NULL UNIQUE REFERENCES satellite,
+
+    international_designator VARCHAR NOT NULL,
+    owner VARCHAR NOT NULL,
+    launch_date DATE NULL DEFAULT NULL,
+    launch_site VARCHAR NOT NULL,
+    object_type VARCHAR NOT NULL,
+    rcs DOUBLE PRECISION NULL DEFAULT NULL,
+    operational_status VARCHAR NOT NULL,
+    decay_date DATE NULL DEFAULT NULL
+);
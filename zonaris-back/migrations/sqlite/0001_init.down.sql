@@ -0,0 +1,8 @@
+DROP TABLE satellite_catalog;
+DROP TABLE satellite_element_set;
+DROP TABLE ground_station;
+DROP TABLE instrument_data;
+DROP TABLE ocean_color_mapping;
+DROP TABLE satellite_instrument;
+DROP TABLE instrument;
+DROP TABLE satellite;
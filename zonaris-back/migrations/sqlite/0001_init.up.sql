@@ -0,0 +1,82 @@
+-- Schema of migrations/0001_init.up.sql in SQLite: ids are never reused, timestamps and dates
+-- are stored as text which sorts in time order.
+
+CREATE TABLE satellite
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+
+    catnr INTEGER NULL DEFAULT NULL,
+
+    tle1 TEXT NULL DEFAULT NULL,
+    tle2 TEXT NULL DEFAULT NULL,
+    omm TEXT NULL DEFAULT NULL
+);
+
+CREATE TABLE instrument
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE satellite_instrument
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    satellite_id INTEGER NOT NULL REFERENCES satellite (id),
+    instrument_id INTEGER NOT NULL REFERENCES instrument (id)
+);
+
+CREATE TABLE ocean_color_mapping
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    satellite_instrument_id INTEGER NOT NULL REFERENCES satellite_instrument (id),
+    sensor_id INTEGER NOT NULL,
+    data_id INTEGER NOT NULL
+);
+
+CREATE TABLE instrument_data
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    satellite_instrument_id INTEGER NOT NULL REFERENCES satellite_instrument (id),
+    path TEXT NOT NULL
+);
+
+CREATE TABLE ground_station
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+
+    latitude REAL NOT NULL,
+    longitude REAL NOT NULL,
+    altitude REAL NOT NULL,
+
+    min_elevation REAL NOT NULL
+);
+
+CREATE TABLE satellite_element_set
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    satellite_id INTEGER NOT NULL REFERENCES satellite (id),
+    epoch TEXT NOT NULL,
+
+    tle1 TEXT NULL DEFAULT NULL,
+    tle2 TEXT NULL DEFAULT NULL,
+    omm TEXT NULL DEFAULT NULL,
+
+    UNIQUE (satellite_id, epoch)
+);
+
+CREATE TABLE satellite_catalog
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    satellite_id INTEGER NOT NULL UNIQUE REFERENCES satellite (id),
+
+    international_designator TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    launch_date TEXT NULL DEFAULT NULL,
+    launch_site TEXT NOT NULL,
+    object_type TEXT NOT NULL,
+    rcs REAL NULL DEFAULT NULL,
+    operational_status TEXT NOT NULL,
+    decay_date TEXT NULL DEFAULT NULL
+);
@@ -0,0 +1,82 @@
+// Command line of the binary. Every step of the server startup can be run on its own, e.g. the
+// migrations and the seed by init containers before `zonaris serve --skip-migrate --skip-seed`.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(name = "zonaris", version, about)]
+pub struct Cli {
+    /// `serve` if omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Migrate the database, seed the initial data, start the jobs and serve the API
+    Serve {
+        /// Expect the database to be migrated already
+        #[arg(long)]
+        skip_migrate: bool,
+        /// Don't apply the seed file
+        #[arg(long)]
+        skip_seed: bool,
+    },
+    /// Migrate the database, `migrate up` if no action is given
+    Migrate {
+        #[command(subcommand)]
+        action: Option<MigrateCommand>,
+    },
+    /// Apply a seed file, the configured one if omitted
+    Seed { file: Option<PathBuf> },
+    /// Create the satellite with the catalog number or update its elements
+    FetchTle { catnr: u32 },
+    /// Render and record the OceanColor granules of every mapping found in the time range
+    IngestOceancolor {
+        /// RFC 3339 time, e.g. 2024-01-01T00:00:00Z
+        #[arg(long)]
+        from: DateTime<Utc>,
+        /// RFC 3339 time, now if omitted
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+    },
+    /// Render a variable of a NetCDF file to an image
+    Render {
+        file: PathBuf,
+        #[arg(long, default_value = "sst4")]
+        var: String,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Print the configuration with the secrets redacted
+    PrintConfig,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum MigrateCommand {
+    /// Apply the pending migrations
+    Up,
+    /// Revert the applied migrations after a version, 0 reverts all of them
+    Down {
+        #[arg(long)]
+        to: i32,
+    },
+    /// List the known and applied migrations
+    Status,
+    /// Compare the tables of the database with the models, fails if they differ
+    Check,
+    /// Print the CREATE TABLE statements generated from the models
+    Schema,
+}
+
+impl Cli {
+    pub fn command(self) -> Command {
+        return self.command.unwrap_or(Command::Serve {
+            skip_migrate: false,
+            skip_seed: false,
+        });
+    }
+}
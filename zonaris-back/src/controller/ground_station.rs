@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::Router;
+use axum::{extract::State, Json};
+use chrono::Duration;
+
+use crate::dto::ground_station::{
+    CreateGroundStationRequest, GetPassesRequest, GroundStationResponse, PassResponse,
+};
+use crate::persistence::model::ground_station::GroundStation;
+use crate::persistence::repository::Id;
+use crate::routes::AppContext;
+
+use super::satellite::propagation_error;
+use super::utils::AppError;
+
+const PATH_ALL: &str = "/station/all";
+const PATH_ADD: &str = "/station";
+const PATH_PASSES: &str = "/station/:id/passes";
+
+const MAX_PASSES_WINDOW_DAYS: i64 = 30;
+
+#[utoipa::path(
+    get,
+    path = PATH_ALL,
+    responses(
+        (status = 200, body=[GroundStationResponse])
+    )
+)]
+async fn get_all(
+    ctx: State<Arc<AppContext>>,
+) -> Result<Json<Vec<GroundStationResponse>>, AppError> {
+    return Ok(Json(
+        ctx.ground_station_service
+            .get_all()
+            .await?
+            .into_iter()
+            .map(GroundStationResponse::from)
+            .collect(),
+    ));
+}
+
+#[utoipa::path(
+    post,
+    path = PATH_ADD,
+    request_body = CreateGroundStationRequest,
+    responses(
+        (status = 200, body=i32),
+        (status = 400)
+    )
+)]
+async fn add(
+    ctx: State<Arc<AppContext>>,
+    Json(request): Json<CreateGroundStationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !request.is_valid() {
+        return Err(AppError::BadRequest(String::from(
+            "expected non-empty name, latitude in [-90; 90], longitude in [-180; 180] and min_elevation in [-90; 90]",
+        )));
+    }
+
+    let id = ctx
+        .ground_station_service
+        .add(GroundStation::from(request))
+        .await?;
+
+    return Ok(Json(id).into_response());
+}
+
+#[utoipa::path(
+    get,
+    path = "/station/{id}/passes",
+    params(
+        ("id" = i32, Path, description = "Ground station id"),
+        GetPassesRequest
+    ),
+    responses(
+        (status = 200, body=[PassResponse]),
+        (status = 400),
+        (status = 404),
+        (status = 422)
+    )
+)]
+async fn get_passes(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+    request: Query<GetPassesRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let window = *request.get_to() - *request.get_from();
+    if window <= Duration::zero() || window > Duration::days(MAX_PASSES_WINDOW_DAYS) {
+        return Err(AppError::BadRequest(format!(
+            "expected from < to and window at most {} days",
+            MAX_PASSES_WINDOW_DAYS
+        )));
+    }
+
+    let passes = match ctx
+        .ground_station_service
+        .get_passes(
+            id,
+            request.get_satellite_id(),
+            *request.get_from(),
+            *request.get_to(),
+        )
+        .await
+        .map_err(propagation_error)?
+    {
+        Some(passes) => passes,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "ground station with id {} or satellite with id {} not found",
+                id,
+                request.get_satellite_id()
+            )));
+        }
+    };
+
+    return Ok(Json(
+        passes
+            .into_iter()
+            .map(PassResponse::from)
+            .collect::<Vec<_>>(),
+    )
+    .into_response());
+}
+
+pub fn create_router(ctx: Arc<AppContext>) -> Router {
+    return Router::new()
+        .route(PATH_ALL, get(get_all))
+        .route(PATH_ADD, post(add))
+        .route(PATH_PASSES, get(get_passes))
+        .with_state(ctx);
+}
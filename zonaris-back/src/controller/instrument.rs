@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get, post};
+use axum::Router;
+use axum::{extract::State, Json};
+
+use crate::dto::instrument::{
+    InstrumentRequest, InstrumentResponse, LinkInstrumentRequest, SatelliteInstrumentResponse,
+};
+use crate::persistence::model::instrument::Instrument;
+use crate::persistence::repository::Id;
+use crate::routes::AppContext;
+use crate::service::instrument::InstrumentError;
+
+use super::utils::AppError;
+
+const PATH_ALL: &str = "/instrument/all";
+const PATH_ADD: &str = "/instrument";
+const PATH_ID: &str = "/instrument/:id";
+const PATH_SATELLITE: &str = "/satellite/:id/instruments";
+const PATH_SATELLITE_INSTRUMENT: &str = "/satellite/:id/instruments/:instrument_id";
+
+const INVALID_NAME: &str = "expected non-empty name";
+
+/// Client errors of the instrument service, the others are classified by `AppError`
+fn instrument_error(error: anyhow::Error) -> AppError {
+    let detail = error.to_string();
+    return match error.downcast_ref::<InstrumentError>() {
+        Some(InstrumentError::SatelliteNotFound(_) | InstrumentError::InstrumentNotFound(_)) => {
+            AppError::NotFound(detail)
+        }
+        Some(
+            InstrumentError::AlreadyLinked { .. }
+            | InstrumentError::HasSatellites(_)
+            | InstrumentError::LinkInUse(_),
+        ) => AppError::Conflict(detail),
+        None => AppError::from(error),
+    };
+}
+
+fn not_found(id: Id) -> AppError {
+    return AppError::NotFound(format!("instrument with id {} not found", id));
+}
+
+#[utoipa::path(
+    get,
+    path = PATH_ALL,
+    responses(
+        (status = 200, body=[InstrumentResponse])
+    )
+)]
+async fn get_all(ctx: State<Arc<AppContext>>) -> Result<Json<Vec<InstrumentResponse>>, AppError> {
+    return Ok(Json(
+        ctx.instrument_service
+            .get_all()
+            .await?
+            .into_iter()
+            .map(InstrumentResponse::from)
+            .collect(),
+    ));
+}
+
+#[utoipa::path(
+    post,
+    path = PATH_ADD,
+    request_body = InstrumentRequest,
+    responses(
+        (status = 200, body=i32),
+        (status = 400)
+    )
+)]
+async fn add(
+    ctx: State<Arc<AppContext>>,
+    Json(request): Json<InstrumentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !request.is_valid() {
+        return Err(AppError::BadRequest(String::from(INVALID_NAME)));
+    }
+
+    let id = ctx
+        .instrument_service
+        .add(Instrument::from(request))
+        .await?;
+
+    return Ok(Json(id).into_response());
+}
+
+#[utoipa::path(
+    get,
+    path = "/instrument/{id}",
+    params(
+        ("id" = i32, Path, description = "Instrument id")
+    ),
+    responses(
+        (status = 200, body=InstrumentResponse),
+        (status = 404)
+    )
+)]
+async fn get_by_id(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+) -> Result<impl IntoResponse, AppError> {
+    return match ctx.instrument_service.get(id).await? {
+        Some(instrument) => Ok(Json(InstrumentResponse::from(instrument)).into_response()),
+        None => Err(not_found(id)),
+    };
+}
+
+#[utoipa::path(
+    patch,
+    path = "/instrument/{id}",
+    params(
+        ("id" = i32, Path, description = "Instrument id")
+    ),
+    request_body = InstrumentRequest,
+    responses(
+        (status = 200, body=InstrumentResponse),
+        (status = 400),
+        (status = 404)
+    )
+)]
+async fn update(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+    Json(request): Json<InstrumentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !request.is_valid() {
+        return Err(AppError::BadRequest(String::from(INVALID_NAME)));
+    }
+
+    return match ctx
+        .instrument_service
+        .update(id, request.get_name())
+        .await?
+    {
+        Some(instrument) => Ok(Json(InstrumentResponse::from(instrument)).into_response()),
+        None => Err(not_found(id)),
+    };
+}
+
+#[utoipa::path(
+    delete,
+    path = "/instrument/{id}",
+    params(
+        ("id" = i32, Path, description = "Instrument id")
+    ),
+    responses(
+        (status = 200),
+        (status = 404),
+        (status = 409)
+    )
+)]
+async fn delete_by_id(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+) -> Result<impl IntoResponse, AppError> {
+    return match ctx.instrument_service.delete(id).await {
+        Ok(true) => Ok(StatusCode::OK.into_response()),
+        Ok(false) => Err(not_found(id)),
+        Err(error) => Err(instrument_error(error)),
+    };
+}
+
+#[utoipa::path(
+    get,
+    path = "/satellite/{id}/instruments",
+    params(
+        ("id" = i32, Path, description = "Satellite id")
+    ),
+    responses(
+        (status = 200, body=[SatelliteInstrumentResponse]),
+        (status = 404)
+    )
+)]
+async fn get_by_satellite_id(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+) -> Result<impl IntoResponse, AppError> {
+    let instruments = match ctx.instrument_service.get_by_satellite_id(id).await? {
+        Some(instruments) => instruments,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "satellite with id {} not found",
+                id
+            )));
+        }
+    };
+
+    return Ok(Json(
+        instruments
+            .into_iter()
+            .map(|(link, instrument)| SatelliteInstrumentResponse::new(link, instrument))
+            .collect::<Vec<_>>(),
+    )
+    .into_response());
+}
+
+#[utoipa::path(
+    post,
+    path = "/satellite/{id}/instruments",
+    params(
+        ("id" = i32, Path, description = "Satellite id")
+    ),
+    request_body = LinkInstrumentRequest,
+    responses(
+        (status = 200, body=i32),
+        (status = 404),
+        (status = 409)
+    )
+)]
+async fn link(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+    Json(request): Json<LinkInstrumentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    return match ctx
+        .instrument_service
+        .link(id, request.get_instrument_id())
+        .await
+    {
+        Ok(link_id) => Ok(Json(link_id).into_response()),
+        Err(error) => Err(instrument_error(error)),
+    };
+}
+
+#[utoipa::path(
+    delete,
+    path = "/satellite/{id}/instruments/{instrument_id}",
+    params(
+        ("id" = i32, Path, description = "Satellite id"),
+        ("instrument_id" = i32, Path, description = "Instrument id")
+    ),
+    responses(
+        (status = 200),
+        (status = 404),
+        (status = 409)
+    )
+)]
+async fn unlink(
+    ctx: State<Arc<AppContext>>,
+    Path((id, instrument_id)): Path<(Id, Id)>,
+) -> Result<impl IntoResponse, AppError> {
+    return match ctx.instrument_service.unlink(id, instrument_id).await {
+        Ok(true) => Ok(StatusCode::OK.into_response()),
+        Ok(false) => Err(AppError::NotFound(format!(
+            "instrument with id {} is not linked to satellite with id {}",
+            instrument_id, id
+        ))),
+        Err(error) => Err(instrument_error(error)),
+    };
+}
+
+pub fn create_router(ctx: Arc<AppContext>) -> Router {
+    return Router::new()
+        .route(PATH_ALL, get(get_all))
+        .route(PATH_ADD, post(add))
+        .route(PATH_ID, get(get_by_id).patch(update).delete(delete_by_id))
+        .route(PATH_SATELLITE, get(get_by_satellite_id).post(link))
+        .route(PATH_SATELLITE_INSTRUMENT, delete(unlink))
+        .with_state(ctx);
+}
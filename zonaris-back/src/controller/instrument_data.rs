@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use axum::extract::Query;
 use axum::http::header::{self, HeaderMap};
-use axum::http::{HeaderValue, StatusCode};
+use axum::http::HeaderValue;
 use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::Router;
@@ -36,7 +36,7 @@ async fn get_by_satellite_id(
             .get_by_satellite_id(request.get_id())
             .await?
             .into_iter()
-            .map(|it| InstrumentDataResponse::from(it))
+            .map(InstrumentDataResponse::from)
             .collect(),
     ));
 }
@@ -61,11 +61,10 @@ async fn get_asset(
     {
         Some(instrument_data) => instrument_data,
         None => {
-            return Ok((
-                StatusCode::NOT_FOUND,
-                format!("instrument data with id {} not found", request.get_id()),
-            )
-                .into_response());
+            return Err(AppError::NotFound(format!(
+                "instrument data with id {} not found",
+                request.get_id()
+            )));
         }
     };
 
@@ -1,5 +1,7 @@
-pub mod satellite;
+pub mod ground_station;
+pub mod instrument;
 pub mod instrument_data;
+pub mod satellite;
 pub mod utils;
 
 // TODO: actually it's a little bit tricky to create controller as struct
@@ -1,15 +1,45 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use axum::extract::{Path, Query};
+use axum::http::header::{self, HeaderMap};
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::IntoResponse;
 use axum::Router;
 use axum::{extract::State, Json};
+use chrono::{Duration, Utc};
 
-use crate::dto::satellite::SatelliteResponse;
+use crate::dto::satellite::{
+    CreateSatelliteRequest, ElementSetResponse, GetGroundTrackRequest, GetHistoryRequest,
+    GetPositionRequest, GetTrackingRequest, GroundTrackResponse, PositionResponse,
+    SatelliteResponse, TrackingFormat, TrackingResponse, UpdateSatelliteRequest,
+};
+use crate::persistence::repository::{HasId, Id};
+use crate::service::celestrak;
+use crate::service::element_set::ElementSetError;
+use crate::service::satellite::SatelliteError;
+use crate::utils::element_set::ElementSet;
+use crate::utils::geodesy::Geodetic;
+use crate::utils::sgp4::Sgp4Error;
+use crate::utils::tle::TLE;
 
 use crate::routes::AppContext;
 
 use super::utils::AppError;
 
 const PATH_ALL: &str = "/satellite/all";
+const PATH_ADD: &str = "/satellite";
+const PATH_ID: &str = "/satellite/:id";
+const PATH_POSITION: &str = "/satellite/position";
+const PATH_GROUND_TRACK: &str = "/satellite/ground_track";
+const PATH_TRACKING: &str = "/satellite/tracking";
+const PATH_HISTORY: &str = "/satellite/:id/history";
+
+const DEFAULT_GROUND_TRACK_STEP: i64 = 60;
+const MAX_GROUND_TRACK_POINTS: i64 = 10_000;
+
+const DEFAULT_TRACKING_STEP: i64 = 1;
+const MAX_TRACKING_POINTS: i64 = 100_000;
 
 #[utoipa::path(
     get,
@@ -19,18 +49,386 @@ const PATH_ALL: &str = "/satellite/all";
     )
 )]
 async fn get_all(ctx: State<Arc<AppContext>>) -> Result<Json<Vec<SatelliteResponse>>, AppError> {
+    let mut catalogs = ctx
+        .satellite_service
+        .get_all_catalogs()
+        .await?
+        .into_iter()
+        .map(|it| (*it.get_satellite_id(), it))
+        .collect::<HashMap<_, _>>();
+
     return Ok(Json(
         ctx.satellite_service
             .get_all()
             .await?
             .into_iter()
-            .map(|it| SatelliteResponse::from(it))
+            .map(|it| {
+                let catalog = it.get_id().and_then(|id| catalogs.remove(&id));
+                SatelliteResponse::new(it, catalog)
+            })
             .collect(),
     ));
 }
 
+/// Client errors of the satellite service, the others are classified by `AppError`
+fn satellite_error(error: anyhow::Error) -> AppError {
+    let detail = error.to_string();
+    return match error.downcast_ref::<SatelliteError>() {
+        Some(SatelliteError::NotFound(_)) => AppError::NotFound(detail),
+        Some(SatelliteError::Ambiguous { .. }) => AppError::BadRequest(detail),
+        Some(SatelliteError::AlreadyExists { .. } | SatelliteError::HasInstruments(_)) => {
+            AppError::Conflict(detail)
+        }
+        None => AppError::from(error),
+    };
+}
+
+/// Satellites without an element set and element sets which can't be propagated to the
+/// requested time, e.g. after the decay, are client errors
+pub(crate) fn propagation_error(error: anyhow::Error) -> AppError {
+    if let Some(element_set_error) = error.downcast_ref::<ElementSetError>() {
+        return AppError::Unprocessable(element_set_error.to_string());
+    }
+
+    return match error.chain().find_map(|it| it.downcast_ref::<Sgp4Error>()) {
+        Some(sgp4_error) => AppError::Unprocessable(format!(
+            "can't propagate to the requested time: {}",
+            sgp4_error
+        )),
+        None => AppError::from(error),
+    };
+}
+
+#[utoipa::path(
+    post,
+    path = PATH_ADD,
+    request_body = CreateSatelliteRequest,
+    responses(
+        (status = 200, body=i32),
+        (status = 400),
+        (status = 404),
+        (status = 409)
+    )
+)]
+async fn add(
+    ctx: State<Arc<AppContext>>,
+    Json(request): Json<CreateSatelliteRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !request.is_valid() {
+        return Err(AppError::BadRequest(String::from(
+            "expected either positive catnr or international_designator (yyyy-nnn[ppp]) and non-empty name if present",
+        )));
+    }
+
+    let query = match (request.get_catnr(), request.get_international_designator()) {
+        (Some(catnr), _) => celestrak::Query::CATNR(catnr),
+        (_, Some(international_designator)) => {
+            celestrak::Query::INTDES(international_designator.clone())
+        }
+        _ => unreachable!("validated request has a query"),
+    };
+
+    return match ctx
+        .satellite_service
+        .add(query, request.get_name().clone())
+        .await
+    {
+        Ok(id) => Ok(Json(id).into_response()),
+        Err(error) => Err(satellite_error(error)),
+    };
+}
+
+#[utoipa::path(
+    patch,
+    path = "/satellite/{id}",
+    params(
+        ("id" = i32, Path, description = "Satellite id")
+    ),
+    request_body = UpdateSatelliteRequest,
+    responses(
+        (status = 200, body=SatelliteResponse),
+        (status = 400),
+        (status = 404),
+        (status = 409)
+    )
+)]
+async fn update(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+    Json(request): Json<UpdateSatelliteRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !request.is_valid() {
+        return Err(AppError::BadRequest(String::from(
+            "expected non-empty name and/or both tle1 and tle2",
+        )));
+    }
+
+    let element_set = match (request.get_tle1(), request.get_tle2()) {
+        (Some(tle1), Some(tle2)) => {
+            let name = request.get_name().clone().unwrap_or_default();
+            match TLE::new(&name, tle1, tle2) {
+                Ok(tle) => Some(ElementSet::TLE(tle)),
+                Err(error) => {
+                    return Err(AppError::BadRequest(format!("invalid TLE: {}", error)));
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let satellite = match ctx
+        .satellite_service
+        .update(id, request.get_name().clone(), element_set)
+        .await
+    {
+        Ok(Some(satellite)) => satellite,
+        Ok(None) => {
+            return Err(AppError::NotFound(format!(
+                "satellite with id {} not found",
+                id
+            )));
+        }
+        Err(error) => return Err(satellite_error(error)),
+    };
+
+    let catalog = ctx.satellite_service.get_catalog(id).await?;
+
+    return Ok(Json(SatelliteResponse::new(satellite, catalog)).into_response());
+}
+
+#[utoipa::path(
+    delete,
+    path = "/satellite/{id}",
+    params(
+        ("id" = i32, Path, description = "Satellite id")
+    ),
+    responses(
+        (status = 200),
+        (status = 404),
+        (status = 409)
+    )
+)]
+async fn delete(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+) -> Result<impl IntoResponse, AppError> {
+    return match ctx.satellite_service.delete(id).await {
+        Ok(true) => Ok(StatusCode::OK.into_response()),
+        Ok(false) => Err(AppError::NotFound(format!(
+            "satellite with id {} not found",
+            id
+        ))),
+        Err(error) => Err(satellite_error(error)),
+    };
+}
+
+#[utoipa::path(
+    get,
+    path = PATH_POSITION,
+    params(GetPositionRequest),
+    responses(
+        (status = 200, body=PositionResponse),
+        (status = 404),
+        (status = 422)
+    )
+)]
+async fn get_position(
+    ctx: State<Arc<AppContext>>,
+    request: Query<GetPositionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let time = request.get_time().unwrap_or_else(Utc::now);
+
+    let position = match ctx
+        .propagation_service
+        .get_position(request.get_id(), time)
+        .await
+        .map_err(propagation_error)?
+    {
+        Some(position) => position,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "satellite with id {} not found",
+                request.get_id()
+            )));
+        }
+    };
+
+    return Ok(Json(PositionResponse::new(request.get_id(), &position)).into_response());
+}
+
+#[utoipa::path(
+    get,
+    path = PATH_GROUND_TRACK,
+    params(GetGroundTrackRequest),
+    responses(
+        (status = 200, body=GroundTrackResponse),
+        (status = 400),
+        (status = 404),
+        (status = 422)
+    )
+)]
+async fn get_ground_track(
+    ctx: State<Arc<AppContext>>,
+    request: Query<GetGroundTrackRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let step = request.get_step().unwrap_or(DEFAULT_GROUND_TRACK_STEP);
+    let window = (*request.get_to() - *request.get_from()).num_seconds();
+
+    if step <= 0 || window <= 0 || window / step > MAX_GROUND_TRACK_POINTS {
+        return Err(AppError::BadRequest(format!(
+            "expected from < to, positive step and at most {} points",
+            MAX_GROUND_TRACK_POINTS
+        )));
+    }
+
+    let lines = match ctx
+        .propagation_service
+        .get_ground_track(
+            request.get_id(),
+            *request.get_from(),
+            *request.get_to(),
+            Duration::seconds(step),
+        )
+        .await
+        .map_err(propagation_error)?
+    {
+        Some(lines) => lines,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "satellite with id {} not found",
+                request.get_id()
+            )));
+        }
+    };
+
+    return Ok(Json(GroundTrackResponse::new(request.get_id(), &lines)).into_response());
+}
+
+#[utoipa::path(
+    get,
+    path = PATH_TRACKING,
+    params(GetTrackingRequest),
+    responses(
+        (status = 200, body=[TrackingResponse], content_type = "application/json"),
+        (status = 200, body=String, content_type = "text/csv"),
+        (status = 400),
+        (status = 404),
+        (status = 422)
+    )
+)]
+async fn get_tracking(
+    ctx: State<Arc<AppContext>>,
+    request: Query<GetTrackingRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let step = request.get_step().unwrap_or(DEFAULT_TRACKING_STEP);
+    let window = (*request.get_to() - *request.get_from()).num_seconds();
+
+    if !request.is_valid() || step <= 0 || window <= 0 || window / step > MAX_TRACKING_POINTS {
+        return Err(AppError::BadRequest(format!(
+            "expected latitude in [-90; 90], longitude in [-180; 180], positive frequency, from < to, positive step and at most {} points",
+            MAX_TRACKING_POINTS
+        )));
+    }
+
+    let observer = Geodetic {
+        latitude: request.get_latitude(),
+        longitude: request.get_longitude(),
+        altitude: request.get_altitude().unwrap_or(0.0),
+    };
+
+    let points = match ctx
+        .propagation_service
+        .get_tracking(
+            request.get_id(),
+            observer,
+            request.get_frequency(),
+            *request.get_from(),
+            *request.get_to(),
+            Duration::seconds(step),
+        )
+        .await
+        .map_err(propagation_error)?
+    {
+        Some(points) => points,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "satellite with id {} not found",
+                request.get_id()
+            )));
+        }
+    };
+
+    let rows = points
+        .iter()
+        .map(|it| TrackingResponse::new(it, request.get_frequency()))
+        .collect::<Vec<_>>();
+
+    return match request.get_format().unwrap_or_default() {
+        TrackingFormat::Json => Ok(Json(rows).into_response()),
+        TrackingFormat::Csv => {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_str("text/csv")?);
+            headers.insert(
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&format!(
+                    "attachment; filename=\"tracking_{}.csv\"",
+                    request.get_id()
+                ))?,
+            );
+
+            Ok((headers, TrackingResponse::to_csv(&rows)).into_response())
+        }
+    };
+}
+
+#[utoipa::path(
+    get,
+    path = "/satellite/{id}/history",
+    params(
+        ("id" = i32, Path, description = "Satellite id"),
+        GetHistoryRequest
+    ),
+    responses(
+        (status = 200, body=[ElementSetResponse]),
+        (status = 404)
+    )
+)]
+async fn get_history(
+    ctx: State<Arc<AppContext>>,
+    Path(id): Path<Id>,
+    request: Query<GetHistoryRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let history = match ctx
+        .element_set_service
+        .get_history(id, *request.get_from(), *request.get_to())
+        .await?
+    {
+        Some(history) => history,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "satellite with id {} not found",
+                id
+            )));
+        }
+    };
+
+    return Ok(Json(
+        history
+            .into_iter()
+            .map(ElementSetResponse::from)
+            .collect::<Vec<_>>(),
+    )
+    .into_response());
+}
+
 pub fn create_router(ctx: Arc<AppContext>) -> Router {
     return Router::new()
         .route(PATH_ALL, axum::routing::get(get_all))
+        .route(PATH_ADD, axum::routing::post(add))
+        .route(PATH_ID, axum::routing::patch(update).delete(delete))
+        .route(PATH_POSITION, axum::routing::get(get_position))
+        .route(PATH_GROUND_TRACK, axum::routing::get(get_ground_track))
+        .route(PATH_TRACKING, axum::routing::get(get_tracking))
+        .route(PATH_HISTORY, axum::routing::get(get_history))
         .with_state(ctx);
 }
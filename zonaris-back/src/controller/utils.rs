@@ -1,20 +1,71 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
+    Json,
 };
+use log::error;
+
+use crate::dto::problem::ProblemDetails;
+use crate::persistence::repository::RepositoryError;
 
 // thx: https://github.com/tokio-rs/axum/blob/main/examples/anyhow-error-response/src/main.rs
 // https://docs.rs/axum/latest/axum/response/index.html
 
-pub struct AppError(anyhow::Error);
+/// Failure of a handler, answered with a problem details body. Errors converted with `?` are
+/// classified by the `RepositoryError` in their chain, the rest are internal.
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    NotFound(String),
+    /// The request is well-formed but can't be answered, e.g. a time the orbit can't be
+    /// propagated to
+    Unprocessable(String),
+    Conflict(String),
+    Unavailable(String),
+    Internal(anyhow::Error),
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        return match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+    }
+}
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
+        let status = self.status();
+        let detail = match self {
+            AppError::BadRequest(detail)
+            | AppError::NotFound(detail)
+            | AppError::Unprocessable(detail)
+            | AppError::Conflict(detail)
+            | AppError::Unavailable(detail) => detail,
+            // the cause is logged instead of being shown to the client
+            AppError::Internal(error) => {
+                error!("request failed: {:#}", error);
+                String::from("Something went wrong")
+            }
+        };
+
+        let problem = ProblemDetails::new(
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default(),
+            &detail,
+        );
+        let mut response = (status, Json(problem)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+
+        return response;
     }
 }
 
@@ -23,6 +74,22 @@ where
     E: Into<anyhow::Error>,
 {
     fn from(err: E) -> Self {
-        Self(err.into())
+        let err = err.into();
+        let Some(repository_error) = err
+            .chain()
+            .find_map(|it| it.downcast_ref::<RepositoryError>())
+        else {
+            return AppError::Internal(err);
+        };
+
+        let detail = repository_error.to_string();
+        return match repository_error {
+            RepositoryError::NotFound(_) => AppError::NotFound(detail),
+            RepositoryError::Conflict(_)
+            | RepositoryError::ForeignKey(_)
+            | RepositoryError::Stale(_) => AppError::Conflict(detail),
+            RepositoryError::Unavailable(_) => AppError::Unavailable(detail),
+            RepositoryError::Other(_) => AppError::Internal(err),
+        };
     }
 }
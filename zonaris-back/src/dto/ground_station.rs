@@ -0,0 +1,103 @@
+use crate::persistence::repository::HasId;
+use crate::service::ground_station::{Pass, PassEvent};
+use crate::{
+    mapper,
+    persistence::{model::ground_station::GroundStation, repository::Id},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use table_macro::Property;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Serialize, ToSchema)]
+pub struct GroundStationResponse {
+    #[schema(value_type = i32)]
+    id: Id,
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    min_elevation: f64,
+}
+
+mapper!(GroundStation, GroundStationResponse, {
+    get_name -> name,
+    get_latitude -> latitude,
+    get_longitude -> longitude,
+    get_altitude -> altitude,
+    get_min_elevation -> min_elevation,
+});
+
+/// Location in EPSG:4326 degrees, altitude in kilometers and minimum elevation in degrees
+#[derive(Deserialize, ToSchema, Property)]
+pub struct CreateGroundStationRequest {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    min_elevation: f64,
+}
+
+impl CreateGroundStationRequest {
+    pub fn is_valid(&self) -> bool {
+        return !self.name.is_empty()
+            && (-90.0..=90.0).contains(&self.latitude)
+            && (-180.0..=180.0).contains(&self.longitude)
+            && (-90.0..=90.0).contains(&self.min_elevation);
+    }
+}
+
+impl From<CreateGroundStationRequest> for GroundStation {
+    fn from(request: CreateGroundStationRequest) -> Self {
+        return GroundStation::new(
+            &request.name,
+            request.latitude,
+            request.longitude,
+            request.altitude,
+            request.min_elevation,
+        );
+    }
+}
+
+#[derive(Deserialize, IntoParams, Property)]
+pub struct GetPassesRequest {
+    satellite_id: Id,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PassEventResponse {
+    time: DateTime<Utc>,
+    azimuth: f64,
+    elevation: f64,
+}
+
+impl From<PassEvent> for PassEventResponse {
+    fn from(event: PassEvent) -> Self {
+        return Self {
+            time: event.time,
+            azimuth: event.azimuth,
+            elevation: event.elevation,
+        };
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PassResponse {
+    aos: PassEventResponse,
+    tca: PassEventResponse,
+    los: PassEventResponse,
+    max_elevation: f64,
+}
+
+impl From<Pass> for PassResponse {
+    fn from(pass: Pass) -> Self {
+        return Self {
+            aos: PassEventResponse::from(pass.aos),
+            tca: PassEventResponse::from(pass.tca),
+            los: PassEventResponse::from(pass.los),
+            max_elevation: pass.tca.elevation,
+        };
+    }
+}
@@ -0,0 +1,70 @@
+use crate::persistence::repository::HasId;
+use crate::{
+    mapper,
+    persistence::{
+        model::{instrument::Instrument, satellite_instrument::SatelliteInstrument},
+        repository::Id,
+    },
+};
+use serde::{Deserialize, Serialize};
+use table_macro::Property;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct InstrumentResponse {
+    #[schema(value_type = i32)]
+    id: Id,
+    name: String,
+}
+
+mapper!(Instrument, InstrumentResponse, {
+    get_name -> name,
+});
+
+/// Used both for creation and renaming
+#[derive(Deserialize, ToSchema, Property)]
+pub struct InstrumentRequest {
+    name: String,
+}
+
+impl InstrumentRequest {
+    pub fn is_valid(&self) -> bool {
+        return !self.name.trim().is_empty();
+    }
+}
+
+impl From<InstrumentRequest> for Instrument {
+    fn from(request: InstrumentRequest) -> Self {
+        return Instrument::new(&request.name);
+    }
+}
+
+/// Instrument carried by a satellite
+#[derive(Serialize, ToSchema)]
+pub struct SatelliteInstrumentResponse {
+    /// Id of the link, instrument data refers to it
+    #[schema(value_type = i32)]
+    id: Id,
+    #[schema(value_type = i32)]
+    satellite_id: Id,
+    #[schema(value_type = i32)]
+    instrument_id: Id,
+    name: String,
+}
+
+impl SatelliteInstrumentResponse {
+    pub fn new(link: SatelliteInstrument, instrument: Instrument) -> Self {
+        return Self {
+            id: link.get_id().expect("id should be presented"),
+            satellite_id: *link.get_satellite_id(),
+            instrument_id: *link.get_instrument_id(),
+            name: instrument.get_name().clone(),
+        };
+    }
+}
+
+#[derive(Deserialize, ToSchema, Property)]
+pub struct LinkInstrumentRequest {
+    #[schema(value_type = i32)]
+    instrument_id: Id,
+}
@@ -1,2 +1,5 @@
+pub mod ground_station;
+pub mod instrument;
 pub mod instrument_data;
+pub mod problem;
 pub mod satellite;
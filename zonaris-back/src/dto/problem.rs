@@ -0,0 +1,24 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Error body of the API (RFC 9457), served as application/problem+json
+#[derive(Serialize, ToSchema)]
+pub struct ProblemDetails {
+    /// Always about:blank, the status explains the problem
+    #[serde(rename = "type")]
+    problem_type: String,
+    title: String,
+    status: u16,
+    detail: String,
+}
+
+impl ProblemDetails {
+    pub fn new(status: u16, title: &str, detail: &str) -> Self {
+        return Self {
+            problem_type: String::from("about:blank"),
+            title: String::from(title),
+            status,
+            detail: String::from(detail),
+        };
+    }
+}
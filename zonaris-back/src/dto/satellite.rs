@@ -1,23 +1,352 @@
 use crate::{
-    mapper,
-    persistence::{model::satellite::Satellite, repository::Id},
+    persistence::{
+        model::{satellite::Satellite, satellite_catalog::SatelliteCatalog},
+        repository::Id,
+    },
+    service::propagation::{SatellitePosition, TrackPoint, TrackingPoint},
+    utils::element_set::ElementSet,
 };
 
 use crate::persistence::repository::HasId;
-use serde::Serialize;
-use utoipa::ToSchema;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use table_macro::Property;
+use utoipa::{IntoParams, ToSchema};
 
 #[derive(Serialize, ToSchema)]
 pub struct SatelliteResponse {
     #[schema(value_type = i32)]
     id: Id,
     name: String,
-    tle1: String,
-    tle2: String,
+    catnr: Option<i64>,
+    /// Absent for element sets which were not sourced from TLE
+    tle1: Option<String>,
+    tle2: Option<String>,
+    /// Absent until the SATCAT record of the satellite is fetched
+    catalog: Option<SatelliteCatalogResponse>,
 }
 
-mapper!(Satellite, SatelliteResponse, {
-    get_name -> name,
-    get_tle1 -> tle1,
-    get_tle2 -> tle2,
-});
+impl SatelliteResponse {
+    pub fn new(satellite: Satellite, catalog: Option<SatelliteCatalog>) -> Self {
+        return Self {
+            id: satellite.get_id().expect("id should be presented"),
+            name: satellite.get_name().clone(),
+            catnr: satellite.get_catnr(),
+            tle1: satellite.get_tle1().clone(),
+            tle2: satellite.get_tle2().clone(),
+            catalog: catalog.map(SatelliteCatalogResponse::from),
+        };
+    }
+}
+
+/// SATCAT metadata, empty strings stand for values which are not provided
+#[derive(Serialize, ToSchema)]
+pub struct SatelliteCatalogResponse {
+    /// yyyy-nnnppp
+    international_designator: String,
+    owner: String,
+    launch_date: Option<NaiveDate>,
+    launch_site: String,
+    /// PAY, R/B, DEB or UNK
+    object_type: String,
+    /// Radar cross section, m^2
+    rcs: Option<f64>,
+    /// SATCAT operational status code, e.g. + (operational), - (nonoperational), D (decayed)
+    operational_status: String,
+    decay_date: Option<NaiveDate>,
+}
+
+impl From<SatelliteCatalog> for SatelliteCatalogResponse {
+    fn from(catalog: SatelliteCatalog) -> Self {
+        return Self {
+            international_designator: catalog.get_international_designator().clone(),
+            owner: catalog.get_owner().clone(),
+            launch_date: *catalog.get_launch_date(),
+            launch_site: catalog.get_launch_site().clone(),
+            object_type: catalog.get_object_type().clone(),
+            rcs: catalog.get_rcs(),
+            operational_status: catalog.get_operational_status().clone(),
+            decay_date: *catalog.get_decay_date(),
+        };
+    }
+}
+
+/// Either a catalog number or an international designator of a single object
+#[derive(Deserialize, ToSchema, Property)]
+pub struct CreateSatelliteRequest {
+    catnr: Option<u32>,
+    /// yyyy-nnnppp, the piece may be omitted if the launch placed a single object
+    international_designator: Option<String>,
+    /// Name of the element set if omitted
+    name: Option<String>,
+}
+
+impl CreateSatelliteRequest {
+    pub fn is_valid(&self) -> bool {
+        let query_valid = match (&self.catnr, &self.international_designator) {
+            (Some(catnr), None) => *catnr > 0,
+            (None, Some(international_designator)) => {
+                is_international_designator(international_designator)
+            }
+            _ => false,
+        };
+
+        return query_valid && self.name.as_ref().is_none_or(|it| !it.trim().is_empty());
+    }
+}
+
+/// yyyy-nnn followed by 0 to 3 piece letters
+fn is_international_designator(value: &str) -> bool {
+    let bytes = value.as_bytes();
+
+    return (8..=11).contains(&bytes.len())
+        && bytes[..4].iter().all(u8::is_ascii_digit)
+        && bytes[4] == b'-'
+        && bytes[5..8].iter().all(u8::is_ascii_digit)
+        && bytes[8..].iter().all(u8::is_ascii_uppercase);
+}
+
+/// New name and/or new element set as both lines of a TLE
+#[derive(Deserialize, ToSchema, Property)]
+pub struct UpdateSatelliteRequest {
+    name: Option<String>,
+    tle1: Option<String>,
+    tle2: Option<String>,
+}
+
+impl UpdateSatelliteRequest {
+    pub fn is_valid(&self) -> bool {
+        let tle_valid = self.tle1.is_some() == self.tle2.is_some();
+        let name_valid = self.name.as_ref().is_none_or(|it| !it.trim().is_empty());
+
+        return tle_valid && name_valid && (self.name.is_some() || self.tle1.is_some());
+    }
+}
+
+#[derive(Deserialize, IntoParams, Property)]
+pub struct GetPositionRequest {
+    id: Id,
+    /// UTC instant to propagate to, current time if omitted
+    time: Option<DateTime<Utc>>,
+}
+
+/// Sub-satellite point in EPSG:4326 (degrees) and altitude above the WGS-84 ellipsoid (kilometers)
+#[derive(Serialize, ToSchema)]
+pub struct PositionResponse {
+    #[schema(value_type = i32)]
+    id: Id,
+    time: DateTime<Utc>,
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+}
+
+impl PositionResponse {
+    pub fn new(id: Id, position: &SatellitePosition) -> Self {
+        return Self {
+            id,
+            time: position.time,
+            latitude: position.geodetic.latitude,
+            longitude: position.geodetic.longitude,
+            altitude: position.geodetic.altitude,
+        };
+    }
+}
+
+#[derive(Deserialize, IntoParams, Property)]
+pub struct GetGroundTrackRequest {
+    id: Id,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    /// Sampling step in seconds, 60 if omitted
+    step: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GroundTrackGeometry {
+    #[serde(rename = "type")]
+    kind: String,
+    /// [longitude, latitude] pairs, one line string per antimeridian crossing
+    #[schema(value_type = Vec<Vec<Vec<f64>>>)]
+    coordinates: Vec<Vec<[f64; 2]>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GroundTrackProperties {
+    #[schema(value_type = i32)]
+    id: Id,
+    /// Timestamp of every vertex, same layout as `coordinates`
+    times: Vec<Vec<DateTime<Utc>>>,
+}
+
+/// GeoJSON Feature with MultiLineString geometry
+#[derive(Serialize, ToSchema)]
+pub struct GroundTrackResponse {
+    #[serde(rename = "type")]
+    kind: String,
+    geometry: GroundTrackGeometry,
+    properties: GroundTrackProperties,
+}
+
+impl GroundTrackResponse {
+    pub fn new(id: Id, lines: &[Vec<TrackPoint>]) -> Self {
+        return Self {
+            kind: String::from("Feature"),
+            geometry: GroundTrackGeometry {
+                kind: String::from("MultiLineString"),
+                coordinates: lines
+                    .iter()
+                    .map(|line| {
+                        line.iter()
+                            .map(|point| [point.longitude, point.latitude])
+                            .collect()
+                    })
+                    .collect(),
+            },
+            properties: GroundTrackProperties {
+                id,
+                times: lines
+                    .iter()
+                    .map(|line| line.iter().map(|point| point.time).collect())
+                    .collect(),
+            },
+        };
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackingFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Observer location in EPSG:4326 degrees and altitude above the WGS-84 ellipsoid in kilometers
+#[derive(Deserialize, IntoParams, Property)]
+pub struct GetTrackingRequest {
+    id: Id,
+    latitude: f64,
+    longitude: f64,
+    /// 0 if omitted
+    altitude: Option<f64>,
+    /// Downlink frequency in Hz
+    frequency: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    /// Sampling step in seconds, 1 if omitted
+    step: Option<i64>,
+    /// json if omitted
+    format: Option<TrackingFormat>,
+}
+
+impl GetTrackingRequest {
+    pub fn is_valid(&self) -> bool {
+        return (-90.0..=90.0).contains(&self.latitude)
+            && (-180.0..=180.0).contains(&self.longitude)
+            && self.frequency > 0.0;
+    }
+}
+
+/// Azimuth and elevation in degrees, range in km, range rate in km/s, Doppler shift and received frequency in Hz
+#[derive(Serialize, ToSchema)]
+pub struct TrackingResponse {
+    time: DateTime<Utc>,
+    azimuth: f64,
+    elevation: f64,
+    range: f64,
+    range_rate: f64,
+    doppler_shift: f64,
+    frequency: f64,
+}
+
+impl TrackingResponse {
+    pub fn new(point: &TrackingPoint, frequency: f64) -> Self {
+        return Self {
+            time: point.time,
+            azimuth: point.look_angles.azimuth,
+            elevation: point.look_angles.elevation,
+            range: point.look_angles.range,
+            range_rate: point.look_angles.range_rate,
+            doppler_shift: point.doppler_shift,
+            frequency: frequency + point.doppler_shift,
+        };
+    }
+
+    pub fn to_csv(rows: &[TrackingResponse]) -> String {
+        let mut csv =
+            String::from("time,azimuth,elevation,range,range_rate,doppler_shift,frequency\n");
+
+        for row in rows {
+            csv.push_str(&format!(
+                "{},{:.4},{:.4},{:.4},{:.6},{:.2},{:.2}\n",
+                row.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+                row.azimuth,
+                row.elevation,
+                row.range,
+                row.range_rate,
+                row.doppler_shift,
+                row.frequency,
+            ));
+        }
+
+        return csv;
+    }
+}
+
+#[derive(Deserialize, IntoParams, Property)]
+pub struct GetHistoryRequest {
+    /// Earliest epoch, unbounded if omitted
+    from: Option<DateTime<Utc>>,
+    /// Latest epoch, unbounded if omitted
+    to: Option<DateTime<Utc>>,
+}
+
+/// Mean elements: angles in degrees, mean motion in revolutions per day
+#[derive(Serialize, ToSchema)]
+pub struct ElementSetResponse {
+    epoch: DateTime<Utc>,
+    catnr: u32,
+    inclination: f64,
+    right_ascension: f64,
+    eccentricity: f64,
+    argument_of_perigee: f64,
+    mean_anomaly: f64,
+    mean_motion: f64,
+    mean_motion_dot: f64,
+    mean_motion_ddot: f64,
+    bstar: f64,
+    element_set_number: u32,
+    revolution_number: u32,
+    /// Absent for element sets which were not sourced from TLE
+    tle1: Option<String>,
+    tle2: Option<String>,
+}
+
+impl From<ElementSet> for ElementSetResponse {
+    fn from(element_set: ElementSet) -> Self {
+        let omm = element_set.to_omm();
+        let (tle1, tle2) = match &element_set {
+            ElementSet::TLE(tle) => (Some(tle.get_tle1().clone()), Some(tle.get_tle2().clone())),
+            ElementSet::OMM(_) => (None, None),
+        };
+
+        return Self {
+            epoch: *omm.get_epoch(),
+            catnr: omm.get_catnr(),
+            inclination: omm.get_inclination(),
+            right_ascension: omm.get_right_ascension(),
+            eccentricity: omm.get_eccentricity(),
+            argument_of_perigee: omm.get_argument_of_perigee(),
+            mean_anomaly: omm.get_mean_anomaly(),
+            mean_motion: omm.get_mean_motion(),
+            mean_motion_dot: omm.get_mean_motion_dot(),
+            mean_motion_ddot: omm.get_mean_motion_ddot(),
+            bstar: omm.get_bstar(),
+            element_set_number: omm.get_element_set_number(),
+            revolution_number: omm.get_revolution_number(),
+            tle1,
+            tle2,
+        };
+    }
+}
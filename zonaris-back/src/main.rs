@@ -1,265 +1,549 @@
+// every function returns explicitly
+#![allow(clippy::needless_return)]
+
+pub mod cli;
+pub mod config;
 pub mod controller;
 pub mod dto;
 pub mod persistence;
 pub mod routes;
 pub mod service;
+pub mod standin;
 pub mod utils;
 
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use anyhow::anyhow;
 use anyhow::Result;
+use chrono::Utc;
+use clap::Parser;
+use cli::{Cli, Command, MigrateCommand};
+use config::{Config, GpProvider};
 use dotenv::dotenv;
-use itertools::Itertools;
+use log::{error, info};
+use persistence::journal::Journals;
+use persistence::model::ground_station::GroundStation;
 use persistence::model::instrument::Instrument;
 use persistence::model::instrument_data::InstrumentData;
 use persistence::model::oceancolor::OceanColorMapping;
 use persistence::model::satellite::Satellite;
+use persistence::model::satellite_catalog::SatelliteCatalog;
+use persistence::model::satellite_element_set::SatelliteElementSet;
 use persistence::model::satellite_instrument::SatelliteInstrument;
-use service::celestrak::CelestrakServiceDefault;
+use service::celestrak::{CelestrakServiceDefault, CelestrakServiceFile};
+use service::element_set::ElementSetServiceDefault;
+use service::ground_station::GroundStationServiceDefault;
+use service::instrument::InstrumentServiceDefault;
 use service::instrument_data::InstrumentDataServiceDefault;
 use service::job::Job;
 use service::oceancolor::OceanColorServiceDefault;
+use service::propagation::PropagationServiceDefault;
+use service::satcat::{SatcatJob, SatcatServiceDefault};
 use service::satellite::SatelliteServiceDefault;
+use service::seed::{Seed, Seeder};
+use service::snapshot::SnapshotJob;
+use service::spacetrack::CelestrakServiceSpaceTrack;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use tokio_cron_scheduler::JobScheduler;
+use utils::geophysical_data::GeophysicalData;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 #[cfg(feature = "cors")]
-use axum::http::Method;
+use axum::http::{HeaderValue, Method};
+#[cfg(feature = "cors")]
+use std::str::FromStr;
 
 #[cfg(feature = "cors")]
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
-#[cfg(not(feature = "postgres"))]
-use persistence::create_inmemory_repository;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use log::warn;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use persistence::schema::{Dialect, SchemaDrift};
 
-#[cfg(feature = "postgres")]
-use tokio::sync::Mutex;
-
-use crate::service::celestrak::CelestrakJob;
+use crate::service::celestrak::{CelestrakJob, Query};
 use crate::service::oceancolor::OceanColorJob;
 #[cfg(feature = "postgres")]
-use persistence::postgres::{create_postgres_repository, migration::migrate};
-
-async fn get_satellite_by_catnr(
-    celestrak_service: &service::CelestrakService,
-    catnr: u32,
-) -> Result<Satellite> {
-    return Ok(Satellite::try_from(
-        celestrak_service
-            .gp_query(service::celestrak::Query::CATNR(catnr))
-            .await?
-            .into_iter()
-            .exactly_one()?,
-    )?);
+use persistence::postgres::{create_postgres_repository, migration, schema, Pool};
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+use persistence::sqlite::{create_sqlite_repository, migration, schema, Database};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    env_logger::init();
+
+    let command = Cli::parse().command();
+
+    // rendering a local file needs no configuration
+    if let Command::Render { file, var, out } = &command {
+        let file = netcdf::open(file)?;
+        GeophysicalData::load_netcdf(&file, var)?
+            .generate_image()
+            .save(out)?;
+        info!("{} rendered to {}", var, out.display());
+        return Ok(());
+    }
+
+    let config = Config::load(matches!(command, Command::Serve { .. }))?;
+
+    match command {
+        Command::Serve {
+            skip_migrate,
+            skip_seed,
+        } => {
+            if !skip_migrate {
+                run_migrations(&config, MigrateCommand::Up).await?;
+            }
+            serve(&config, !skip_seed).await?;
+        }
+        Command::Migrate { action } => {
+            run_migrations(&config, action.unwrap_or(MigrateCommand::Up)).await?
+        }
+        Command::Seed { file } => {
+            let ctx = create_context(&config).await?;
+            match file {
+                Some(file) => apply_seed(&ctx, &file).await?,
+                None => apply_seed(&ctx, config.seed_file().0).await?,
+            }
+        }
+        Command::FetchTle { catnr } => {
+            let ctx = create_context(&config).await?;
+            let (id, created) = ctx.satellite_service.fetch(catnr).await?;
+            if created {
+                info!("satellite {} created with id {}", catnr, id);
+            } else {
+                info!("elements of satellite {} (id {}) updated", catnr, id);
+            }
+        }
+        Command::IngestOceancolor { from, to } => {
+            config.validate_oceancolor()?;
+            let ctx = create_context(&config).await?;
+            let to = to.unwrap_or_else(Utc::now);
+            let count = create_oceancolor_job(&config, &ctx)
+                .ingest(from.naive_utc(), to.naive_utc())
+                .await?;
+            info!("{} granules ingested", count);
+        }
+        Command::PrintConfig => print!("{}", config.redacted().to_toml()),
+        Command::Render { .. } => unreachable!(),
+    }
+
+    return Ok(());
 }
 
-async fn add_test_data(
-    celestrak_service: service::CelestrakService,
-    satellite_repository: persistence::Repository<Satellite>,
-    instrument_repository: persistence::Repository<Instrument>,
-    satellite_instrument_repository: persistence::Repository<SatelliteInstrument>,
-    oceancolor_mapping_repository: persistence::Repository<OceanColorMapping>,
-) -> Result<()> {
-    let (terra, aqua, s3a) = {
-        let terra = get_satellite_by_catnr(&celestrak_service, 25994).await?;
-        let aqua = get_satellite_by_catnr(&celestrak_service, 27424).await?;
-        let s3a = get_satellite_by_catnr(&celestrak_service, 41335).await?;
-
-        let mut lock = satellite_repository.write().await;
-
-        // TODO: delete unwrap
-        (
-            lock.add(terra).await?.unwrap(),
-            lock.add(aqua).await?.unwrap(),
-            lock.add(s3a).await?.unwrap(),
-        )
-    };
+async fn serve(config: &Config, seed: bool) -> Result<()> {
+    // stand-in upstream APIs, e.g. server.standin_ip = "127.0.0.1:3002" with the upstream URLs
+    // set to http://127.0.0.1:3002 to run the jobs offline
+    if let Some(standin_ip) = &config.server.standin_ip {
+        let listener = tokio::net::TcpListener::bind(standin_ip.parse::<SocketAddr>()?).await?;
 
-    let (modis, olci) = {
-        let mut lock = instrument_repository.write().await;
+        tokio::spawn(async move {
+            if let Err(e) = standin::serve(listener).await {
+                error!("stand-in error: {}", e);
+            }
+        });
+    }
 
-        // TODO: delete unwrap
-        (
-            lock.add(Instrument::new("MODIS")).await?.unwrap(),
-            lock.add(Instrument::new("OLCI")).await?.unwrap(),
+    let ctx = create_context(config).await?;
+
+    // seed initial data, seed.toml is skipped if it doesn't exist and no file is configured
+    let (seed_path, seed_required) = config.seed_file();
+    if seed && (seed_required || seed_path.exists()) {
+        apply_seed(&ctx, seed_path).await?;
+    }
+
+    // setup job scheduler
+    let jobs = &config.jobs;
+    if jobs.celestrak.enabled {
+        // GROUP and SPECIAL catalogs are fetched as a whole
+        let catalogs = jobs
+            .celestrak
+            .groups
+            .iter()
+            .cloned()
+            .map(Query::GROUP)
+            .chain(jobs.celestrak.specials.iter().cloned().map(Query::SPECIAL))
+            .collect::<Vec<_>>();
+
+        let celestrak_job = CelestrakJob::new(
+            ctx.celestrak_service.clone(),
+            ctx.satellite_service.clone(),
+            ctx.satellite_repository.clone(),
+            catalogs,
+        )
+        .create_job(std::time::Duration::from_secs(jobs.celestrak.interval))?;
+        ctx.job_scheduler.add(celestrak_job).await?;
+    }
+
+    if jobs.satcat.enabled {
+        let satcat_job = SatcatJob::new(
+            ctx.satcat_service.clone(),
+            ctx.satellite_repository.clone(),
+            ctx.satellite_catalog_repository.clone(),
         )
+        .create_job(std::time::Duration::from_secs(jobs.satcat.interval))?;
+        ctx.job_scheduler.add(satcat_job).await?;
+    }
+
+    if jobs.oceancolor.enabled {
+        let ocean_color_job = create_oceancolor_job(config, &ctx)
+            .create_job(std::time::Duration::from_secs(jobs.oceancolor.interval))?;
+        ctx.job_scheduler.add(ocean_color_job).await?;
+    }
+
+    if jobs.snapshot.enabled && config.storage.data_dir.is_some() {
+        let snapshot_job = SnapshotJob::new(ctx.journals.clone())
+            .create_job(std::time::Duration::from_secs(jobs.snapshot.interval))?;
+        ctx.job_scheduler.add(snapshot_job).await?;
+    }
+
+    ctx.job_scheduler.start().await?;
+
+    // startup application
+    let journals = ctx.journals.clone();
+    let app = routes::create_router(Arc::new(ctx))
+        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()));
+
+    #[cfg(feature = "cors")]
+    let app = {
+        let cors = &config.server.cors;
+        let methods = cors
+            .methods
+            .iter()
+            .map(|it| Method::from_str(it))
+            .collect::<Result<Vec<_>, _>>()?;
+        let origin = if cors.origins.is_empty() {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                cors.origins
+                    .iter()
+                    .map(|it| HeaderValue::from_str(it))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        };
+
+        app.layer(CorsLayer::new().allow_methods(methods).allow_origin(origin))
     };
 
-    let (terra_modis, aqua_modis, s3a_olci) = {
-        let mut lock = satellite_instrument_repository.write().await;
+    let addr = config.server.ip.parse::<SocketAddr>()?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
 
-        // TODO: delete unwrap
-        (
-            lock.add(SatelliteInstrument::new(terra, modis))
-                .await?
-                .unwrap(),
-            lock.add(SatelliteInstrument::new(aqua, modis))
-                .await?
-                .unwrap(),
-            lock.add(SatelliteInstrument::new(s3a, olci))
-                .await?
-                .unwrap(),
-        )
+    // the write logs are replayed on the next start otherwise
+    journals.snapshot()?;
+
+    return Ok(());
+}
+
+/// Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            error!("can't listen for ctrl+c: {}", error);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                error!("can't listen for SIGTERM: {}", error);
+                std::future::pending::<()>().await;
+            }
+        }
     };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    {
-        let mut lock = oceancolor_mapping_repository.write().await;
-        lock.add(OceanColorMapping::new(terra_modis, 8, 1102))
-            .await?;
-        lock.add(OceanColorMapping::new(aqua_modis, 7, 1062))
-            .await?;
-        lock.add(OceanColorMapping::new(s3a_olci, 29, 1267)).await?;
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("shutting down");
+}
+
+#[cfg(feature = "postgres")]
+async fn run_migrations(config: &Config, action: MigrateCommand) -> Result<()> {
+    let pool = Pool::connect(&config.database).await?;
+
+    match action {
+        MigrateCommand::Up => {
+            let applied = migration::migrate(pool.clone()).await?;
+            info!("database migrated, {} migrations applied", applied.len());
+            // the handwritten migrations can diverge from the models
+            for drift in schema::check(pool).await? {
+                warn!("schema drift: {}", drift);
+            }
+        }
+        MigrateCommand::Down { to } => {
+            let reverted = migration::revert(pool, to).await?;
+            info!("database reverted, {} migrations reverted", reverted.len());
+        }
+        MigrateCommand::Status => {
+            for status in migration::status(pool).await? {
+                println!("{:>4} {:<24} {}", status.version, status.name, status.state);
+            }
+        }
+        MigrateCommand::Check => report_drift(schema::check(pool).await?)?,
+        MigrateCommand::Schema => print_schema(Dialect::Postgres),
     }
 
     return Ok(());
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    dotenv().ok();
-    env_logger::init();
+#[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+async fn run_migrations(config: &Config, action: MigrateCommand) -> Result<()> {
+    let database = Database::open(&config.database.path)?;
+
+    match action {
+        MigrateCommand::Up => {
+            let applied = migration::migrate(database.clone()).await?;
+            info!("database migrated, {} migrations applied", applied.len());
+            // the handwritten migrations can diverge from the models
+            for drift in schema::check(database).await? {
+                warn!("schema drift: {}", drift);
+            }
+        }
+        MigrateCommand::Down { to } => {
+            let reverted = migration::revert(database, to).await?;
+            info!("database reverted, {} migrations reverted", reverted.len());
+        }
+        MigrateCommand::Status => {
+            for status in migration::status(database).await? {
+                println!("{:>4} {:<24} {}", status.version, status.name, status.state);
+            }
+        }
+        MigrateCommand::Check => report_drift(schema::check(database).await?)?,
+        MigrateCommand::Schema => print_schema(Dialect::Sqlite),
+    }
 
-    // fetch environment variables
-    #[cfg(feature = "postgres")]
-    let database_connection_url = std::env::var("DATABASE_URL")?;
+    return Ok(());
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn report_drift(drifts: Vec<SchemaDrift>) -> Result<()> {
+    for drift in &drifts {
+        println!("{}", drift);
+    }
+    if !drifts.is_empty() {
+        return Err(anyhow!(
+            "schema of the database differs from the models, {} differences",
+            drifts.len()
+        ));
+    }
+
+    info!("schema of the database matches the models");
+    return Ok(());
+}
 
-    let server_ip = std::env::var("SERVER_IP")?;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn print_schema(dialect: Dialect) {
+    for table in persistence::model::tables() {
+        println!("{}", table.create_table(dialect));
+    }
+}
 
-    let oceancolor_authorization = std::env::var("OCEANCOLOR_AUTHORIZATION")?;
-    let oceancolor_job_timestep = std::env::var("OCEANCOLOR_JOB_TIMESTEP")?.parse::<u64>()?;
-    let oceancolor_job_notfound = std::env::var("OCEANCOLOR_JOB_NOTFOUND")?.parse::<i64>()?;
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+async fn run_migrations(_config: &Config, _action: MigrateCommand) -> Result<()> {
+    info!("in-memory storage, nothing to migrate");
 
-    let celestrak_job_timestep = std::env::var("CELESTRAK_JOB_TIMESTEP")?.parse::<u64>()?;
+    return Ok(());
+}
+
+async fn apply_seed(ctx: &routes::AppContext, path: &Path) -> Result<()> {
+    Seeder::new(
+        ctx.satellite_service.clone(),
+        ctx.instrument_service.clone(),
+        ctx.oceancolor_mapping_repository.clone(),
+    )
+    .apply(&Seed::from_file(path)?)
+    .await?;
 
+    return Ok(());
+}
+
+fn create_oceancolor_job(config: &Config, ctx: &routes::AppContext) -> OceanColorJob {
+    return OceanColorJob::new(
+        chrono::Duration::seconds(config.jobs.oceancolor.not_found),
+        config.storage.images_dir.clone(),
+        ctx.oceancolor_mapping_repository.clone(),
+        ctx.instrument_data_service.clone(),
+        ctx.oceancolor_service.clone(),
+    );
+}
+
+/// Repositories and services, the job scheduler is created without jobs and isn't started
+async fn create_context(config: &Config) -> Result<routes::AppContext> {
     // config connection with database
     #[cfg(feature = "postgres")]
-    let client = {
-        let (client, connection) =
-            tokio_postgres::connect(&database_connection_url, tokio_postgres::NoTls).await?;
-
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                eprintln!("connection error: {}", e);
-            }
-        });
+    let pool = Pool::connect(&config.database).await?;
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    let database = Database::open(&config.database.path)?;
 
-        let client = Arc::new(Mutex::new(client));
-        // TODO: migrate should be done by different tools in deploy time (k8s init containers)
-        migrate(client.clone()).await?;
+    // construct repositories, the in-memory ones are persisted if storage.data_dir is set
+    #[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+    let journals = Journals::new(config.storage.data_dir.as_deref())?;
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    let journals = Journals::default();
 
-        client
+    #[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+    let (
+        satellite_repository,
+        instrument_repository,
+        satellite_instrument_repository,
+        instrument_data_repository,
+        oceancolor_mapping_repository,
+        ground_station_repository,
+        satellite_element_set_repository,
+        satellite_catalog_repository,
+    ) = {
+        (
+            journals.repository::<Satellite>()?,
+            journals.repository::<Instrument>()?,
+            journals.repository::<SatelliteInstrument>()?,
+            journals.repository::<InstrumentData>()?,
+            journals.repository::<OceanColorMapping>()?,
+            journals.repository::<GroundStation>()?,
+            journals.repository::<SatelliteElementSet>()?,
+            journals.repository::<SatelliteCatalog>()?,
+        )
     };
 
-    // construct repositories
-    #[cfg(not(feature = "postgres"))]
+    #[cfg(feature = "postgres")]
     let (
         satellite_repository,
         instrument_repository,
         satellite_instrument_repository,
         instrument_data_repository,
         oceancolor_mapping_repository,
+        ground_station_repository,
+        satellite_element_set_repository,
+        satellite_catalog_repository,
     ) = {
         (
-            create_inmemory_repository::<Satellite>(),
-            create_inmemory_repository::<Instrument>(),
-            create_inmemory_repository::<SatelliteInstrument>(),
-            create_inmemory_repository::<InstrumentData>(),
-            create_inmemory_repository::<OceanColorMapping>(),
+            create_postgres_repository::<Satellite>(pool.clone()),
+            create_postgres_repository::<Instrument>(pool.clone()),
+            create_postgres_repository::<SatelliteInstrument>(pool.clone()),
+            create_postgres_repository::<InstrumentData>(pool.clone()),
+            create_postgres_repository::<OceanColorMapping>(pool.clone()),
+            create_postgres_repository::<GroundStation>(pool.clone()),
+            create_postgres_repository::<SatelliteElementSet>(pool.clone()),
+            create_postgres_repository::<SatelliteCatalog>(pool.clone()),
         )
     };
 
-    #[cfg(feature = "postgres")]
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
     let (
         satellite_repository,
         instrument_repository,
         satellite_instrument_repository,
         instrument_data_repository,
         oceancolor_mapping_repository,
+        ground_station_repository,
+        satellite_element_set_repository,
+        satellite_catalog_repository,
     ) = {
         (
-            create_postgres_repository::<Satellite>(client.clone(), "satellite"),
-            create_postgres_repository::<Instrument>(client.clone(), "instrument"),
-            create_postgres_repository::<SatelliteInstrument>(
-                client.clone(),
-                "satellite_instrument",
-            ),
-            create_postgres_repository::<InstrumentData>(client.clone(), "instrument_data"),
-            create_postgres_repository::<OceanColorMapping>(client.clone(), "ocean_color_mapping"),
+            create_sqlite_repository::<Satellite>(database.clone()),
+            create_sqlite_repository::<Instrument>(database.clone()),
+            create_sqlite_repository::<SatelliteInstrument>(database.clone()),
+            create_sqlite_repository::<InstrumentData>(database.clone()),
+            create_sqlite_repository::<OceanColorMapping>(database.clone()),
+            create_sqlite_repository::<GroundStation>(database.clone()),
+            create_sqlite_repository::<SatelliteElementSet>(database.clone()),
+            create_sqlite_repository::<SatelliteCatalog>(database.clone()),
         )
     };
 
     // construct services
-    let satellite_service = Arc::new(SatelliteServiceDefault::new(satellite_repository.clone()));
-
-    let celestrak_service = Arc::new(CelestrakServiceDefault::new());
+    let upstream = &config.upstream;
+    let satcat_service = Arc::new(SatcatServiceDefault::new(&upstream.celestrak_url));
+
+    let celestrak_service: service::CelestrakService = match config.gp_provider() {
+        GpProvider::Celestrak => Arc::new(CelestrakServiceDefault::new(&upstream.celestrak_url)),
+        GpProvider::File => Arc::new(CelestrakServiceFile::new(
+            config.storage.celestrak_files.clone(),
+        )),
+        GpProvider::SpaceTrack => Arc::new(CelestrakServiceSpaceTrack::new(
+            &upstream.spacetrack_url,
+            upstream.spacetrack_identity.as_deref().unwrap_or_default(),
+            upstream.spacetrack_password.as_deref().unwrap_or_default(),
+        )?),
+    };
 
     let instrument_data_service = Arc::new(InstrumentDataServiceDefault::new(
         satellite_instrument_repository.clone(),
         instrument_data_repository.clone(),
     ));
 
-    let ocean_color_service = Arc::new(OceanColorServiceDefault::new(&oceancolor_authorization));
+    let ocean_color_service = Arc::new(OceanColorServiceDefault::new(
+        upstream
+            .oceancolor_authorization
+            .as_deref()
+            .unwrap_or_default(),
+        &upstream.oceancolor_url,
+    ));
 
-    // add test data
-    add_test_data(
-        celestrak_service.clone(),
+    let element_set_service = Arc::new(ElementSetServiceDefault::new(
         satellite_repository.clone(),
-        instrument_repository.clone(),
+        satellite_element_set_repository.clone(),
+    ));
+
+    let satellite_service = Arc::new(SatelliteServiceDefault::new(
+        satellite_repository.clone(),
+        satellite_catalog_repository.clone(),
         satellite_instrument_repository.clone(),
-        oceancolor_mapping_repository.clone(),
-    )
-    .await?;
+        celestrak_service.clone(),
+        element_set_service.clone(),
+    ));
 
-    // setup job scheduler
-    let job_scheduler = JobScheduler::new().await?;
+    let propagation_service = Arc::new(PropagationServiceDefault::new(element_set_service.clone()));
 
-    let celestrak_job = CelestrakJob::new(celestrak_service.clone(), satellite_repository.clone())
-        .create_job(std::time::Duration::from_secs(celestrak_job_timestep))?;
-    job_scheduler.add(celestrak_job).await?;
+    let ground_station_service = Arc::new(GroundStationServiceDefault::new(
+        ground_station_repository.clone(),
+        element_set_service.clone(),
+    ));
 
-    let ocean_color_job = OceanColorJob::new(
-        chrono::Duration::seconds(oceancolor_job_notfound),
+    let instrument_service = Arc::new(InstrumentServiceDefault::new(
+        instrument_repository.clone(),
+        satellite_repository.clone(),
+        satellite_instrument_repository.clone(),
+        instrument_data_repository.clone(),
         oceancolor_mapping_repository.clone(),
-        instrument_data_service.clone(),
-        ocean_color_service.clone(),
-    )
-    .create_job(std::time::Duration::from_secs(oceancolor_job_timestep))?;
-    job_scheduler.add(ocean_color_job).await?;
-
-    job_scheduler.start().await?;
+    ));
 
-    // startup application
-    let app_context = Arc::new(routes::AppContext {
+    return Ok(routes::AppContext {
         satellite_service,
         celestrak_service,
         oceancolor_service: ocean_color_service,
+        element_set_service,
+        propagation_service,
+        ground_station_service,
+        satcat_service,
+        instrument_service,
         satellite_repository,
         instrument_repository,
         satellite_instrument_repository,
         instrument_data_service,
         instrument_data_repository,
         oceancolor_mapping_repository,
-        job_scheduler,
+        ground_station_repository,
+        satellite_element_set_repository,
+        satellite_catalog_repository,
+        job_scheduler: JobScheduler::new().await?,
+        journals,
     });
-
-    let mut app = routes::create_router(app_context)
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()));
-
-    #[cfg(feature = "cors")]
-    {
-        app = app.layer(
-            CorsLayer::new()
-                .allow_methods([Method::GET, Method::POST])
-                .allow_origin(Any),
-        );
-    }
-
-    let addr = server_ip.parse::<SocketAddr>()?;
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-
-    Ok(())
 }
 
 #[derive(OpenApi)]
@@ -268,11 +552,48 @@ async fn main() -> Result<()> {
         crate::controller::instrument_data::get_by_satellite_id,
         crate::controller::instrument_data::get_asset,
         crate::controller::satellite::get_all,
+        crate::controller::satellite::add,
+        crate::controller::satellite::update,
+        crate::controller::satellite::delete,
+        crate::controller::satellite::get_position,
+        crate::controller::satellite::get_ground_track,
+        crate::controller::satellite::get_tracking,
+        crate::controller::satellite::get_history,
+        crate::controller::ground_station::get_all,
+        crate::controller::ground_station::add,
+        crate::controller::ground_station::get_passes,
+        crate::controller::instrument::get_all,
+        crate::controller::instrument::add,
+        crate::controller::instrument::get_by_id,
+        crate::controller::instrument::update,
+        crate::controller::instrument::delete_by_id,
+        crate::controller::instrument::get_by_satellite_id,
+        crate::controller::instrument::link,
+        crate::controller::instrument::unlink,
     ),
     components(schemas(
         crate::persistence::repository::Id,
         crate::dto::instrument_data::InstrumentDataResponse,
-        crate::dto::satellite::SatelliteResponse
+        crate::dto::problem::ProblemDetails,
+        crate::dto::satellite::SatelliteResponse,
+        crate::dto::satellite::SatelliteCatalogResponse,
+        crate::dto::satellite::CreateSatelliteRequest,
+        crate::dto::satellite::UpdateSatelliteRequest,
+        crate::dto::satellite::PositionResponse,
+        crate::dto::satellite::GroundTrackResponse,
+        crate::dto::satellite::GroundTrackGeometry,
+        crate::dto::satellite::GroundTrackProperties,
+        crate::dto::satellite::TrackingFormat,
+        crate::dto::satellite::TrackingResponse,
+        crate::dto::satellite::ElementSetResponse,
+        crate::dto::ground_station::GroundStationResponse,
+        crate::dto::ground_station::CreateGroundStationRequest,
+        crate::dto::ground_station::PassResponse,
+        crate::dto::ground_station::PassEventResponse,
+        crate::dto::instrument::InstrumentResponse,
+        crate::dto::instrument::InstrumentRequest,
+        crate::dto::instrument::SatelliteInstrumentResponse,
+        crate::dto::instrument::LinkInstrumentRequest
     ))
 )]
 struct ApiDoc;
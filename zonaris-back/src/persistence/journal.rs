@@ -0,0 +1,269 @@
+// Durability of the in-memory repositories. Every repository has a snapshot of its records,
+// <name>.snapshot.json, and a write log of the changes made since, <name>.log, one JSON entry per
+// line. The entry is appended before the record is changed in memory; a snapshot replaces the log
+// once the records are written to it. On startup the snapshot is loaded, the log is replayed on top
+// of it and a fresh snapshot is written.
+//
+// The log isn't synced on every write: the changes survive a crash of the process but not of the
+// machine, the snapshots are synced before they replace the log.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::repository::{HasId, Id, InMemoryRepository};
+use super::schema::Table;
+
+#[derive(Serialize, Deserialize)]
+pub enum Entry<T> {
+    /// The record is added or updated
+    Put(T),
+    Delete(Id),
+}
+
+/// Appends the changes of an in-memory repository to its log
+pub trait WriteLog<T>: Send + Sync {
+    fn append(&self, entry: &Entry<T>) -> Result<()>;
+}
+
+pub trait Snapshot: Send + Sync {
+    /// Writes the records to the snapshot and empties the log
+    fn snapshot(&self) -> Result<()>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile<T> {
+    next_id: Id,
+    records: Vec<T>,
+}
+
+struct Log {
+    file: File,
+    /// Greater than every id ever put, deleted ids aren't reused after a restart
+    next_id: Id,
+}
+
+pub struct Journal<T> {
+    name: String,
+    directory: PathBuf,
+    /// Records of the repository
+    data: Arc<Mutex<HashMap<Id, T>>>,
+    log: Mutex<Log>,
+}
+
+impl<T> Journal<T>
+where
+    T: HasId + Clone + Send + Sync + 'static,
+    T: Serialize + DeserializeOwned,
+{
+    /// Loads the records of `name` from `directory`, the repository shares them with the journal
+    pub fn open(directory: &Path, name: &str) -> Result<(Arc<Journal<T>>, InMemoryRepository<T>)> {
+        let snapshot_path = directory.join(format!("{}.snapshot.json", name));
+        let log_path = directory.join(format!("{}.log", name));
+
+        let mut records = HashMap::new();
+        let mut next_id = Id::default();
+        if snapshot_path.exists() {
+            let text = std::fs::read_to_string(&snapshot_path)?;
+            let snapshot = serde_json::from_str::<SnapshotFile<T>>(&text)
+                .with_context(|| format!("{} is corrupted", snapshot_path.display()))?;
+            next_id = snapshot.next_id;
+            for record in snapshot.records {
+                let id = record.get_id().ok_or(anyhow!("record doesn't have id"))?;
+                records.insert(id, record);
+            }
+        }
+
+        let replayed = replay(&log_path, &mut records, &mut next_id)?;
+
+        info!(
+            "{}: {} records loaded, {} log entries replayed",
+            name,
+            records.len(),
+            replayed
+        );
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)?;
+        let data = Arc::new(Mutex::new(records));
+        let journal = Arc::new(Journal {
+            name: String::from(name),
+            directory: PathBuf::from(directory),
+            data: data.clone(),
+            log: Mutex::new(Log { file, next_id }),
+        });
+
+        let repository = InMemoryRepository::with_journal(data, next_id, journal.clone());
+        return Ok((journal, repository));
+    }
+}
+
+/// Applies the entries of the log to `records` and `next_id`, returns their number. A torn last
+/// entry of a write interrupted by a crash is cut off.
+fn replay<T>(path: &Path, records: &mut HashMap<Id, T>, next_id: &mut Id) -> Result<usize>
+where
+    T: HasId + DeserializeOwned,
+{
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let text = std::fs::read(path)?;
+    let mut count = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive(|it| *it == b'\n') {
+        if !line.ends_with(b"\n") {
+            warn!(
+                "{}: torn entry at byte {} is dropped",
+                path.display(),
+                offset
+            );
+            OpenOptions::new()
+                .write(true)
+                .open(path)?
+                .set_len(offset as u64)?;
+            break;
+        }
+
+        match serde_json::from_slice::<Entry<T>>(line) {
+            // entries of a snapshot which wasn't followed by emptying the log are applied again
+            Ok(Entry::Put(record)) => {
+                let id = record.get_id().ok_or(anyhow!("record doesn't have id"))?;
+                *next_id = (*next_id).max(id.next());
+                records.insert(id, record);
+            }
+            Ok(Entry::Delete(id)) => {
+                records.remove(&id);
+            }
+            Err(error) => {
+                return Err(anyhow!(
+                    "{} is corrupted at byte {}: {}",
+                    path.display(),
+                    offset,
+                    error
+                ));
+            }
+        }
+
+        offset += line.len();
+        count += 1;
+    }
+
+    return Ok(count);
+}
+
+impl<T> WriteLog<T> for Journal<T>
+where
+    T: HasId + Send + Sync,
+    T: Serialize,
+{
+    fn append(&self, entry: &Entry<T>) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut log = self.log.lock().unwrap();
+        // written at once, a crash leaves at most the last entry torn
+        log.file.write_all(&line)?;
+        if let Entry::Put(record) = entry {
+            if let Some(id) = record.get_id() {
+                log.next_id = log.next_id.max(id.next());
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+impl<T> Snapshot for Journal<T>
+where
+    T: HasId + Clone + Send + Sync,
+    T: Serialize,
+{
+    fn snapshot(&self) -> Result<()> {
+        // the repository appends with its records locked, so no entry is lost between writing
+        // the snapshot and emptying the log
+        let data = self.data.lock().unwrap();
+        let log = self.log.lock().unwrap();
+
+        let mut records = data.values().collect::<Vec<_>>();
+        records.sort_by_key(|it| it.get_id());
+        let snapshot = SnapshotFile {
+            next_id: log.next_id,
+            records,
+        };
+
+        let path = self.directory.join(format!("{}.snapshot.json", self.name));
+        let temporary = self
+            .directory
+            .join(format!("{}.snapshot.json.tmp", self.name));
+        let mut file = File::create(&temporary)?;
+        file.write_all(&serde_json::to_vec(&snapshot)?)?;
+        file.sync_all()?;
+        // the previous snapshot is replaced at once, a crash leaves one of them
+        std::fs::rename(&temporary, &path)?;
+
+        log.file.set_len(0)?;
+
+        info!("{}: snapshot of {} records written", self.name, data.len());
+        return Ok(());
+    }
+}
+
+/// Journals of the in-memory repositories, empty if they aren't persisted
+#[derive(Clone, Default)]
+pub struct Journals {
+    directory: Option<PathBuf>,
+    journals: Arc<Mutex<Vec<Arc<dyn Snapshot>>>>,
+}
+
+impl Journals {
+    /// Journals kept in `directory`, it is created if it doesn't exist. Nothing is persisted if
+    /// it is None.
+    pub fn new(directory: Option<&Path>) -> Result<Journals> {
+        if let Some(directory) = directory {
+            std::fs::create_dir_all(directory)
+                .with_context(|| format!("can't create {}", directory.display()))?;
+        }
+
+        return Ok(Journals {
+            directory: directory.map(PathBuf::from),
+            journals: Arc::default(),
+        });
+    }
+
+    /// Repository of the records of `T`, loaded from the files named after its table
+    pub fn repository<T>(&self) -> Result<super::InMemoryRepository<T>>
+    where
+        T: HasId + Clone + Send + Sync + 'static,
+        T: Serialize + DeserializeOwned + Table,
+    {
+        let Some(directory) = &self.directory else {
+            return Ok(super::create_inmemory_repository::<T>());
+        };
+
+        let (journal, repository) = Journal::<T>::open(directory, T::TABLE)?;
+        // the log is replayed on every start until it is emptied
+        journal.snapshot()?;
+        self.journals.lock().unwrap().push(journal);
+
+        return Ok(Arc::new(tokio::sync::RwLock::new(repository)));
+    }
+
+    /// Snapshots every journal, stops on the first failure
+    pub fn snapshot(&self) -> Result<()> {
+        let journals = self.journals.lock().unwrap().clone();
+        for journal in journals {
+            journal.snapshot()?;
+        }
+
+        return Ok(());
+    }
+}
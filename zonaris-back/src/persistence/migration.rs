@@ -0,0 +1,134 @@
+// Numbered migrations of the database schema, shared by the SQL backends. Every migration has an
+// up and a down script in migrations/, the applied ones are recorded in schema_migration with the
+// checksum of their up script. Nothing is run if the recorded history has drifted from the
+// migrations of the build: an applied migration was changed or removed, or a later one was
+// applied before it.
+
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// SHA-256 of the up script
+    pub fn checksum(&self) -> String {
+        return format!("{:x}", Sha256::digest(self.up.as_bytes()));
+    }
+}
+
+/// Row of schema_migration
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppliedMigration {
+    pub version: i32,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MigrationError {
+    #[error("migration {0} was changed after it was applied")]
+    Changed(i32),
+    #[error("migration {0} is applied but unknown to this build")]
+    Unknown(i32),
+    #[error("migration {0} is pending but a later one is applied")]
+    Skipped(i32),
+    #[error("no migration {0} to revert to")]
+    UnknownTarget(i32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MigrationState {
+    Pending,
+    Applied(DateTime<Utc>),
+    Changed(DateTime<Utc>),
+    Unknown(DateTime<Utc>),
+}
+
+impl Display for MigrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            MigrationState::Pending => write!(f, "pending"),
+            MigrationState::Applied(at) => write!(f, "applied at {}", at),
+            MigrationState::Changed(at) => write!(f, "applied at {}, changed since", at),
+            MigrationState::Unknown(at) => write!(f, "applied at {}, unknown to this build", at),
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i32,
+    pub name: String,
+    pub state: MigrationState,
+}
+
+/// Every known and applied migration ordered by version
+pub fn status_of(migrations: &[Migration], applied: &[AppliedMigration]) -> Vec<MigrationStatus> {
+    let mut result = migrations
+        .iter()
+        .map(|migration| {
+            let state = match applied.iter().find(|it| it.version == migration.version) {
+                Some(it) if it.checksum == migration.checksum() => {
+                    MigrationState::Applied(it.applied_at)
+                }
+                Some(it) => MigrationState::Changed(it.applied_at),
+                None => MigrationState::Pending,
+            };
+
+            MigrationStatus {
+                version: migration.version,
+                name: String::from(migration.name),
+                state,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for it in applied {
+        if !migrations
+            .iter()
+            .any(|migration| migration.version == it.version)
+        {
+            result.push(MigrationStatus {
+                version: it.version,
+                name: it.name.clone(),
+                state: MigrationState::Unknown(it.applied_at),
+            });
+        }
+    }
+
+    result.sort_by_key(|it| it.version);
+    return result;
+}
+
+/// Fails on the first migration whose applied state differs from the build
+pub fn check_drift(
+    migrations: &[Migration],
+    applied: &[AppliedMigration],
+) -> Result<(), MigrationError> {
+    let mut pending = None;
+    for status in status_of(migrations, applied) {
+        match status.state {
+            MigrationState::Pending => {
+                pending.get_or_insert(status.version);
+            }
+            MigrationState::Changed(_) => return Err(MigrationError::Changed(status.version)),
+            MigrationState::Unknown(_) => return Err(MigrationError::Unknown(status.version)),
+            MigrationState::Applied(_) => {
+                if let Some(version) = pending {
+                    return Err(MigrationError::Skipped(version));
+                }
+            }
+        }
+    }
+
+    return Ok(());
+}
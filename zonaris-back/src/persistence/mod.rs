@@ -4,10 +4,18 @@ use tokio::sync::RwLock;
 
 use self::repository::HasId;
 
+pub mod journal;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub mod migration;
 pub mod model;
 #[cfg(feature = "postgres")]
 pub mod postgres;
+pub mod query;
 pub mod repository;
+pub mod schema;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod unit_of_work;
 
 pub type Repository<T> = Arc<RwLock<dyn self::repository::Repository<T> + Send + Sync>>;
 pub type InMemoryRepository<T> = Arc<RwLock<self::repository::InMemoryRepository<T>>>;
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use table_macro::{Property, Table};
+
+use crate::{persistence::repository::Id, utils::geodesy::Geodetic};
+
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "ground_station")]
+pub struct GroundStation {
+    #[id]
+    #[none]
+    id: Option<Id>,
+    name: String,
+
+    // WGS-84 position: degrees and kilometers above the ellipsoid
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+
+    min_elevation: f64, // degrees
+}
+
+impl GroundStation {
+    pub fn new(
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+        min_elevation: f64,
+    ) -> Self {
+        return Self {
+            id: None,
+            name: String::from(name),
+            latitude,
+            longitude,
+            altitude,
+            min_elevation,
+        };
+    }
+
+    pub fn get_location(&self) -> Geodetic {
+        return Geodetic {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            altitude: self.altitude,
+        };
+    }
+}
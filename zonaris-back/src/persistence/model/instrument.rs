@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
 use table_macro::{Property, Table};
 
 use crate::persistence::repository::Id;
 
-#[derive(Clone, Table, Property)]
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "instrument")]
 pub struct Instrument {
     #[id]
     #[none]
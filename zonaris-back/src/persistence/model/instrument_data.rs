@@ -1,14 +1,17 @@
+use serde::{Deserialize, Serialize};
 use table_macro::{Property, Table};
 
 use crate::persistence::repository::{Id, Reference};
 
 use super::satellite_instrument::SatelliteInstrument;
 
-#[derive(Clone, Table, Property)]
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "instrument_data")]
 pub struct InstrumentData {
     #[id]
     #[none]
     id: Option<Id>,
+    #[references(SatelliteInstrument)]
     satellite_instrument_id: Reference<SatelliteInstrument>,
     path: String,
 }
@@ -1,5 +1,33 @@
+use crate::persistence::schema::{Table, TableSchema};
+
+use self::ground_station::GroundStation;
+use self::instrument::Instrument;
+use self::instrument_data::InstrumentData;
+use self::oceancolor::OceanColorMapping;
+use self::satellite::Satellite;
+use self::satellite_catalog::SatelliteCatalog;
+use self::satellite_element_set::SatelliteElementSet;
+use self::satellite_instrument::SatelliteInstrument;
+
+pub mod ground_station;
 pub mod instrument;
 pub mod instrument_data;
 pub mod oceancolor;
 pub mod satellite;
+pub mod satellite_catalog;
+pub mod satellite_element_set;
 pub mod satellite_instrument;
+
+/// Schemas of the tables of the models, every table comes after the ones it references
+pub fn tables() -> Vec<TableSchema> {
+    return vec![
+        Satellite::schema(),
+        Instrument::schema(),
+        SatelliteInstrument::schema(),
+        OceanColorMapping::schema(),
+        InstrumentData::schema(),
+        GroundStation::schema(),
+        SatelliteElementSet::schema(),
+        SatelliteCatalog::schema(),
+    ];
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use table_macro::{Property, Table};
 
 use crate::persistence::repository::{Id, Reference};
@@ -8,13 +9,17 @@ use super::satellite_instrument::SatelliteInstrument;
 pub type SensorId = i32;
 pub type DataId = i32;
 
-#[derive(Clone, Table, Property)]
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "ocean_color_mapping")]
 pub struct OceanColorMapping {
     #[id]
     #[none]
     id: Option<Id>,
+    #[references(SatelliteInstrument)]
     satellite_instrument_id: Reference<SatelliteInstrument>,
+    #[column(sql_type = "INTEGER")]
     sensor_id: SensorId,
+    #[column(sql_type = "INTEGER")]
     data_id: DataId,
 }
 
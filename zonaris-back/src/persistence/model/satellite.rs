@@ -1,9 +1,18 @@
-use anyhow::{Error, Result};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use table_macro::{Property, Table};
 
-use crate::{persistence::repository::Id, service::celestrak::TLE};
+use crate::{
+    persistence::repository::Id,
+    utils::{
+        element_set::{ElementSet, StoredElementSet},
+        omm::OMM,
+        tle::{TleError, TLE},
+    },
+};
 
-#[derive(Clone, Table, Property)]
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "satellite")]
 pub struct Satellite {
     #[id]
     #[none]
@@ -12,31 +21,66 @@ pub struct Satellite {
 
     catnr: Option<i64>, // Satellite Catalog Number
 
-    // TODO: make optional and maybe transfer to other table
-    tle1: String,
-    tle2: String,
+    // TODO: maybe transfer to other table
+    // either both lines of a TLE or an OMM (JSON) for elements sourced from OMM
+    tle1: Option<String>,
+    tle2: Option<String>,
+    omm: Option<String>,
 }
 
 impl Satellite {
-    // TODO: make tle optional
-    pub fn new(name: &str, tle1: &str, tle2: &str) -> Result<Self> {
-        return Satellite::try_from(TLE::new(name, tle1, tle2));
+    pub fn new(name: &str, tle1: &str, tle2: &str) -> Result<Self, TleError> {
+        return Ok(Satellite::from(TLE::new(name, tle1, tle2)?));
     }
-}
 
-impl TryFrom<TLE> for Satellite {
-    type Error = Error;
+    /// Replaces the current element set, the previous ones are kept in `SatelliteElementSet`
+    pub fn set_element_set(&mut self, element_set: ElementSet) {
+        self.catnr = Some(element_set.get_catnr().into());
+
+        let stored = StoredElementSet::from(&element_set);
+        self.tle1 = stored.tle1;
+        self.tle2 = stored.tle2;
+        self.omm = stored.omm;
+    }
 
-    fn try_from(tle: TLE) -> Result<Self> {
-        let catnr = tle.get_catnr()?;
-        return Ok(Self {
+    /// None if the satellite has no elements
+    pub fn get_element_set(&self) -> Result<Option<ElementSet>> {
+        return StoredElementSet {
+            tle1: self.tle1.clone(),
+            tle2: self.tle2.clone(),
+            omm: self.omm.clone(),
+        }
+        .decode(&self.name);
+    }
+}
+
+// element sets are validated on parsing, so any of them makes a valid satellite
+impl From<ElementSet> for Satellite {
+    fn from(element_set: ElementSet) -> Self {
+        let mut satellite = Self {
             id: None,
-            name: tle.name,
+            name: element_set.get_name().clone(),
+
+            catnr: None,
 
-            catnr: Some(catnr.try_into()?),
+            tle1: None,
+            tle2: None,
+            omm: None,
+        };
+        satellite.set_element_set(element_set);
+
+        return satellite;
+    }
+}
+
+impl From<TLE> for Satellite {
+    fn from(tle: TLE) -> Self {
+        return Satellite::from(ElementSet::TLE(tle));
+    }
+}
 
-            tle1: tle.tle1,
-            tle2: tle.tle2,
-        });
+impl From<OMM> for Satellite {
+    fn from(omm: OMM) -> Self {
+        return Satellite::from(ElementSet::OMM(omm));
     }
 }
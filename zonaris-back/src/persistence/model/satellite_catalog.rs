@@ -0,0 +1,75 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use table_macro::{Property, Table};
+
+use crate::{
+    persistence::repository::{Id, Reference},
+    utils::satcat::SatcatRecord,
+};
+
+use super::satellite::Satellite;
+
+/// SATCAT metadata of a satellite, one per satellite.
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "satellite_catalog")]
+pub struct SatelliteCatalog {
+    #[id]
+    #[none]
+    id: Option<Id>,
+    #[column(unique)]
+    #[references(Satellite)]
+    satellite_id: Reference<Satellite>,
+
+    international_designator: String,
+    owner: String,
+    launch_date: Option<NaiveDate>,
+    launch_site: String,
+    object_type: String,
+    rcs: Option<f64>,
+    operational_status: String,
+    decay_date: Option<NaiveDate>,
+}
+
+impl SatelliteCatalog {
+    pub fn new(satellite_id: Id, record: &SatcatRecord) -> Self {
+        let mut catalog = Self {
+            id: None,
+            satellite_id: Reference::new(satellite_id),
+
+            international_designator: String::new(),
+            owner: String::new(),
+            launch_date: None,
+            launch_site: String::new(),
+            object_type: String::new(),
+            rcs: None,
+            operational_status: String::new(),
+            decay_date: None,
+        };
+        catalog.set_record(record);
+
+        return catalog;
+    }
+
+    /// false if the record doesn't change anything
+    pub fn set_record(&mut self, record: &SatcatRecord) -> bool {
+        let changed = self.international_designator != *record.get_international_designator()
+            || self.owner != *record.get_owner()
+            || self.launch_date != *record.get_launch_date()
+            || self.launch_site != *record.get_launch_site()
+            || self.object_type != *record.get_object_type()
+            || self.rcs != record.get_rcs()
+            || self.operational_status != *record.get_operational_status()
+            || self.decay_date != *record.get_decay_date();
+
+        self.international_designator = record.get_international_designator().clone();
+        self.owner = record.get_owner().clone();
+        self.launch_date = *record.get_launch_date();
+        self.launch_site = record.get_launch_site().clone();
+        self.object_type = record.get_object_type().clone();
+        self.rcs = record.get_rcs();
+        self.operational_status = record.get_operational_status().clone();
+        self.decay_date = *record.get_decay_date();
+
+        return changed;
+    }
+}
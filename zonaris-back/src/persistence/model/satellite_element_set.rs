@@ -0,0 +1,53 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use table_macro::{Property, Table};
+
+use crate::{
+    persistence::repository::{Id, Reference},
+    utils::element_set::{ElementSet, StoredElementSet},
+};
+
+use super::satellite::Satellite;
+
+/// Element set which was valid for the satellite at some point, one per (satellite, epoch).
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "satellite_element_set", unique(satellite_id, epoch))]
+pub struct SatelliteElementSet {
+    #[id]
+    #[none]
+    id: Option<Id>,
+    #[references(Satellite)]
+    satellite_id: Reference<Satellite>,
+    epoch: DateTime<Utc>,
+
+    // either both lines of a TLE or an OMM (JSON)
+    tle1: Option<String>,
+    tle2: Option<String>,
+    omm: Option<String>,
+}
+
+impl SatelliteElementSet {
+    pub fn new(satellite_id: Id, element_set: &ElementSet) -> Self {
+        let stored = StoredElementSet::from(element_set);
+
+        return Self {
+            id: None,
+            satellite_id: Reference::new(satellite_id),
+            epoch: *element_set.get_epoch(),
+            tle1: stored.tle1,
+            tle2: stored.tle2,
+            omm: stored.omm,
+        };
+    }
+
+    /// None if nothing is stored
+    pub fn get_element_set(&self, name: &str) -> Result<Option<ElementSet>> {
+        return StoredElementSet {
+            tle1: self.tle1.clone(),
+            tle2: self.tle2.clone(),
+            omm: self.omm.clone(),
+        }
+        .decode(name);
+    }
+}
@@ -1,15 +1,19 @@
+use serde::{Deserialize, Serialize};
 use table_macro::{Property, Table};
 
 use crate::persistence::repository::{Id, Reference};
 
 use super::{instrument::Instrument, satellite::Satellite};
 
-#[derive(Clone, Table, Property)]
+#[derive(Clone, Table, Property, Serialize, Deserialize)]
+#[table(name = "satellite_instrument")]
 pub struct SatelliteInstrument {
     #[id]
     #[none]
     id: Option<Id>,
+    #[references(Satellite)]
     satellite_id: Reference<Satellite>,
+    #[references(Instrument)]
     instrument_id: Reference<Instrument>,
 }
 
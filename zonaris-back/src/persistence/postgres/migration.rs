@@ -1,64 +1,159 @@
-// TODO: normal migrations
-// TODO: delete IF NOT EXISTS
+// Migrations of the Postgres schema in migrations/, see `crate::persistence::migration`
 
 use anyhow::Result;
+use log::info;
+use tokio_postgres::GenericClient;
 
-use super::Client;
+use crate::persistence::migration::{
+    check_drift, status_of, AppliedMigration, Migration, MigrationError, MigrationStatus,
+};
 
-pub async fn migrate(client: Client) -> Result<()> {
-    init_db(&client).await?;
+use super::Pool;
 
-    return Ok(());
+/// Key of the advisory lock which serializes concurrent runs, e.g. of several replicas
+const LOCK_KEY: i64 = 0x7a6f6e61726973;
+
+/// Versions start with 1 and have no gaps, applied migrations must never be edited
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    name: "init",
+    up: include_str!("../../../migrations/0001_init.up.sql"),
+    down: include_str!("../../../migrations/0001_init.down.sql"),
+}];
+
+/// Applies the pending migrations in order, each one in its own transaction, returns their
+/// versions
+pub async fn migrate(pool: Pool) -> Result<Vec<i32>> {
+    let mut client = pool.get().await?;
+
+    let mut result = Vec::new();
+    loop {
+        let transaction = client.transaction().await?;
+        let applied = lock(&transaction).await?;
+
+        let next = MIGRATIONS
+            .iter()
+            .find(|migration| !applied.iter().any(|it| it.version == migration.version));
+        let Some(migration) = next else {
+            break;
+        };
+
+        transaction.batch_execute(migration.up).await?;
+        transaction
+            .execute(
+                "INSERT INTO schema_migration (version, name, checksum) VALUES ($1, $2, $3)",
+                &[&migration.version, &migration.name, &migration.checksum()],
+            )
+            .await?;
+        transaction.commit().await?;
+
+        info!("migration {} {} applied", migration.version, migration.name);
+        result.push(migration.version);
+    }
+
+    return Ok(result);
+}
+
+/// Reverts the applied migrations after `target` in reverse order, each one in its own
+/// transaction, returns their versions. Target 0 reverts all of them.
+pub async fn revert(pool: Pool, target: i32) -> Result<Vec<i32>> {
+    if target != 0 && !MIGRATIONS.iter().any(|it| it.version == target) {
+        return Err(MigrationError::UnknownTarget(target).into());
+    }
+
+    let mut client = pool.get().await?;
+
+    let mut result = Vec::new();
+    loop {
+        let transaction = client.transaction().await?;
+        let applied = lock(&transaction).await?;
+
+        let last = applied
+            .iter()
+            .filter(|it| it.version > target)
+            .max_by_key(|it| it.version)
+            .and_then(|last| MIGRATIONS.iter().find(|it| it.version == last.version));
+        let Some(migration) = last else {
+            break;
+        };
+
+        transaction.batch_execute(migration.down).await?;
+        transaction
+            .execute(
+                "DELETE FROM schema_migration WHERE version = $1",
+                &[&migration.version],
+            )
+            .await?;
+        transaction.commit().await?;
+
+        info!(
+            "migration {} {} reverted",
+            migration.version, migration.name
+        );
+        result.push(migration.version);
+    }
+
+    return Ok(result);
+}
+
+pub async fn status(pool: Pool) -> Result<Vec<MigrationStatus>> {
+    let client = pool.get().await?;
+    return Ok(status_of(MIGRATIONS, &load_applied(&**client).await?));
+}
+
+/// Takes the migration lock for the transaction, creates the tracking table and returns the
+/// applied migrations after checking them for drift
+async fn lock(transaction: &tokio_postgres::Transaction<'_>) -> Result<Vec<AppliedMigration>> {
+    transaction
+        .execute("SELECT pg_advisory_xact_lock($1)", &[&LOCK_KEY])
+        .await?;
+
+    if !has_tracking_table(transaction).await? {
+        let statement = "CREATE TABLE schema_migration
+        (
+            version INTEGER PRIMARY KEY,
+            name VARCHAR NOT NULL,
+            checksum VARCHAR NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        );";
+        transaction.execute(statement, &[]).await?;
+    }
+
+    let applied = load_applied(transaction).await?;
+    check_drift(MIGRATIONS, &applied)?;
+
+    return Ok(applied);
+}
+
+/// Empty if the tracking table doesn't exist
+async fn load_applied(client: &impl GenericClient) -> Result<Vec<AppliedMigration>> {
+    if !has_tracking_table(client).await? {
+        return Ok(Vec::new());
+    }
+
+    let rows = client
+        .query(
+            "SELECT version, name, checksum, applied_at FROM schema_migration ORDER BY version",
+            &[],
+        )
+        .await?;
+
+    return rows
+        .into_iter()
+        .map(|row| {
+            Ok(AppliedMigration {
+                version: row.try_get("version")?,
+                name: row.try_get("name")?,
+                checksum: row.try_get("checksum")?,
+                applied_at: row.try_get("applied_at")?,
+            })
+        })
+        .collect();
 }
 
-pub async fn init_db(client: &Client) -> Result<()> {
-    let mut client = client.lock().await;
-    let transaction = client.transaction().await?;
-
-    let statement = "CREATE TABLE IF NOT EXISTS satellite
-    (
-        id SERIAL PRIMARY KEY,
-        name VARCHAR NOT NULL,
-
-        catnr BIGINT NULL DEFAULT NULL,
-
-        tle1 VARCHAR NOT NULL,
-        tle2 VARCHAR NOT NULL
-    );";
-    transaction.execute(statement, &[]).await?;
-
-    let statement = "CREATE TABLE IF NOT EXISTS instrument
-    (
-        id SERIAL PRIMARY KEY,
-        name VARCHAR NOT NULL
-    );";
-    transaction.execute(statement, &[]).await?;
-
-    let statement = "CREATE TABLE IF NOT EXISTS satellite_instrument
-    (
-        id SERIAL PRIMARY KEY,
-        satellite_id INTEGER NOT NULL REFERENCES satellite,
-        instrument_id INTEGER NOT NULL REFERENCES instrument
-    );";
-    transaction.execute(statement, &[]).await?;
-
-    let statement = "CREATE TABLE IF NOT EXISTS ocean_color_mapping
-    (
-        id SERIAL PRIMARY KEY,
-        satellite_instrument_id INTEGER NOT NULL REFERENCES satellite_instrument,
-        sensor_id INTEGER NOT NULL,
-        data_id INTEGER NOT NULL
-    );";
-    transaction.execute(statement, &[]).await?;
-
-    let statement = "CREATE TABLE IF NOT EXISTS instrument_data
-    (
-        id SERIAL PRIMARY KEY,
-        satellite_instrument_id INTEGER NOT NULL REFERENCES satellite_instrument,
-        path VARCHAR NOT NULL
-    );";
-    transaction.execute(statement, &[]).await?;
-
-    transaction.commit().await?;
-    return Ok(());
+async fn has_tracking_table(client: &impl GenericClient) -> Result<bool> {
+    return Ok(client
+        .query_one("SELECT to_regclass('schema_migration') IS NOT NULL", &[])
+        .await?
+        .try_get(0)?);
 }
@@ -1,33 +1,249 @@
-use std::{ops::Deref, sync::Arc};
+use std::{ops::Deref, str::FromStr, sync::Arc, time::Duration};
 
-use tokio::sync::{Mutex, RwLock};
+use anyhow::Result;
+use deadpool_postgres::{Manager, ManagerConfig, Object, PoolError, RecyclingMethod, Runtime};
+use log::warn;
+use tokio::sync::{OwnedMutexGuard, RwLock};
 use tokio_postgres::{
+    error::SqlState,
     types::{FromSql, IsNull, ToSql},
-    Row,
+    NoTls, Row,
 };
 
+use crate::config::DatabaseConfig;
 use crate::persistence::repository::HasId;
 
 use self::repository::ColumnValuePair;
 
-use super::repository::{Id, Reference};
+use super::query::Value;
+use super::repository::{Id, Reference, RepositoryError};
+use super::schema::Table;
+use super::unit_of_work::{PostgresTransaction, UnitOfWork};
 
 pub mod migration;
 pub mod repository;
+pub mod schema;
 
-pub type Client = Arc<Mutex<tokio_postgres::Client>>;
 pub type PostgresRepository<T> = Arc<RwLock<self::repository::PostgresRepository<T>>>;
 
-pub fn create_postgres_repository<T>(client: Client, table: &str) -> PostgresRepository<T>
+/// Longest delay between two attempts to connect
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Connection pool shared by the repositories, connections are checked when they are taken from
+/// the pool and the broken ones are replaced
+#[derive(Clone)]
+pub struct Pool {
+    pool: deadpool_postgres::Pool,
+    retry_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl Pool {
+    /// Waits for the database to accept the first connection
+    pub async fn connect(config: &DatabaseConfig) -> Result<Pool> {
+        let mut pg_config =
+            tokio_postgres::Config::from_str(config.url.as_deref().unwrap_or_default())?;
+        pg_config.connect_timeout(Duration::from_secs(config.connect_timeout));
+        if config.statement_timeout > 0 {
+            let options = format!(
+                "{} -c statement_timeout={}",
+                pg_config.get_options().unwrap_or_default(),
+                config.statement_timeout * 1000
+            );
+            pg_config.options(options.trim());
+        }
+
+        let manager = Manager::from_config(
+            pg_config,
+            NoTls,
+            ManagerConfig {
+                recycling_method: RecyclingMethod::Verified,
+            },
+        );
+        let pool = deadpool_postgres::Pool::builder(manager)
+            .max_size(config.pool_size)
+            .create_timeout(Some(Duration::from_secs(config.connect_timeout)))
+            .runtime(Runtime::Tokio1)
+            .build()?;
+
+        let pool = Pool {
+            pool,
+            retry_attempts: config.retry_attempts,
+            retry_delay: Duration::from_secs(config.retry_delay),
+        };
+        // the first connection is returned to the pool right away
+        drop(pool.get().await?);
+
+        return Ok(pool);
+    }
+
+    /// Connection from the pool, a new one is established if none is idle. Failed attempts are
+    /// retried with exponential backoff, `Unavailable` once they are exhausted.
+    pub async fn get(&self) -> Result<Object, RepositoryError> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            let error = match self.pool.get().await {
+                Ok(client) => return Ok(client),
+                Err(error @ (PoolError::Backend(_) | PoolError::Timeout(_))) => error,
+                Err(error) => return Err(anyhow::Error::from(error).into()),
+            };
+
+            if attempt == self.retry_attempts {
+                return Err(RepositoryError::Unavailable(error.to_string()));
+            }
+            attempt += 1;
+
+            warn!(
+                "can't connect to the database ({}), retry {} of {} in {:?}",
+                error, attempt, self.retry_attempts, delay
+            );
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RETRY_DELAY);
+        }
+    }
+
+    /// Connection of the unit of work of the task, its transaction is begun on the first use, or
+    /// a connection from the pool outside of a unit of work
+    pub async fn connection(&self) -> Result<Connection, RepositoryError> {
+        let Some(unit) = UnitOfWork::current() else {
+            return Ok(Connection::Pool(self.get().await?));
+        };
+
+        let mut transaction = unit.postgres.clone().lock_owned().await;
+        if transaction.is_none() {
+            let connection = self.get().await?;
+            connection.batch_execute("BEGIN").await?;
+            *transaction = Some(PostgresTransaction {
+                connection,
+                failed: false,
+            });
+        }
+
+        return Ok(Connection::UnitOfWork(transaction));
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum Connection {
+    Pool(Object),
+    /// Held until the statement is done, the statements of a unit of work run one by one
+    UnitOfWork(OwnedMutexGuard<Option<PostgresTransaction>>),
+}
+
+impl Connection {
+    fn client(&self) -> &tokio_postgres::Client {
+        return match self {
+            Connection::Pool(connection) => connection,
+            Connection::UnitOfWork(transaction) => {
+                &transaction
+                    .as_ref()
+                    .expect("transaction is begun")
+                    .connection
+            }
+        };
+    }
+
+    /// Failed statements abort the transaction of the unit of work
+    fn check<R>(&mut self, result: Result<R, tokio_postgres::Error>) -> Result<R, RepositoryError> {
+        if let (Err(_), Connection::UnitOfWork(transaction)) = (&result, self) {
+            if let Some(transaction) = transaction.as_mut() {
+                transaction.failed = true;
+            }
+        }
+
+        return Ok(result?);
+    }
+
+    pub async fn query(
+        &mut self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, RepositoryError> {
+        let result = self.client().query(statement, params).await;
+        return self.check(result);
+    }
+
+    pub async fn query_one(
+        &mut self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, RepositoryError> {
+        let result = self.client().query_one(statement, params).await;
+        return self.check(result);
+    }
+
+    pub async fn query_opt(
+        &mut self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, RepositoryError> {
+        let result = self.client().query_opt(statement, params).await;
+        return self.check(result);
+    }
+
+    pub async fn execute(
+        &mut self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, RepositoryError> {
+        let result = self.client().execute(statement, params).await;
+        return self.check(result);
+    }
+}
+
+impl From<tokio_postgres::Error> for RepositoryError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        let Some(db_error) = error.as_db_error() else {
+            // the connection was lost, errors of the conversion of values are left as they are
+            return if error.is_closed()
+                || std::error::Error::source(&error).is_some_and(|it| it.is::<std::io::Error>())
+            {
+                RepositoryError::Unavailable(error.to_string())
+            } else {
+                RepositoryError::Other(error.into())
+            };
+        };
+
+        let message = String::from(db_error.detail().unwrap_or(db_error.message()));
+        let code = db_error.code();
+        if *code == SqlState::UNIQUE_VIOLATION {
+            return RepositoryError::Conflict(message);
+        }
+        if *code == SqlState::FOREIGN_KEY_VIOLATION {
+            return RepositoryError::ForeignKey(message);
+        }
+        if *code == SqlState::T_R_SERIALIZATION_FAILURE || *code == SqlState::T_R_DEADLOCK_DETECTED
+        {
+            return RepositoryError::Stale(message);
+        }
+        // connection exceptions (08), insufficient resources (53), shutdowns and statement timeouts
+        if code.code().starts_with("08")
+            || code.code().starts_with("53")
+            || *code == SqlState::ADMIN_SHUTDOWN
+            || *code == SqlState::CRASH_SHUTDOWN
+            || *code == SqlState::CANNOT_CONNECT_NOW
+            || *code == SqlState::QUERY_CANCELED
+        {
+            return RepositoryError::Unavailable(message);
+        }
+
+        return RepositoryError::Other(error.into());
+    }
+}
+
+/// Repository of the table of `T`
+pub fn create_postgres_repository<T>(pool: Pool) -> PostgresRepository<T>
 where
     T: HasId,
     T: Clone,
     T: Send + Sync,
     T: TryFrom<Row, Error = anyhow::Error>,
     T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
 {
     return Arc::new(tokio::sync::RwLock::new(
-        self::repository::PostgresRepository::<T>::new(client, table),
+        self::repository::PostgresRepository::<T>::new(pool),
     ));
 }
 
@@ -90,3 +306,44 @@ where
         return self.deref().to_sql_checked(ty, out);
     }
 }
+
+impl ToSql for Value {
+    fn to_sql(
+        &self,
+        ty: &tokio_postgres::types::Type,
+        out: &mut tokio_util::bytes::BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        return match self {
+            Value::Null => Ok(IsNull::Yes),
+            Value::Bool(value) => value.to_sql(ty, out),
+            Value::I32(value) => value.to_sql(ty, out),
+            Value::I64(value) => value.to_sql(ty, out),
+            Value::F64(value) => value.to_sql(ty, out),
+            Value::Text(value) => value.to_sql(ty, out),
+            Value::Timestamp(value) => value.to_sql(ty, out),
+            Value::Date(value) => value.to_sql(ty, out),
+        };
+    }
+
+    fn accepts(_ty: &tokio_postgres::types::Type) -> bool {
+        return true;
+    }
+
+    /// The type is checked against the variant, not the whole enum
+    fn to_sql_checked(
+        &self,
+        ty: &tokio_postgres::types::Type,
+        out: &mut tokio_util::bytes::BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        return match self {
+            Value::Null => Ok(IsNull::Yes),
+            Value::Bool(value) => value.to_sql_checked(ty, out),
+            Value::I32(value) => value.to_sql_checked(ty, out),
+            Value::I64(value) => value.to_sql_checked(ty, out),
+            Value::F64(value) => value.to_sql_checked(ty, out),
+            Value::Text(value) => value.to_sql_checked(ty, out),
+            Value::Timestamp(value) => value.to_sql_checked(ty, out),
+            Value::Date(value) => value.to_sql_checked(ty, out),
+        };
+    }
+}
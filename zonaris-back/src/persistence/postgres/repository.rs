@@ -6,9 +6,11 @@ use itertools::Itertools;
 use log::info;
 use tokio_postgres::Row;
 
-use crate::persistence::repository::{HasId, Id, Repository};
+use crate::persistence::query::{Query, QueryBuilder};
+use crate::persistence::repository::{HasId, Id, Repository, RepositoryError};
+use crate::persistence::schema::Table;
 
-use super::Client;
+use super::Pool;
 
 pub struct PostgresRepository<T>
 where
@@ -17,10 +19,10 @@ where
     T: Send + Sync,
     T: TryFrom<Row, Error = Error>,
     T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
 {
     marker: PhantomData<T>,
-    client: Client,
-    table: String,
+    pool: Pool,
 }
 
 impl<T> PostgresRepository<T>
@@ -30,12 +32,12 @@ where
     T: Send + Sync,
     T: TryFrom<Row, Error = Error>,
     T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
 {
-    pub fn new(client: Client, table: &str) -> Self {
+    pub fn new(pool: Pool) -> Self {
         return Self {
             marker: PhantomData,
-            client,
-            table: String::from(table),
+            pool,
         };
     }
 }
@@ -65,6 +67,14 @@ impl ColumnValuePair {
     }
 }
 
+fn params(builder: &QueryBuilder) -> Vec<&(dyn tokio_postgres::types::ToSql + Sync)> {
+    return builder
+        .params()
+        .iter()
+        .map(|it| it as &(dyn tokio_postgres::types::ToSql + Sync))
+        .collect();
+}
+
 #[async_trait]
 impl<T> Repository<T> for PostgresRepository<T>
 where
@@ -73,15 +83,16 @@ where
     T: Send + Sync,
     T: TryFrom<Row, Error = Error>,
     T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
 {
-    async fn get(&self, id: Id) -> Result<Option<T>> {
-        let statement = format!("SELECT * FROM {} WHERE id = $1 LIMIT 1", self.table);
+    async fn get(&self, id: Id) -> Result<Option<T>, RepositoryError> {
+        let statement = format!("SELECT * FROM {} WHERE id = $1 LIMIT 1", T::TABLE);
         info!("statement: {}", &statement);
 
         let row = self
-            .client
-            .lock()
-            .await
+            .pool
+            .connection()
+            .await?
             .query_opt(&statement, &[&id])
             .await?;
 
@@ -91,7 +102,7 @@ where
         });
     }
 
-    async fn add(&mut self, entity: T) -> Result<Option<Id>> {
+    async fn add(&mut self, entity: T) -> Result<Id, RepositoryError> {
         // TODO: statement can be generated just one
         let column_value_pairs: Vec<ColumnValuePair> = entity.try_into()?;
 
@@ -102,7 +113,9 @@ where
 
         let statement = format!(
             "INSERT INTO {} ({}) VALUES ({}) RETURNING id",
-            self.table, columns, values
+            T::TABLE,
+            columns,
+            values
         );
 
         info!("statement: {}", &statement);
@@ -113,21 +126,32 @@ where
             .collect::<Vec<_>>();
 
         let row = self
-            .client
-            .lock()
-            .await
+            .pool
+            .connection()
+            .await?
             .query_one(&statement, &params)
             .await?;
 
-        return Ok(Some(row.get(0)));
+        return Ok(row.try_get(0)?);
     }
 
-    async fn delete(&mut self, id: Id) -> Result<bool> {
-        let statement = format!("DELETE FROM {} WHERE id = $1", self.table);
-        return Ok(self.client.lock().await.execute(&statement, &[&id]).await? != 0);
+    async fn delete(&mut self, id: Id) -> Result<(), RepositoryError> {
+        let statement = format!("DELETE FROM {} WHERE id = $1", T::TABLE);
+        if self
+            .pool
+            .connection()
+            .await?
+            .execute(&statement, &[&id])
+            .await?
+            == 0
+        {
+            return Err(RepositoryError::NotFound(id));
+        }
+
+        return Ok(());
     }
 
-    async fn update(&mut self, entity: T) -> Result<bool> {
+    async fn update(&mut self, entity: T) -> Result<(), RepositoryError> {
         let id = entity.get_id().ok_or(anyhow!("entity doesn't have id"))?;
 
         // TODO: statement can be generated just one
@@ -140,10 +164,10 @@ where
             .join(", ");
 
         let statement = format!(
-            "UPDATE {} SET {} WHERE id = {}",
-            self.table,
+            "UPDATE {} SET {} WHERE id = ${}",
+            T::TABLE,
             columns,
-            format!("${}", column_value_pairs.len() + 1)
+            column_value_pairs.len() + 1
         );
 
         info!("statement: {}", &statement);
@@ -155,24 +179,63 @@ where
 
         params.push(&id);
 
-        return Ok(self
-            .client
-            .lock()
-            .await
+        if self
+            .pool
+            .connection()
+            .await?
             .execute(&statement, &params)
             .await?
-            != 0);
+            == 0
+        {
+            return Err(RepositoryError::NotFound(id));
+        }
+
+        return Ok(());
+    }
+
+    async fn get_all(&self) -> Result<Vec<T>, RepositoryError> {
+        let statement = format!("SELECT * FROM {}", T::TABLE);
+        info!("statement: {}", &statement);
+
+        let rows = self.pool.connection().await?.query(&statement, &[]).await?;
+
+        return Ok(rows
+            .into_iter()
+            .map(|row| T::try_from(row))
+            .collect::<Result<Vec<_>>>()?);
     }
 
-    async fn get_all(&self) -> Result<Vec<T>> {
-        let statement = format!("SELECT * FROM {}", self.table);
+    async fn query(&self, query: &Query<T>) -> Result<Vec<T>, RepositoryError> {
+        let mut builder = QueryBuilder::default();
+        let statement = builder.select(T::TABLE, query);
         info!("statement: {}", &statement);
 
-        let rows = self.client.lock().await.query(&statement, &[]).await?;
+        let rows = self
+            .pool
+            .connection()
+            .await?
+            .query(&statement, &params(&builder))
+            .await?;
 
         return Ok(rows
             .into_iter()
             .map(|row| T::try_from(row))
             .collect::<Result<Vec<_>>>()?);
     }
+
+    async fn count(&self, query: &Query<T>) -> Result<usize, RepositoryError> {
+        let mut builder = QueryBuilder::default();
+        let statement = builder.count(T::TABLE, query);
+        info!("statement: {}", &statement);
+
+        let count: i64 = self
+            .pool
+            .connection()
+            .await?
+            .query_one(&statement, &params(&builder))
+            .await?
+            .get(0);
+
+        return Ok(usize::try_from(count).map_err(anyhow::Error::from)?);
+    }
 }
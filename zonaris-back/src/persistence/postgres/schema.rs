@@ -0,0 +1,92 @@
+// Schema of the live Postgres tables in the current schema, see `crate::persistence::schema`
+
+use anyhow::Result;
+use tokio_postgres::GenericClient;
+
+use crate::persistence::model::tables;
+use crate::persistence::schema::{Dialect, LiveColumn, LiveIndex, LiveTable, SchemaDrift};
+
+use super::Pool;
+
+/// Differences of the tables of the database from the models
+pub async fn check(pool: Pool) -> Result<Vec<SchemaDrift>> {
+    let client = pool.get().await?;
+
+    let mut result = Vec::new();
+    for table in tables() {
+        let live = load_table(&**client, table.name).await?;
+        result.extend(table.diff(live.as_ref(), Dialect::Postgres));
+    }
+
+    return Ok(result);
+}
+
+/// None if the table doesn't exist
+async fn load_table(client: &impl GenericClient, table: &str) -> Result<Option<LiveTable>> {
+    let columns = client
+        .query(
+            "SELECT column_name::text, data_type::text, is_nullable = 'YES'
+            FROM information_schema.columns
+            WHERE table_schema = current_schema() AND table_name = $1
+            ORDER BY ordinal_position",
+            &[&table],
+        )
+        .await?;
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    let references = client
+        .query(
+            "SELECT a.attname::text, r.relname::text
+            FROM pg_constraint c
+            JOIN pg_class t ON t.oid = c.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            JOIN pg_class r ON r.oid = c.confrelid
+            JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = ANY (c.conkey)
+            WHERE c.contype = 'f' AND n.nspname = current_schema() AND t.relname = $1",
+            &[&table],
+        )
+        .await?;
+
+    // unique keys are enforced by unique indexes
+    let indexes = client
+        .query(
+            "SELECT i.indisunique, array_agg(a.attname::text ORDER BY k.ord)
+            FROM pg_index i
+            JOIN pg_class t ON t.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            CROSS JOIN LATERAL unnest(i.indkey::int2[]) WITH ORDINALITY AS k (attnum, ord)
+            JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = k.attnum
+            WHERE n.nspname = current_schema() AND t.relname = $1 AND NOT i.indisprimary
+            GROUP BY i.indexrelid, i.indisunique",
+            &[&table],
+        )
+        .await?;
+
+    return Ok(Some(LiveTable {
+        columns: columns
+            .iter()
+            .map(|row| {
+                Ok(LiveColumn {
+                    name: row.try_get(0)?,
+                    sql_type: row.try_get(1)?,
+                    nullable: row.try_get(2)?,
+                })
+            })
+            .collect::<Result<_>>()?,
+        references: references
+            .iter()
+            .map(|row| Ok((row.try_get(0)?, row.try_get(1)?)))
+            .collect::<Result<_>>()?,
+        indexes: indexes
+            .iter()
+            .map(|row| {
+                Ok(LiveIndex {
+                    unique: row.try_get(0)?,
+                    columns: row.try_get(1)?,
+                })
+            })
+            .collect::<Result<_>>()?,
+    }));
+}
@@ -1,3 +1,6 @@
+// every function returns explicitly
+#![allow(clippy::needless_return)]
+
 use proc_macro::TokenStream;
 use table::impl_table_macro;
 
@@ -7,7 +10,7 @@ mod property;
 mod table;
 mod utils;
 
-#[proc_macro_derive(Table, attributes(id))]
+#[proc_macro_derive(Table, attributes(id, table, column, references))]
 pub fn table_macro_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
     return impl_table_macro(&ast).into();
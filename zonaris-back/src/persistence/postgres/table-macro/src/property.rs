@@ -64,7 +64,7 @@ fn collect_field_info<'a>(ast: &'a syn::DeriveInput) -> Vec<FieldInfo<'a>> {
                             name,
                             kind,
                             field,
-                            field_ident: &ident,
+                            field_ident: ident,
                         });
                     }
                     None => panic!("not supported"),
@@ -1,27 +1,183 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{self, Ident};
+use syn::{self, Field, Ident, Meta, NestedMeta, Type};
 
-use crate::utils::contains_attribute;
+use crate::utils::{
+    attribute_arguments, contains_attribute, lit_string, path_ident, path_name, split_type,
+    to_snake_case,
+};
 
 const ID_ATTRIBUTE_NAME: &str = "id";
+const TABLE_ATTRIBUTE_NAME: &str = "table";
+const COLUMN_ATTRIBUTE_NAME: &str = "column";
+const REFERENCES_ATTRIBUTE_NAME: &str = "references";
+
+/// `#[table(name = "...", unique(column, ...))]` of the struct
+struct TableInfo {
+    name: String,
+    unique: Vec<Vec<String>>,
+}
+
+fn parse_table_info(ast: &syn::DeriveInput) -> TableInfo {
+    let mut result = TableInfo {
+        name: to_snake_case(&ast.ident.to_string()),
+        unique: Vec::new(),
+    };
+
+    for argument in attribute_arguments(&ast.attrs, TABLE_ATTRIBUTE_NAME) {
+        match argument {
+            NestedMeta::Meta(Meta::NameValue(it)) if it.path.is_ident("name") => {
+                result.name = lit_string(&it.lit);
+            }
+            NestedMeta::Meta(Meta::List(it)) if it.path.is_ident("unique") => {
+                result
+                    .unique
+                    .push(it.nested.iter().map(path_name).collect());
+            }
+            _ => panic!("expected #[table(name = \"...\", unique(column, ...))]"),
+        }
+    }
+
+    return result;
+}
+
+/// `#[column(name = "...", sql_type = "...", unique, index)]` and `#[references(Model)]` of a
+/// field
+struct ColumnInfo {
+    name: String,
+    sql_type: Option<String>,
+    unique: bool,
+    index: bool,
+    references: Option<syn::Path>,
+}
+
+fn parse_column_info(field: &Field, ident: &Ident) -> ColumnInfo {
+    let mut result = ColumnInfo {
+        name: ident.to_string(),
+        sql_type: None,
+        unique: false,
+        index: false,
+        references: None,
+    };
+
+    for argument in attribute_arguments(&field.attrs, COLUMN_ATTRIBUTE_NAME) {
+        match argument {
+            NestedMeta::Meta(Meta::NameValue(it)) if it.path.is_ident("name") => {
+                result.name = lit_string(&it.lit);
+            }
+            NestedMeta::Meta(Meta::NameValue(it)) if it.path.is_ident("sql_type") => {
+                result.sql_type = Some(lit_string(&it.lit));
+            }
+            NestedMeta::Meta(Meta::Path(it)) => match path_ident(&it).as_str() {
+                "unique" => result.unique = true,
+                "index" => result.index = true,
+                _ => panic!("unknown column attribute"),
+            },
+            _ => panic!("expected #[column(name = \"...\", sql_type = \"...\", unique, index)]"),
+        }
+    }
+
+    let references = attribute_arguments(&field.attrs, REFERENCES_ATTRIBUTE_NAME);
+    match references.as_slice() {
+        [] => {}
+        [NestedMeta::Meta(Meta::Path(path))] => result.references = Some(path.clone()),
+        _ => panic!("expected #[references(Model)]"),
+    }
+
+    return result;
+}
+
+/// `SqlType` of the Rust type, of the value of an Option, None if the type is unknown
+fn infer_sql_type(ty: &Type, is_id: bool) -> Option<TokenStream> {
+    let (name, argument) = split_type(ty)?;
+    if name == "Option" {
+        return infer_sql_type(argument?, is_id);
+    }
+
+    let sql_type = match name.as_str() {
+        "Id" if is_id => quote!(Serial),
+        "Id" | "Reference" | "i32" => quote!(Integer),
+        "i64" => quote!(BigInt),
+        "f64" => quote!(Double),
+        "bool" => quote!(Boolean),
+        "String" => quote!(Varchar),
+        "DateTime" => quote!(Timestamp),
+        "NaiveDate" => quote!(Date),
+        _ => return None,
+    };
+
+    return Some(quote!(crate::persistence::schema::SqlType::#sql_type));
+}
 
 pub fn impl_table_macro(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
+    let table = parse_table_info(ast);
 
     let mut id: Option<&Ident> = None;
 
     let mut fields_from_row = Vec::new();
     let mut to_col_val_pairs = Vec::new();
+    let mut fields_from_sqlite_row = Vec::new();
+    let mut to_sqlite_col_val_pairs = Vec::new();
+    let mut query_fields = Vec::new();
+    let mut column_schemas = Vec::new();
     match &ast.data {
         syn::Data::Struct(data_struct) => {
             for field in &data_struct.fields {
                 match &field.ident {
                     Some(ident) => {
-                        let ident_string = ident.to_string();
+                        let column = parse_column_info(field, ident);
+                        let is_id = contains_attribute(field, ID_ATTRIBUTE_NAME);
+
+                        // the name of the column, the field keeps its own
+                        let ident_string = column.name.clone();
                         fields_from_row.push(quote! { #ident: row.get(columns[#ident_string]) });
+                        fields_from_sqlite_row.push(quote! { #ident: row.get(#ident_string)? });
+
+                        let ty = &field.ty;
+                        let const_ident =
+                            Ident::new(&ident.to_string().to_uppercase(), ident.span());
+                        query_fields.push(quote! {
+                            pub const #const_ident: crate::persistence::query::Field<#name, #ty> =
+                                crate::persistence::query::Field::new(#ident_string, |it: &#name| {
+                                    crate::persistence::query::ToValue::to_value(&it.#ident)
+                                });
+                        });
+
+                        let nullable =
+                            matches!(split_type(ty), Some((name, _)) if name == "Option");
+                        let sql_type = match &column.sql_type {
+                            Some(sql_type) => {
+                                quote!(crate::persistence::schema::SqlType::parse(#sql_type))
+                            }
+                            None => infer_sql_type(ty, is_id).unwrap_or_else(|| {
+                                panic!(
+                                    "type of {} is unknown, set #[column(sql_type = \"...\")]",
+                                    ident
+                                )
+                            }),
+                        };
+                        let unique = column.unique;
+                        let index = column.index;
+                        let references = match &column.references {
+                            Some(path) => quote! {
+                                Some(<#path as crate::persistence::schema::Table>::TABLE)
+                            },
+                            None => quote!(None),
+                        };
+                        column_schemas.push(quote! {
+                            crate::persistence::schema::ColumnSchema {
+                                name: #ident_string,
+                                sql_type: #sql_type,
+                                nullable: #nullable,
+                                primary_key: #is_id,
+                                unique: #unique,
+                                index: #index,
+                                references: #references,
+                            }
+                        });
 
-                        if contains_attribute(field, ID_ATTRIBUTE_NAME) {
+                        if is_id {
                             id = Some(ident);
                             continue; // NOTE: id shouldn't be in "update" queries
                         }
@@ -29,6 +185,12 @@ pub fn impl_table_macro(ast: &syn::DeriveInput) -> TokenStream {
                         to_col_val_pairs.push(
                             quote!( r.push(ColumnValuePair::new(#ident_string, self.#ident)); ),
                         );
+                        to_sqlite_col_val_pairs.push(quote! {
+                            r.push(crate::persistence::sqlite::repository::ColumnValuePair::new(
+                                #ident_string,
+                                &self.#ident,
+                            ));
+                        });
                     }
                     None => panic!("not supported"),
                 };
@@ -53,9 +215,29 @@ pub fn impl_table_macro(ast: &syn::DeriveInput) -> TokenStream {
         quote! {}
     };
 
+    let table_name = &table.name;
+    let unique = &table.unique;
+
     return quote! {
         #gen_hasid
 
+        impl crate::persistence::schema::Table for #name {
+            const TABLE: &'static str = #table_name;
+
+            fn schema() -> crate::persistence::schema::TableSchema {
+                return crate::persistence::schema::TableSchema {
+                    name: #table_name,
+                    columns: vec![ #( #column_schemas ),* ],
+                    unique: vec![ #( vec![ #( #unique ),* ] ),* ],
+                };
+            }
+        }
+
+        /// Columns for `crate::persistence::query::Query`
+        impl #name {
+            #( #query_fields )*
+        }
+
         #[cfg(feature = "postgres")]
         use tokio_postgres::Row;
 
@@ -92,5 +274,31 @@ pub fn impl_table_macro(ast: &syn::DeriveInput) -> TokenStream {
                 return Ok(r);
             }
         }
+
+        #[cfg(feature = "sqlite")]
+        impl TryFrom<&rusqlite::Row<'_>> for #name {
+            type Error = anyhow::Error;
+
+            fn try_from(row: &rusqlite::Row<'_>) -> anyhow::Result<Self> {
+                return Ok(Self {
+                    #( #fields_from_sqlite_row, )*
+                });
+            }
+        }
+
+        #[cfg(feature = "sqlite")]
+        impl TryInto<Vec<crate::persistence::sqlite::repository::ColumnValuePair>> for #name {
+            type Error = anyhow::Error;
+
+            fn try_into(
+                self,
+            ) -> anyhow::Result<Vec<crate::persistence::sqlite::repository::ColumnValuePair>> {
+                let mut r = Vec::new();
+
+                #( #to_sqlite_col_val_pairs )*
+
+                return Ok(r);
+            }
+        }
     };
 }
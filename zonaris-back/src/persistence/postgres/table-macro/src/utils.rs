@@ -1,4 +1,4 @@
-use syn::{Field, GenericArgument, PathArguments, Type};
+use syn::{Attribute, Field, GenericArgument, Lit, Meta, NestedMeta, Path, PathArguments, Type};
 
 pub fn contains_attribute(field: &Field, name: &str) -> bool {
     return field
@@ -7,6 +7,77 @@ pub fn contains_attribute(field: &Field, name: &str) -> bool {
         .any(|it| it.path.get_ident().map(|it| it == name).unwrap_or(false));
 }
 
+/// Arguments of every `#[name(...)]`
+pub fn attribute_arguments(attrs: &[Attribute], name: &str) -> Vec<NestedMeta> {
+    let mut result = Vec::new();
+    for attr in attrs.iter().filter(|it| it.path.is_ident(name)) {
+        match attr.parse_meta() {
+            Ok(Meta::List(list)) => result.extend(list.nested),
+            _ => panic!("expected #[{}(...)]", name),
+        }
+    }
+
+    return result;
+}
+
+pub fn lit_string(lit: &Lit) -> String {
+    return match lit {
+        Lit::Str(it) => it.value(),
+        _ => panic!("expected a string"),
+    };
+}
+
+/// Name of a single ident argument, e.g. a column of `unique(satellite_id, epoch)`
+pub fn path_name(nested: &NestedMeta) -> String {
+    return match nested {
+        NestedMeta::Meta(Meta::Path(path)) => path_ident(path),
+        _ => panic!("expected a name"),
+    };
+}
+
+pub fn path_ident(path: &Path) -> String {
+    return path
+        .get_ident()
+        .map(|it| it.to_string())
+        .unwrap_or_else(|| panic!("expected a name"));
+}
+
+/// OceanColorMapping -> ocean_color_mapping
+pub fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+
+    return result;
+}
+
+/// Name of the type without its path and the type argument of a generic one, e.g. Reference and
+/// Satellite of Reference<Satellite>
+pub fn split_type(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+
+    let segment = tp.path.segments.last()?;
+    let argument = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first() {
+            Some(GenericArgument::Type(argument)) => Some(argument),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    return Some((segment.ident.to_string(), argument));
+}
+
 pub fn is_copy_type(ty: &Type) -> bool {
     if let Type::Path(tp) = ty {
         let segments = &tp.path.segments;
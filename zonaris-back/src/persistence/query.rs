@@ -0,0 +1,639 @@
+// Typed queries of the repositories. The Table derive generates a `Field` constant for every
+// column (e.g. `InstrumentData::SATELLITE_INSTRUMENT_ID`), the filters and orderings built from
+// them are compiled to SQL by the Postgres and SQLite repositories and evaluated on the entities
+// by the in-memory one. All of them follow the SQL semantics of Postgres: comparisons with NULL
+// match nothing and NULLs are sorted after every other value.
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use chrono::{DateTime, NaiveDate, Utc};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::repository::{HasId, Reference};
+
+/// Column value as both backends see it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    I32(i32),
+    I64(i64),
+    F64(f64),
+    Text(String),
+    Timestamp(DateTime<Utc>),
+    Date(NaiveDate),
+}
+
+impl Value {
+    /// None if any of the values is NULL or they are of different types
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        return match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+            (Value::I32(a), Value::I32(b)) => a.partial_cmp(b),
+            (Value::I64(a), Value::I64(b)) => a.partial_cmp(b),
+            (Value::F64(a), Value::F64(b)) => a.partial_cmp(b),
+            (Value::Text(a), Value::Text(b)) => a.partial_cmp(b),
+            (Value::Timestamp(a), Value::Timestamp(b)) => a.partial_cmp(b),
+            (Value::Date(a), Value::Date(b)) => a.partial_cmp(b),
+            _ => None,
+        };
+    }
+
+    /// Sort order of ORDER BY ... ASC
+    fn sort_order(&self, other: &Value) -> Ordering {
+        return match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Null, _) => Ordering::Greater,
+            (_, Value::Null) => Ordering::Less,
+            _ => self.compare(other).unwrap_or(Ordering::Equal),
+        };
+    }
+}
+
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+macro_rules! impl_to_value {
+    ($type:ty, $variant:ident) => {
+        impl ToValue for $type {
+            fn to_value(&self) -> Value {
+                return Value::$variant(self.clone());
+            }
+        }
+    };
+}
+
+impl_to_value!(bool, Bool);
+impl_to_value!(i32, I32);
+impl_to_value!(i64, I64);
+impl_to_value!(f64, F64);
+impl_to_value!(String, Text);
+impl_to_value!(DateTime<Utc>, Timestamp);
+impl_to_value!(NaiveDate, Date);
+
+impl<T: HasId> ToValue for Reference<T> {
+    fn to_value(&self) -> Value {
+        return (**self).to_value();
+    }
+}
+
+impl<V: ToValue> ToValue for Option<V> {
+    fn to_value(&self) -> Value {
+        return match self {
+            Some(value) => value.to_value(),
+            None => Value::Null,
+        };
+    }
+}
+
+/// Name of a column of `T` and how to read it from an entity
+pub struct Column<T> {
+    pub name: &'static str,
+    pub value_of: fn(&T) -> Value,
+}
+
+impl<T> Clone for Column<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Column<T> {}
+
+/// Column of `T` holding values of type `V`
+pub struct Field<T, V> {
+    column: Column<T>,
+    marker: PhantomData<fn() -> V>,
+}
+
+impl<T, V> Clone for Field<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for Field<T, V> {}
+
+impl<T, V: ToValue> Field<T, V> {
+    pub const fn new(name: &'static str, value_of: fn(&T) -> Value) -> Self {
+        return Self {
+            column: Column { name, value_of },
+            marker: PhantomData,
+        };
+    }
+
+    pub fn name(&self) -> &'static str {
+        return self.column.name;
+    }
+
+    fn compare(self, comparison: Comparison, value: impl Into<V>) -> Filter<T> {
+        return Filter::Compare(self.column, comparison, value.into().to_value());
+    }
+
+    pub fn eq(self, value: impl Into<V>) -> Filter<T> {
+        return self.compare(Comparison::Eq, value);
+    }
+
+    pub fn ne(self, value: impl Into<V>) -> Filter<T> {
+        return self.compare(Comparison::Ne, value);
+    }
+
+    pub fn lt(self, value: impl Into<V>) -> Filter<T> {
+        return self.compare(Comparison::Lt, value);
+    }
+
+    pub fn le(self, value: impl Into<V>) -> Filter<T> {
+        return self.compare(Comparison::Le, value);
+    }
+
+    pub fn gt(self, value: impl Into<V>) -> Filter<T> {
+        return self.compare(Comparison::Gt, value);
+    }
+
+    pub fn ge(self, value: impl Into<V>) -> Filter<T> {
+        return self.compare(Comparison::Ge, value);
+    }
+
+    /// Matches nothing if `values` is empty
+    pub fn is_in<I>(self, values: impl IntoIterator<Item = I>) -> Filter<T>
+    where
+        I: Into<V>,
+    {
+        return Filter::In(
+            self.column,
+            values.into_iter().map(|it| it.into().to_value()).collect(),
+        );
+    }
+
+    pub fn is_null(self) -> Filter<T> {
+        return Filter::IsNull(self.column);
+    }
+
+    pub fn is_not_null(self) -> Filter<T> {
+        return self.is_null().not();
+    }
+
+    pub fn asc(self) -> Order<T> {
+        return Order {
+            column: self.column,
+            descending: false,
+        };
+    }
+
+    pub fn desc(self) -> Order<T> {
+        return Order {
+            column: self.column,
+            descending: true,
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    pub fn sql(&self) -> &'static str {
+        return match self {
+            Comparison::Eq => "=",
+            Comparison::Ne => "<>",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        };
+    }
+
+    fn holds(&self, ordering: Ordering) -> bool {
+        return match self {
+            Comparison::Eq => ordering == Ordering::Equal,
+            Comparison::Ne => ordering != Ordering::Equal,
+            Comparison::Lt => ordering == Ordering::Less,
+            Comparison::Le => ordering != Ordering::Greater,
+            Comparison::Gt => ordering == Ordering::Greater,
+            Comparison::Ge => ordering != Ordering::Less,
+        };
+    }
+}
+
+pub enum Filter<T> {
+    Compare(Column<T>, Comparison, Value),
+    In(Column<T>, Vec<Value>),
+    IsNull(Column<T>),
+    And(Vec<Filter<T>>),
+    Or(Vec<Filter<T>>),
+    Not(Box<Filter<T>>),
+}
+
+impl<T> Filter<T> {
+    pub fn and(self, other: Filter<T>) -> Filter<T> {
+        return match self {
+            Filter::And(mut filters) => {
+                filters.push(other);
+                Filter::And(filters)
+            }
+            filter => Filter::And(vec![filter, other]),
+        };
+    }
+
+    pub fn or(self, other: Filter<T>) -> Filter<T> {
+        return match self {
+            Filter::Or(mut filters) => {
+                filters.push(other);
+                Filter::Or(filters)
+            }
+            filter => Filter::Or(vec![filter, other]),
+        };
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Filter<T> {
+        return Filter::Not(Box::new(self));
+    }
+
+    pub fn matches(&self, entity: &T) -> bool {
+        return self.evaluate(entity) == Some(true);
+    }
+
+    /// Three-valued logic of SQL, None is unknown
+    fn evaluate(&self, entity: &T) -> Option<bool> {
+        return match self {
+            Filter::Compare(column, comparison, value) => (column.value_of)(entity)
+                .compare(value)
+                .map(|it| comparison.holds(it)),
+            Filter::In(column, values) => {
+                let actual = (column.value_of)(entity);
+                let mut result = Some(false);
+                for value in values {
+                    match actual.compare(value) {
+                        Some(Ordering::Equal) => return Some(true),
+                        Some(_) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Filter::IsNull(column) => Some((column.value_of)(entity) == Value::Null),
+            Filter::And(filters) => {
+                let mut result = Some(true);
+                for filter in filters {
+                    match filter.evaluate(entity) {
+                        Some(false) => return Some(false),
+                        Some(true) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Filter::Or(filters) => {
+                let mut result = Some(false);
+                for filter in filters {
+                    match filter.evaluate(entity) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Filter::Not(filter) => filter.evaluate(entity).map(|it| !it),
+        };
+    }
+}
+
+pub struct Order<T> {
+    pub column: Column<T>,
+    pub descending: bool,
+}
+
+impl<T> Clone for Order<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Order<T> {}
+
+/// Position after an entity in the order of a query: the values of its order columns followed by
+/// its id. The order columns of keyset pagination shouldn't contain NULLs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cursor(pub Vec<Value>);
+
+/// Entities of a query with a limit, `next` continues after the last one if the page is full
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<Cursor>,
+}
+
+/// Every entity if nothing is set. Entities are ordered by the order columns followed by the id,
+/// keyset (`after`) and offset pagination are applied in this order.
+pub struct Query<T> {
+    filter: Option<Filter<T>>,
+    order: Vec<Order<T>>,
+    limit: Option<usize>,
+    offset: usize,
+    after: Option<Cursor>,
+}
+
+impl<T> Default for Query<T> {
+    fn default() -> Self {
+        return Self {
+            filter: None,
+            order: Vec::new(),
+            limit: None,
+            offset: 0,
+            after: None,
+        };
+    }
+}
+
+impl<T: HasId> Query<T> {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Filters are combined with AND
+    pub fn filter(mut self, filter: Filter<T>) -> Self {
+        self.filter = Some(match self.filter {
+            Some(current) => current.and(filter),
+            None => filter,
+        });
+        return self;
+    }
+
+    pub fn order_by(mut self, order: Order<T>) -> Self {
+        self.order.push(order);
+        return self;
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        return self;
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        return self;
+    }
+
+    pub fn after(mut self, cursor: Cursor) -> Self {
+        self.after = Some(cursor);
+        return self;
+    }
+
+    pub fn get_filter(&self) -> Option<&Filter<T>> {
+        return self.filter.as_ref();
+    }
+
+    pub fn get_limit(&self) -> Option<usize> {
+        return self.limit;
+    }
+
+    pub fn get_offset(&self) -> usize {
+        return self.offset;
+    }
+
+    pub fn get_after(&self) -> Option<&Cursor> {
+        return self.after.as_ref();
+    }
+
+    /// Order columns followed by the id
+    pub fn get_order(&self) -> Vec<Order<T>> {
+        let mut order = self.order.clone();
+        order.push(Order {
+            column: Column {
+                name: "id",
+                value_of: |it: &T| it.get_id().to_value(),
+            },
+            descending: false,
+        });
+        return order;
+    }
+
+    pub fn cursor_of(&self, entity: &T) -> Cursor {
+        return Cursor(
+            self.get_order()
+                .iter()
+                .map(|it| (it.column.value_of)(entity))
+                .collect(),
+        );
+    }
+
+    /// Evaluates the query on entities in memory
+    pub fn apply(&self, entities: impl IntoIterator<Item = T>) -> Vec<T> {
+        let order = self.get_order();
+
+        let mut result = entities
+            .into_iter()
+            .filter(|it| self.filter.as_ref().is_none_or(|filter| filter.matches(it)))
+            .filter(|it| {
+                self.after
+                    .as_ref()
+                    .is_none_or(|cursor| Self::is_after(&order, it, cursor))
+            })
+            .collect::<Vec<_>>();
+
+        result.sort_by(|a, b| {
+            for it in &order {
+                let ordering = (it.column.value_of)(a).sort_order(&(it.column.value_of)(b));
+                let ordering = if it.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            return Ordering::Equal;
+        });
+
+        return result
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+    }
+
+    /// Number of entities in memory matching the filter
+    pub fn count<'a>(&self, entities: impl IntoIterator<Item = &'a T>) -> usize
+    where
+        T: 'a,
+    {
+        return entities
+            .into_iter()
+            .filter(|it| self.filter.as_ref().is_none_or(|filter| filter.matches(it)))
+            .count();
+    }
+
+    /// Whether the entity follows the cursor, the same as the keyset condition in SQL
+    fn is_after(order: &[Order<T>], entity: &T, cursor: &Cursor) -> bool {
+        for (it, value) in order.iter().zip(&cursor.0) {
+            let ordering = match (it.column.value_of)(entity).compare(value) {
+                Some(ordering) if it.descending => ordering.reverse(),
+                Some(ordering) => ordering,
+                None => return false,
+            };
+            match ordering {
+                Ordering::Greater => return true,
+                Ordering::Less => return false,
+                Ordering::Equal => {}
+            }
+        }
+        return false;
+    }
+}
+
+impl<T: HasId> Page<T> {
+    pub fn new(query: &Query<T>, items: Vec<T>) -> Self {
+        let next = match query.get_limit() {
+            Some(limit) if limit > 0 && items.len() == limit => {
+                items.last().map(|it| query.cursor_of(it))
+            }
+            _ => None,
+        };
+        return Self { items, next };
+    }
+}
+
+/// SQL of a query with its parameters, values are always passed as parameters
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+#[derive(Default)]
+pub struct QueryBuilder {
+    params: Vec<Value>,
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+impl QueryBuilder {
+    pub fn params(&self) -> &[Value] {
+        return &self.params;
+    }
+
+    fn param(&mut self, value: &Value) -> String {
+        self.params.push(value.clone());
+        return format!("${}", self.params.len());
+    }
+
+    pub fn filter<T>(&mut self, filter: &Filter<T>) -> String {
+        return match filter {
+            Filter::Compare(column, comparison, value) => {
+                format!("{} {} {}", column.name, comparison.sql(), self.param(value))
+            }
+            Filter::In(_, values) if values.is_empty() => String::from("FALSE"),
+            Filter::In(column, values) => {
+                let values = values.iter().map(|it| self.param(it)).join(", ");
+                format!("{} IN ({})", column.name, values)
+            }
+            Filter::IsNull(column) => format!("{} IS NULL", column.name),
+            Filter::And(filters) if filters.is_empty() => String::from("TRUE"),
+            Filter::And(filters) => {
+                format!(
+                    "({})",
+                    filters.iter().map(|it| self.filter(it)).join(" AND ")
+                )
+            }
+            Filter::Or(filters) if filters.is_empty() => String::from("FALSE"),
+            Filter::Or(filters) => {
+                format!(
+                    "({})",
+                    filters.iter().map(|it| self.filter(it)).join(" OR ")
+                )
+            }
+            Filter::Not(filter) => format!("NOT ({})", self.filter(filter)),
+        };
+    }
+
+    /// Rows after the cursor in the order: (a > $1) OR (a = $1 AND b > $2) OR ...
+    pub fn keyset<T>(&mut self, order: &[Order<T>], cursor: &Cursor) -> String {
+        let columns = order
+            .iter()
+            .zip(&cursor.0)
+            .map(|(it, value)| (it, self.param(value)))
+            .collect::<Vec<_>>();
+
+        let conditions = (0..columns.len())
+            .map(|i| {
+                let (last, param) = &columns[i];
+                columns[..i]
+                    .iter()
+                    .map(|(it, param)| format!("{} = {}", it.column.name, param))
+                    .chain([format!(
+                        "{} {} {}",
+                        last.column.name,
+                        if last.descending { "<" } else { ">" },
+                        param
+                    )])
+                    .join(" AND ")
+            })
+            .map(|it| format!("({})", it))
+            .join(" OR ");
+
+        return format!("({})", conditions);
+    }
+
+    /// WHERE clause of the filter and the cursor of the query, empty if there are none
+    pub fn condition<T: HasId>(&mut self, query: &Query<T>, with_cursor: bool) -> String {
+        let mut conditions = Vec::new();
+        if let Some(filter) = query.get_filter() {
+            conditions.push(self.filter(filter));
+        }
+        if let Some(cursor) = query.get_after().filter(|_| with_cursor) {
+            conditions.push(self.keyset(&query.get_order(), cursor));
+        }
+
+        if conditions.is_empty() {
+            return String::new();
+        }
+        return format!(" WHERE {}", conditions.join(" AND "));
+    }
+
+    pub fn select<T: HasId>(&mut self, table: &str, query: &Query<T>) -> String {
+        let mut statement = format!("SELECT * FROM {}", table);
+        statement.push_str(&self.condition(query, true));
+
+        let order = query
+            .get_order()
+            .iter()
+            .map(|it| {
+                // SQLite sorts NULLs first by default
+                format!(
+                    "{} {}",
+                    it.column.name,
+                    if it.descending {
+                        "DESC NULLS FIRST"
+                    } else {
+                        "ASC NULLS LAST"
+                    }
+                )
+            })
+            .join(", ");
+        statement.push_str(&format!(" ORDER BY {}", order));
+
+        if let Some(limit) = query.get_limit() {
+            statement.push_str(&format!(" LIMIT {}", limit));
+        }
+        if query.get_offset() > 0 {
+            statement.push_str(&format!(" OFFSET {}", query.get_offset()));
+        }
+
+        return statement;
+    }
+
+    pub fn count<T: HasId>(&mut self, table: &str, query: &Query<T>) -> String {
+        return format!(
+            "SELECT COUNT(*) FROM {}{}",
+            table,
+            self.condition(query, false)
+        );
+    }
+}
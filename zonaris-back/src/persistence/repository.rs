@@ -2,16 +2,43 @@ use std::{
     collections::HashMap,
     marker::PhantomData,
     ops::{AddAssign, Deref},
+    sync::{Arc, Mutex, MutexGuard},
 };
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use log::error;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use utoipa::ToSchema;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+use super::journal::{Entry, WriteLog};
+use super::query::{Page, Query, ToValue, Value};
+use super::unit_of_work::UnitOfWork;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
 pub struct Id(i32);
 
+impl Id {
+    /// The id after this one
+    pub(super) fn next(self) -> Id {
+        return Id(self.0 + 1);
+    }
+}
+
 impl AddAssign<i32> for Id {
     fn add_assign(&mut self, rhs: i32) {
         self.0 += rhs;
@@ -24,6 +51,57 @@ impl std::fmt::Display for Id {
     }
 }
 
+impl ToValue for Id {
+    fn to_value(&self) -> Value {
+        return Value::I32(self.0);
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> tokio_postgres::types::FromSql<'a> for Id {
+    fn from_sql(
+        ty: &tokio_postgres::types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        return Ok(Id(i32::from_sql(ty, raw)?));
+    }
+
+    fn accepts(ty: &tokio_postgres::types::Type) -> bool {
+        return <i32 as tokio_postgres::types::FromSql>::accepts(ty);
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl tokio_postgres::types::ToSql for Id {
+    fn to_sql(
+        &self,
+        ty: &tokio_postgres::types::Type,
+        out: &mut tokio_util::bytes::BytesMut,
+    ) -> Result<tokio_postgres::types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        return self.0.to_sql(ty, out);
+    }
+
+    fn accepts(ty: &tokio_postgres::types::Type) -> bool {
+        return <i32 as tokio_postgres::types::ToSql>::accepts(ty);
+    }
+
+    tokio_postgres::types::to_sql_checked!();
+}
+
+#[cfg(feature = "sqlite")]
+impl rusqlite::types::FromSql for Id {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        return i32::column_result(value).map(Id);
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl rusqlite::types::ToSql for Id {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        return self.0.to_sql();
+    }
+}
+
 pub trait HasId {
     fn get_id(&self) -> Option<Id>;
     fn set_id(&mut self, id: Id);
@@ -42,17 +120,17 @@ impl<T: HasId> Reference<T> {
         };
     }
 
-    pub async fn resolve<R: Repository<T>>(&self, repository: &R) -> Result<Option<T>> {
+    pub async fn resolve<R: Repository<T>>(
+        &self,
+        repository: &R,
+    ) -> Result<Option<T>, RepositoryError> {
         return repository.get(self.id).await;
     }
 }
 
 impl<T: HasId> Clone for Reference<T> {
     fn clone(&self) -> Self {
-        Self {
-            id: self.id.clone(),
-            marker: PhantomData,
-        }
+        *self
     }
 }
 
@@ -66,32 +144,87 @@ impl<T: HasId> Deref for Reference<T> {
     }
 }
 
+impl<T: HasId> From<Id> for Reference<T> {
+    fn from(id: Id) -> Self {
+        return Reference::new(id);
+    }
+}
+
+/// Written as the id
+impl<T: HasId> Serialize for Reference<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return self.id.serialize(serializer);
+    }
+}
+
+impl<'de, T: HasId> Deserialize<'de> for Reference<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return Id::deserialize(deserializer).map(Reference::new);
+    }
+}
+
 impl<T: HasId> PartialEq<Id> for Reference<T> {
     fn eq(&self, other: &Id) -> bool {
         return self.id.eq(other);
     }
 }
 
-// TODO: replace bool-s with Result<(), RepositoryError>
+/// Failures of the repositories the callers can react to, the backend specific ones are mapped onto
+/// these variants and everything else is `Other`. `ForeignKey` and `Stale` come from the
+/// constraints and transactions of the SQL backends, the in-memory repositories never return them.
+#[derive(Error, Debug)]
+pub enum RepositoryError {
+    #[error("record with id {0} not found")]
+    NotFound(Id),
+    /// Unique key violation, e.g. the id or the catalog number of a satellite is taken
+    #[error("record already exists: {0}")]
+    Conflict(String),
+    /// The record references a missing one or is referenced by another. Postgres and SQLite only,
+    /// an in-memory repository doesn't know the tables which reference its records.
+    #[error("reference violated: {0}")]
+    ForeignKey(String),
+    /// The transaction failed to serialize with a concurrent one or deadlocked and can be retried.
+    /// Postgres only, the records have no version to check.
+    #[error("transaction conflicted with a concurrent one: {0}")]
+    Stale(String),
+    /// The backend can't be reached or doesn't answer in time, the operation can be retried
+    #[error("storage is unavailable: {0}")]
+    Unavailable(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[async_trait]
 pub trait Repository<T>
 where
     T: HasId,
 {
     /// Some(T) if record with given id found else None
-    async fn get(&self, id: Id) -> Result<Option<T>>;
+    async fn get(&self, id: Id) -> Result<Option<T>, RepositoryError>;
+
+    /// Id of the added entity, `Conflict` if an entity with the same key exists
+    async fn add(&mut self, entity: T) -> Result<Id, RepositoryError>;
+
+    /// `NotFound` if there is no entity with the id
+    async fn delete(&mut self, id: Id) -> Result<(), RepositoryError>;
 
-    /// true if successfully added or if it's impossible to determine status of operation else false (for example in case when entity already with same key already in repository)
-    async fn add(&mut self, entity: T) -> Result<Option<Id>>;
+    /// `NotFound` if there is no entity with the id of `entity`
+    async fn update(&mut self, entity: T) -> Result<(), RepositoryError>;
 
-    /// true if successfully deleted or if it's impossible to determine status of operation else false
-    async fn delete(&mut self, id: Id) -> Result<bool>;
+    /// Every entity, `query` should be preferred for anything but small tables
+    async fn get_all(&self) -> Result<Vec<T>, RepositoryError>;
 
-    /// true if successfully updated or if it's impossible to determine status of operation else false (for example in case when `entity.get_id().is_none()`)
-    async fn update(&mut self, entity: T) -> Result<bool>;
+    /// Entities matching the filter of the query in its order and page
+    async fn query(&self, query: &Query<T>) -> Result<Vec<T>, RepositoryError>;
 
-    // TODO: this function can have performance issue. recomended implementation with pagination (offset, size)
-    async fn get_all(&self) -> Result<Vec<T>>;
+    /// Number of entities matching the filter of the query, its order and page are ignored
+    async fn count(&self, query: &Query<T>) -> Result<usize, RepositoryError>;
+
+    /// Entities of `query` with the cursor of the next page if the page is full
+    async fn page(&self, query: &Query<T>) -> Result<Page<T>, RepositoryError> {
+        let items = self.query(query).await?;
+        return Ok(Page::new(query, items));
+    }
 }
 
 pub struct InMemoryRepository<T>
@@ -100,8 +233,22 @@ where
     T: Clone,
     T: Send + Sync, // TODO: i don't sure why
 {
-    data: HashMap<Id, T>,
+    // shared with the rollbacks of the units of work
+    data: Arc<Mutex<HashMap<Id, T>>>,
     next_id: Id,
+    /// Changes are appended to it before they are made if the records are persisted
+    journal: Option<Arc<dyn WriteLog<T>>>,
+}
+
+impl<T> Default for InMemoryRepository<T>
+where
+    T: HasId,
+    T: Clone,
+    T: Send + Sync,
+{
+    fn default() -> Self {
+        return Self::new();
+    }
 }
 
 impl<T> InMemoryRepository<T>
@@ -112,18 +259,80 @@ where
 {
     pub fn new() -> InMemoryRepository<T> {
         return InMemoryRepository::<T> {
-            data: HashMap::new(),
+            data: Arc::new(Mutex::new(HashMap::new())),
             next_id: Id(0),
+            journal: None,
         };
     }
 
+    /// Records loaded by the journal, it shares them to take snapshots
+    pub(super) fn with_journal(
+        data: Arc<Mutex<HashMap<Id, T>>>,
+        next_id: Id,
+        journal: Arc<dyn WriteLog<T>>,
+    ) -> InMemoryRepository<T> {
+        return InMemoryRepository::<T> {
+            data,
+            next_id,
+            journal: Some(journal),
+        };
+    }
+
+    fn data(&self) -> MutexGuard<'_, HashMap<Id, T>> {
+        return self.data.lock().unwrap();
+    }
+
     fn get_unoccupied_id(&mut self) -> Id {
-        while self.data.contains_key(&self.next_id) {
+        while self.data().contains_key(&self.next_id) {
             self.next_id += 1;
         }
 
         return self.next_id;
     }
+
+    /// Called with the records locked, the entries are in the order of the changes
+    fn log(&self, entry: Entry<T>) -> Result<(), RepositoryError> {
+        if let Some(journal) = &self.journal {
+            journal
+                .append(&entry)
+                .map_err(|error| RepositoryError::Unavailable(error.to_string()))?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl<T> InMemoryRepository<T>
+where
+    T: HasId,
+    T: Clone,
+    T: Send + Sync + 'static,
+{
+    /// Puts `previous` back under `id` if the unit of work of the task is rolled back
+    fn record(&self, id: Id, previous: Option<T>) {
+        if let Some(unit) = UnitOfWork::current() {
+            let data = self.data.clone();
+            let journal = self.journal.clone();
+            unit.on_rollback(move || {
+                let mut data = data.lock().unwrap();
+                let entry = match &previous {
+                    Some(previous) => Entry::Put(previous.clone()),
+                    None => Entry::Delete(id),
+                };
+                if let Some(journal) = journal {
+                    // the change stays in the log until the next snapshot if this fails
+                    if let Err(error) = journal.append(&entry) {
+                        error!("can't log rollback of {}: {}", id, error);
+                    }
+                }
+
+                match previous {
+                    Some(previous) => data.insert(id, previous),
+                    None => data.remove(&id),
+                };
+            });
+        }
+    }
 }
 
 impl<T> From<&[T]> for InMemoryRepository<T>
@@ -133,18 +342,7 @@ where
     T: Send + Sync,
 {
     fn from(elements: &[T]) -> Self {
-        let mut repository = InMemoryRepository::<T>::new();
-        let mut id = Id(0);
-        for element in elements {
-            let mut element = element.clone();
-            element.set_id(id);
-            repository.data.insert(id, element);
-            id += 1;
-        }
-
-        repository.next_id = id;
-
-        return repository;
+        return InMemoryRepository::from(elements.to_vec());
     }
 }
 
@@ -159,7 +357,7 @@ where
         let mut id = Id(0);
         for mut element in elements {
             element.set_id(id);
-            repository.data.insert(id, element);
+            repository.data().insert(id, element);
             id += 1;
         }
 
@@ -174,43 +372,80 @@ impl<T> Repository<T> for InMemoryRepository<T>
 where
     T: HasId,
     T: Clone,
-    T: Send + Sync,
+    T: Send + Sync + 'static,
 {
-    async fn get(&self, id: Id) -> Result<Option<T>> {
-        return Ok(self.data.get(&id).cloned());
+    async fn get(&self, id: Id) -> Result<Option<T>, RepositoryError> {
+        return Ok(self.data().get(&id).cloned());
     }
 
-    async fn add(&mut self, mut entity: T) -> Result<Option<Id>> {
+    async fn add(&mut self, mut entity: T) -> Result<Id, RepositoryError> {
         let key = if let Some(id) = entity.get_id() {
             id
         } else {
             self.get_unoccupied_id()
         };
 
-        if self.data.contains_key(&key) {
-            return Err(anyhow!("key already presented"));
+        {
+            let mut data = self.data();
+            if data.contains_key(&key) {
+                return Err(RepositoryError::Conflict(format!("id {} is taken", key)));
+            }
+
+            entity.set_id(key);
+            if self.journal.is_some() {
+                self.log(Entry::Put(entity.clone()))?;
+            }
+            data.insert(key, entity);
         }
 
-        entity.set_id(key);
-        self.data.insert(key, entity);
-        return Ok(Some(key));
+        // ids aren't reused once their records are deleted, as with the sequences of databases
+        self.next_id = self.next_id.max(key.next());
+        self.record(key, None);
+        return Ok(key);
     }
 
-    async fn delete(&mut self, id: Id) -> Result<bool> {
-        return Ok(self.data.remove(&id).is_some());
+    async fn delete(&mut self, id: Id) -> Result<(), RepositoryError> {
+        let previous = {
+            let mut data = self.data();
+            if !data.contains_key(&id) {
+                return Err(RepositoryError::NotFound(id));
+            }
+
+            self.log(Entry::Delete(id))?;
+            data.remove(&id)
+        };
+
+        self.record(id, previous);
+        return Ok(());
     }
 
-    async fn update(&mut self, entity: T) -> Result<bool> {
+    async fn update(&mut self, entity: T) -> Result<(), RepositoryError> {
         let key = entity.get_id().ok_or(anyhow!("entity doesn't have id"))?;
-        if let Some(data) = self.data.get_mut(&key) {
-            *data = entity;
-            return Ok(true);
-        }
+        let previous = {
+            let mut data = self.data();
+            let Some(current) = data.get_mut(&key) else {
+                return Err(RepositoryError::NotFound(key));
+            };
+
+            if self.journal.is_some() {
+                self.log(Entry::Put(entity.clone()))?;
+            }
+            std::mem::replace(current, entity)
+        };
+
+        self.record(key, Some(previous));
+        return Ok(());
+    }
+
+    async fn get_all(&self) -> Result<Vec<T>, RepositoryError> {
+        return Ok(self.data().values().cloned().collect());
+    }
 
-        return Ok(false);
+    async fn query(&self, query: &Query<T>) -> Result<Vec<T>, RepositoryError> {
+        return Ok(query.apply(self.data().values().cloned()));
     }
 
-    async fn get_all(&self) -> Result<Vec<T>> {
-        return Ok(self.data.values().cloned().collect());
+    async fn count(&self, query: &Query<T>) -> Result<usize, RepositoryError> {
+        return Ok(query.count(self.data().values()));
     }
 }
@@ -0,0 +1,347 @@
+// Schema of the tables described by `#[derive(Table)]`: the columns come from the fields of the
+// model, their types from the Rust types unless `#[column(sql_type = ...)]` is set. The migrations
+// stay handwritten, the generated schema is the reference they are checked against: `migrate
+// check` and the startup diff it with the tables of the live database.
+
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+/// Model stored in a table, implemented by `#[derive(Table)]`
+pub trait Table {
+    /// `#[table(name = ...)]`
+    const TABLE: &'static str;
+
+    fn schema() -> TableSchema;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dialect {
+    Postgres,
+    Sqlite,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SqlType {
+    /// Id of the table, generated by the database and never reused
+    Serial,
+    Integer,
+    BigInt,
+    Double,
+    Varchar,
+    Boolean,
+    Timestamp,
+    Date,
+    /// Written as it is in both dialects
+    Custom(&'static str),
+}
+
+impl SqlType {
+    /// Type of `#[column(sql_type = ...)]`, the Postgres names of the known types are accepted
+    pub fn parse(name: &'static str) -> SqlType {
+        return match name.to_uppercase().as_str() {
+            "SERIAL" => SqlType::Serial,
+            "INTEGER" | "INT" => SqlType::Integer,
+            "BIGINT" => SqlType::BigInt,
+            "DOUBLE PRECISION" => SqlType::Double,
+            "VARCHAR" => SqlType::Varchar,
+            "BOOLEAN" => SqlType::Boolean,
+            "TIMESTAMPTZ" => SqlType::Timestamp,
+            "DATE" => SqlType::Date,
+            _ => SqlType::Custom(name),
+        };
+    }
+
+    /// Name in CREATE TABLE
+    pub fn name(&self, dialect: Dialect) -> &'static str {
+        return match dialect {
+            Dialect::Postgres => match self {
+                SqlType::Serial => "SERIAL",
+                SqlType::Integer => "INTEGER",
+                SqlType::BigInt => "BIGINT",
+                SqlType::Double => "DOUBLE PRECISION",
+                SqlType::Varchar => "VARCHAR",
+                SqlType::Boolean => "BOOLEAN",
+                SqlType::Timestamp => "TIMESTAMPTZ",
+                SqlType::Date => "DATE",
+                SqlType::Custom(name) => name,
+            },
+            // timestamps and dates are stored as text which sorts in time order
+            Dialect::Sqlite => match self {
+                SqlType::Serial | SqlType::Integer | SqlType::BigInt | SqlType::Boolean => {
+                    "INTEGER"
+                }
+                SqlType::Double => "REAL",
+                SqlType::Varchar | SqlType::Timestamp | SqlType::Date => "TEXT",
+                SqlType::Custom(name) => name,
+            },
+        };
+    }
+
+    /// Name the database reports for columns of the type, information_schema.columns.data_type
+    /// of Postgres and the declared type of SQLite
+    pub fn live_name(&self, dialect: Dialect) -> &'static str {
+        return match (dialect, self) {
+            (Dialect::Postgres, SqlType::Serial | SqlType::Integer) => "integer",
+            (Dialect::Postgres, SqlType::BigInt) => "bigint",
+            (Dialect::Postgres, SqlType::Double) => "double precision",
+            (Dialect::Postgres, SqlType::Varchar) => "character varying",
+            (Dialect::Postgres, SqlType::Boolean) => "boolean",
+            (Dialect::Postgres, SqlType::Timestamp) => "timestamp with time zone",
+            (Dialect::Postgres, SqlType::Date) => "date",
+            _ => self.name(dialect),
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSchema {
+    /// `#[column(name = ...)]`, the name of the field by default
+    pub name: &'static str,
+    pub sql_type: SqlType,
+    /// The field is an Option
+    pub nullable: bool,
+    /// `#[id]`
+    pub primary_key: bool,
+    /// `#[column(unique)]`
+    pub unique: bool,
+    /// `#[column(index)]`
+    pub index: bool,
+    /// Table of `#[references(Model)]`
+    pub references: Option<&'static str>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableSchema {
+    pub name: &'static str,
+    pub columns: Vec<ColumnSchema>,
+    /// `#[table(unique(a, b))]`, unique keys of several columns
+    pub unique: Vec<Vec<&'static str>>,
+}
+
+impl TableSchema {
+    /// CREATE TABLE followed by CREATE INDEX of the indexed columns
+    pub fn create_table(&self, dialect: Dialect) -> String {
+        let mut definitions = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut definition = format!("{} {}", column.name, column.sql_type.name(dialect));
+                if column.primary_key {
+                    definition += match dialect {
+                        Dialect::Postgres => " PRIMARY KEY",
+                        Dialect::Sqlite => " PRIMARY KEY AUTOINCREMENT",
+                    };
+                } else if column.nullable {
+                    definition += " NULL DEFAULT NULL";
+                } else {
+                    definition += " NOT NULL";
+                }
+                if column.unique {
+                    definition += " UNIQUE";
+                }
+                if let Some(table) = column.references {
+                    definition += &format!(" REFERENCES {} (id)", table);
+                }
+                return definition;
+            })
+            .collect::<Vec<_>>();
+        for columns in &self.unique {
+            definitions.push(format!("UNIQUE ({})", columns.join(", ")));
+        }
+
+        let mut result = format!(
+            "CREATE TABLE {}\n(\n    {}\n);\n",
+            self.name,
+            definitions.join(",\n    ")
+        );
+        for column in self.columns.iter().filter(|it| it.index) {
+            result += &format!(
+                "CREATE INDEX {}_{}_idx ON {} ({});\n",
+                self.name, column.name, self.name, column.name
+            );
+        }
+
+        return result;
+    }
+
+    /// Differences of the live table from the schema, None if the table doesn't exist. Extra
+    /// indexes and unique keys of the live table aren't reported.
+    pub fn diff(&self, live: Option<&LiveTable>, dialect: Dialect) -> Vec<SchemaDrift> {
+        let table = String::from(self.name);
+        let Some(live) = live else {
+            return vec![SchemaDrift::MissingTable(table)];
+        };
+
+        let mut result = Vec::new();
+        for column in &self.columns {
+            let name = String::from(column.name);
+            let Some(live_column) = live.columns.iter().find(|it| it.name == name) else {
+                result.push(SchemaDrift::MissingColumn(table.clone(), name));
+                continue;
+            };
+
+            let expected = column.sql_type.live_name(dialect);
+            if !live_column.sql_type.eq_ignore_ascii_case(expected) {
+                result.push(SchemaDrift::Type {
+                    table: table.clone(),
+                    column: name.clone(),
+                    expected: String::from(expected),
+                    actual: live_column.sql_type.clone(),
+                });
+            }
+            // the id is not null as the primary key even if SQLite reports it as nullable
+            if !column.primary_key && live_column.nullable != column.nullable {
+                result.push(SchemaDrift::Nullable {
+                    table: table.clone(),
+                    column: name.clone(),
+                    expected: column.nullable,
+                });
+            }
+            if let Some(references) = column.references {
+                if !live
+                    .references
+                    .iter()
+                    .any(|(column, referenced)| *column == name && referenced == references)
+                {
+                    result.push(SchemaDrift::MissingReference {
+                        table: table.clone(),
+                        column: name.clone(),
+                        references: String::from(references),
+                    });
+                }
+            }
+            if column.index && !live.indexes.iter().any(|it| it.columns == [column.name]) {
+                result.push(SchemaDrift::MissingIndex(table.clone(), vec![name.clone()]));
+            }
+        }
+
+        let unique = self
+            .columns
+            .iter()
+            .filter(|it| it.unique)
+            .map(|it| vec![it.name])
+            .chain(self.unique.iter().cloned());
+        for columns in unique {
+            if !live
+                .indexes
+                .iter()
+                .any(|it| it.unique && it.columns == columns)
+            {
+                result.push(SchemaDrift::MissingUnique(
+                    table.clone(),
+                    columns.into_iter().map(String::from).collect(),
+                ));
+            }
+        }
+
+        for column in &live.columns {
+            if !self.columns.iter().any(|it| it.name == column.name) {
+                result.push(SchemaDrift::ExtraColumn(table.clone(), column.name.clone()));
+            }
+        }
+
+        return result;
+    }
+}
+
+/// Table as the database describes it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LiveTable {
+    pub columns: Vec<LiveColumn>,
+    /// Column and the table it references
+    pub references: Vec<(String, String)>,
+    /// Indexes other than the one of the primary key, unique keys included
+    pub indexes: Vec<LiveIndex>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiveColumn {
+    pub name: String,
+    pub sql_type: String,
+    pub nullable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiveIndex {
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaDrift {
+    MissingTable(String),
+    MissingColumn(String, String),
+    ExtraColumn(String, String),
+    Type {
+        table: String,
+        column: String,
+        expected: String,
+        actual: String,
+    },
+    Nullable {
+        table: String,
+        column: String,
+        expected: bool,
+    },
+    MissingReference {
+        table: String,
+        column: String,
+        references: String,
+    },
+    MissingUnique(String, Vec<String>),
+    MissingIndex(String, Vec<String>),
+}
+
+impl Display for SchemaDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            SchemaDrift::MissingTable(table) => write!(f, "table {} is missing", table),
+            SchemaDrift::MissingColumn(table, column) => {
+                write!(f, "column {}.{} is missing", table, column)
+            }
+            SchemaDrift::ExtraColumn(table, column) => {
+                write!(f, "column {}.{} isn't in the model", table, column)
+            }
+            SchemaDrift::Type {
+                table,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column {}.{} is {}, expected {}",
+                table, column, actual, expected
+            ),
+            SchemaDrift::Nullable {
+                table,
+                column,
+                expected: true,
+            } => write!(f, "column {}.{} should be nullable", table, column),
+            SchemaDrift::Nullable { table, column, .. } => {
+                write!(f, "column {}.{} should be NOT NULL", table, column)
+            }
+            SchemaDrift::MissingReference {
+                table,
+                column,
+                references,
+            } => write!(
+                f,
+                "column {}.{} doesn't reference {}",
+                table, column, references
+            ),
+            SchemaDrift::MissingUnique(table, columns) => write!(
+                f,
+                "unique key {} ({}) is missing",
+                table,
+                columns.iter().join(", ")
+            ),
+            SchemaDrift::MissingIndex(table, columns) => write!(
+                f,
+                "index of {} ({}) is missing",
+                table,
+                columns.iter().join(", ")
+            ),
+        };
+    }
+}
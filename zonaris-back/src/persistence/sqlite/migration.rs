@@ -0,0 +1,143 @@
+// Migrations of the SQLite schema in migrations/sqlite/, see `crate::persistence::migration`. They
+// mirror the Postgres ones version by version.
+
+use anyhow::Result;
+use log::info;
+
+use crate::persistence::migration::{
+    check_drift, status_of, AppliedMigration, Migration, MigrationError, MigrationStatus,
+};
+
+use super::Database;
+
+/// Versions start with 1 and have no gaps, applied migrations must never be edited
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    name: "init",
+    up: include_str!("../../../migrations/sqlite/0001_init.up.sql"),
+    down: include_str!("../../../migrations/sqlite/0001_init.down.sql"),
+}];
+
+/// Applies the pending migrations in order, each one in its own transaction, returns their
+/// versions
+pub async fn migrate(database: Database) -> Result<Vec<i32>> {
+    let mut connection = database.get().await;
+
+    let mut result = Vec::new();
+    loop {
+        // the write lock of the immediate transaction serializes concurrent runs
+        let transaction =
+            connection.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        let applied = lock(&transaction)?;
+
+        let next = MIGRATIONS
+            .iter()
+            .find(|migration| !applied.iter().any(|it| it.version == migration.version));
+        let Some(migration) = next else {
+            break;
+        };
+
+        transaction.execute_batch(migration.up)?;
+        transaction.execute(
+            "INSERT INTO schema_migration (version, name, checksum) VALUES ($1, $2, $3)",
+            (migration.version, migration.name, migration.checksum()),
+        )?;
+        transaction.commit()?;
+
+        info!("migration {} {} applied", migration.version, migration.name);
+        result.push(migration.version);
+    }
+
+    return Ok(result);
+}
+
+/// Reverts the applied migrations after `target` in reverse order, each one in its own
+/// transaction, returns their versions. Target 0 reverts all of them.
+pub async fn revert(database: Database, target: i32) -> Result<Vec<i32>> {
+    if target != 0 && !MIGRATIONS.iter().any(|it| it.version == target) {
+        return Err(MigrationError::UnknownTarget(target).into());
+    }
+
+    let mut connection = database.get().await;
+
+    let mut result = Vec::new();
+    loop {
+        let transaction =
+            connection.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        let applied = lock(&transaction)?;
+
+        let last = applied
+            .iter()
+            .filter(|it| it.version > target)
+            .max_by_key(|it| it.version)
+            .and_then(|last| MIGRATIONS.iter().find(|it| it.version == last.version));
+        let Some(migration) = last else {
+            break;
+        };
+
+        transaction.execute_batch(migration.down)?;
+        transaction.execute(
+            "DELETE FROM schema_migration WHERE version = $1",
+            [migration.version],
+        )?;
+        transaction.commit()?;
+
+        info!(
+            "migration {} {} reverted",
+            migration.version, migration.name
+        );
+        result.push(migration.version);
+    }
+
+    return Ok(result);
+}
+
+pub async fn status(database: Database) -> Result<Vec<MigrationStatus>> {
+    let connection = database.get().await;
+    return Ok(status_of(MIGRATIONS, &load_applied(&connection)?));
+}
+
+/// Creates the tracking table in the transaction and returns the applied migrations after
+/// checking them for drift
+fn lock(transaction: &rusqlite::Transaction) -> Result<Vec<AppliedMigration>> {
+    transaction.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migration
+        (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'))
+        );",
+    )?;
+
+    let applied = load_applied(transaction)?;
+    check_drift(MIGRATIONS, &applied)?;
+
+    return Ok(applied);
+}
+
+/// Empty if the tracking table doesn't exist
+fn load_applied(connection: &rusqlite::Connection) -> Result<Vec<AppliedMigration>> {
+    let exists: bool = connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migration')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+
+    let mut statement = connection.prepare(
+        "SELECT version, name, checksum, applied_at FROM schema_migration ORDER BY version",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok(AppliedMigration {
+            version: row.get("version")?,
+            name: row.get("name")?,
+            checksum: row.get("checksum")?,
+            applied_at: row.get("applied_at")?,
+        })
+    })?;
+
+    return Ok(rows.collect::<Result<Vec<_>, _>>()?);
+}
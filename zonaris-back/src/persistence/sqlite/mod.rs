@@ -0,0 +1,240 @@
+// SQLite backend for single-file deployments. The repositories share one connection: SQLite runs
+// one write transaction at a time anyway, so a unit of work holds the connection until it is
+// committed and the statements of other tasks wait for it. Statements run on the calling task,
+// they are answered from the local file. They don't wait for the write lock of another process,
+// which would block the thread of the runtime, and fail with `Unavailable` instead.
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use rusqlite::{
+    ffi,
+    types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef},
+    ErrorCode, Row, Statement,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
+
+use crate::persistence::repository::HasId;
+
+use self::repository::ColumnValuePair;
+
+use super::query::Value;
+use super::repository::{Id, Reference, RepositoryError};
+use super::schema::Table;
+use super::unit_of_work::{SqliteTransaction, UnitOfWork};
+
+pub mod migration;
+pub mod repository;
+pub mod schema;
+
+pub type SqliteRepository<T> = Arc<RwLock<self::repository::SqliteRepository<T>>>;
+
+/// Connection to the database file shared by the repositories
+#[derive(Clone)]
+pub struct Database {
+    connection: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Database {
+    /// Opens the database file, it is created if it doesn't exist
+    pub fn open(path: &Path) -> Result<Database> {
+        let connection = rusqlite::Connection::open(path)?;
+        // rusqlite waits for 5 seconds by default
+        connection.busy_timeout(Duration::ZERO)?;
+        // foreign keys are checked only if they are enabled for the connection
+        connection.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")?;
+
+        return Ok(Database {
+            connection: Arc::new(Mutex::new(connection)),
+        });
+    }
+
+    /// The connection outside of a unit of work, waits for the running unit of work
+    pub async fn get(&self) -> OwnedMutexGuard<rusqlite::Connection> {
+        return self.connection.clone().lock_owned().await;
+    }
+
+    /// Connection of the unit of work of the task, its transaction is begun on the first use, or
+    /// the shared connection outside of a unit of work
+    pub async fn connection(&self) -> Result<Connection, RepositoryError> {
+        let Some(unit) = UnitOfWork::current() else {
+            return Ok(Connection::Database(self.get().await));
+        };
+
+        let mut transaction = unit.sqlite.clone().lock_owned().await;
+        if transaction.is_none() {
+            let connection = self.get().await;
+            // the write lock is taken right away, a deferred transaction fails instead of
+            // waiting if another process writes before it does
+            connection.execute_batch("BEGIN IMMEDIATE")?;
+            *transaction = Some(SqliteTransaction {
+                connection,
+                failed: false,
+            });
+        }
+
+        return Ok(Connection::UnitOfWork(transaction));
+    }
+}
+
+pub enum Connection {
+    Database(OwnedMutexGuard<rusqlite::Connection>),
+    /// Held until the statement is done, the statements of a unit of work run one by one
+    UnitOfWork(OwnedMutexGuard<Option<SqliteTransaction>>),
+}
+
+impl Connection {
+    fn client(&self) -> &rusqlite::Connection {
+        return match self {
+            Connection::Database(connection) => connection,
+            Connection::UnitOfWork(transaction) => {
+                &transaction
+                    .as_ref()
+                    .expect("transaction is begun")
+                    .connection
+            }
+        };
+    }
+
+    /// Failed statements roll the transaction of the unit of work back on commit, as they do in
+    /// Postgres
+    fn check<R>(&mut self, result: Result<R>) -> Result<R, RepositoryError> {
+        return result.map_err(|error| {
+            if let Connection::UnitOfWork(transaction) = self {
+                if let Some(transaction) = transaction.as_mut() {
+                    transaction.failed = true;
+                }
+            }
+
+            match error.downcast::<rusqlite::Error>() {
+                Ok(error) => error.into(),
+                Err(error) => RepositoryError::Other(error),
+            }
+        });
+    }
+
+    /// Rows of the statement read by `map`, the parameters are $1, $2, ...
+    pub fn query<R>(
+        &mut self,
+        statement: &str,
+        params: &[Value],
+        map: impl Fn(&Row) -> Result<R>,
+    ) -> Result<Vec<R>, RepositoryError> {
+        let result = (|| {
+            let mut statement = self.client().prepare_cached(statement)?;
+            bind(&mut statement, params)?;
+
+            let mut rows = statement.raw_query();
+            let mut result = Vec::new();
+            while let Some(row) = rows.next()? {
+                result.push(map(row)?);
+            }
+            return Ok(result);
+        })();
+        return self.check(result);
+    }
+
+    /// Number of the changed rows
+    pub fn execute(&mut self, statement: &str, params: &[Value]) -> Result<usize, RepositoryError> {
+        let result = (|| {
+            let mut statement = self.client().prepare_cached(statement)?;
+            bind(&mut statement, params)?;
+            return Ok(statement.raw_execute()?);
+        })();
+        return self.check(result);
+    }
+}
+
+fn bind(statement: &mut Statement, params: &[Value]) -> rusqlite::Result<()> {
+    for (i, value) in params.iter().enumerate() {
+        let name = format!("${}", i + 1);
+        // parameters are bound by name, $2 can appear before $1 or more than once
+        let index = statement
+            .parameter_index(&name)?
+            .ok_or(rusqlite::Error::InvalidParameterName(name))?;
+        statement.raw_bind_parameter(index, value)?;
+    }
+
+    return Ok(());
+}
+
+impl From<rusqlite::Error> for RepositoryError {
+    fn from(error: rusqlite::Error) -> Self {
+        let rusqlite::Error::SqliteFailure(failure, message) = &error else {
+            // errors of the conversion of values are left as they are
+            return RepositoryError::Other(error.into());
+        };
+
+        let message = message.clone().unwrap_or_else(|| error.to_string());
+        if failure.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE
+            || failure.extended_code == ffi::SQLITE_CONSTRAINT_PRIMARYKEY
+        {
+            return RepositoryError::Conflict(message);
+        }
+        if failure.extended_code == ffi::SQLITE_CONSTRAINT_FOREIGNKEY {
+            return RepositoryError::ForeignKey(message);
+        }
+        // another process holds the write lock, the file can't be read or written
+        if matches!(
+            failure.code,
+            ErrorCode::DatabaseBusy
+                | ErrorCode::DatabaseLocked
+                | ErrorCode::CannotOpen
+                | ErrorCode::SystemIoFailure
+                | ErrorCode::DiskFull
+        ) {
+            return RepositoryError::Unavailable(message);
+        }
+
+        return RepositoryError::Other(error.into());
+    }
+}
+
+/// Repository of the table of `T`
+pub fn create_sqlite_repository<T>(database: Database) -> SqliteRepository<T>
+where
+    T: HasId,
+    T: Clone,
+    T: Send + Sync,
+    T: for<'a> TryFrom<&'a Row<'a>, Error = anyhow::Error>,
+    T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
+{
+    return Arc::new(tokio::sync::RwLock::new(
+        self::repository::SqliteRepository::<T>::new(database),
+    ));
+}
+
+impl<T> FromSql for Reference<T>
+where
+    T: HasId,
+{
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        return Id::column_result(value).map(Reference::new);
+    }
+}
+
+impl<T> ToSql for Reference<T>
+where
+    T: HasId,
+{
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        return (**self).to_sql();
+    }
+}
+
+/// Timestamps and dates are written as the text chrono values are read from
+impl ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        return match self {
+            Value::Null => Ok(ToSqlOutput::from(rusqlite::types::Null)),
+            Value::Bool(value) => value.to_sql(),
+            Value::I32(value) => value.to_sql(),
+            Value::I64(value) => value.to_sql(),
+            Value::F64(value) => value.to_sql(),
+            Value::Text(value) => value.to_sql(),
+            Value::Timestamp(value) => value.to_sql(),
+            Value::Date(value) => value.to_sql(),
+        };
+    }
+}
@@ -0,0 +1,215 @@
+use std::marker::PhantomData;
+
+use anyhow::{anyhow, Error, Result};
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::info;
+use rusqlite::Row;
+
+use crate::persistence::query::{Query, QueryBuilder, ToValue, Value};
+use crate::persistence::repository::{HasId, Id, Repository, RepositoryError};
+use crate::persistence::schema::Table;
+
+use super::Database;
+
+pub struct SqliteRepository<T>
+where
+    T: HasId,
+    T: Clone,
+    T: Send + Sync,
+    T: for<'a> TryFrom<&'a Row<'a>, Error = Error>,
+    T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
+{
+    marker: PhantomData<T>,
+    database: Database,
+}
+
+impl<T> SqliteRepository<T>
+where
+    T: HasId,
+    T: Clone,
+    T: Send + Sync,
+    T: for<'a> TryFrom<&'a Row<'a>, Error = Error>,
+    T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
+{
+    pub fn new(database: Database) -> Self {
+        return Self {
+            marker: PhantomData,
+            database,
+        };
+    }
+}
+
+pub struct ColumnValuePair {
+    column: String,
+    value: Value,
+}
+
+impl ColumnValuePair {
+    pub fn new(column: &str, value: &impl ToValue) -> Self {
+        return Self {
+            column: String::from(column),
+            value: value.to_value(),
+        };
+    }
+
+    pub fn column(&self) -> &str {
+        return &self.column;
+    }
+
+    pub fn value(&self) -> &Value {
+        return &self.value;
+    }
+}
+
+#[async_trait]
+impl<T> Repository<T> for SqliteRepository<T>
+where
+    T: HasId,
+    T: Clone,
+    T: Send + Sync,
+    T: for<'a> TryFrom<&'a Row<'a>, Error = Error>,
+    T: TryInto<Vec<ColumnValuePair>, Error = anyhow::Error>,
+    T: Table,
+{
+    async fn get(&self, id: Id) -> Result<Option<T>, RepositoryError> {
+        let statement = format!("SELECT * FROM {} WHERE id = $1 LIMIT 1", T::TABLE);
+        info!("statement: {}", &statement);
+
+        let rows =
+            self.database
+                .connection()
+                .await?
+                .query(&statement, &[id.to_value()], |row| T::try_from(row))?;
+
+        return Ok(rows.into_iter().next());
+    }
+
+    async fn add(&mut self, entity: T) -> Result<Id, RepositoryError> {
+        let column_value_pairs: Vec<ColumnValuePair> = entity.try_into()?;
+
+        let columns = column_value_pairs.iter().map(|it| it.column()).join(", ");
+        let values = (0..column_value_pairs.len())
+            .map(|it| format!("${}", it + 1))
+            .join(", ");
+
+        let statement = format!(
+            "INSERT INTO {} ({}) VALUES ({}) RETURNING id",
+            T::TABLE,
+            columns,
+            values
+        );
+
+        info!("statement: {}", &statement);
+
+        let params = column_value_pairs
+            .into_iter()
+            .map(|it| it.value)
+            .collect::<Vec<_>>();
+
+        let ids = self
+            .database
+            .connection()
+            .await?
+            .query(&statement, &params, |row| Ok(row.get(0)?))?;
+
+        return Ok(ids.into_iter().next().ok_or(anyhow!("no id is returned"))?);
+    }
+
+    async fn delete(&mut self, id: Id) -> Result<(), RepositoryError> {
+        let statement = format!("DELETE FROM {} WHERE id = $1", T::TABLE);
+        if self
+            .database
+            .connection()
+            .await?
+            .execute(&statement, &[id.to_value()])?
+            == 0
+        {
+            return Err(RepositoryError::NotFound(id));
+        }
+
+        return Ok(());
+    }
+
+    async fn update(&mut self, entity: T) -> Result<(), RepositoryError> {
+        let id = entity.get_id().ok_or(anyhow!("entity doesn't have id"))?;
+
+        let column_value_pairs: Vec<ColumnValuePair> = entity.try_into()?;
+
+        let columns = column_value_pairs
+            .iter()
+            .enumerate()
+            .map(|(i, it)| format!("{} = ${}", it.column(), i + 1))
+            .join(", ");
+
+        let statement = format!(
+            "UPDATE {} SET {} WHERE id = ${}",
+            T::TABLE,
+            columns,
+            column_value_pairs.len() + 1
+        );
+
+        info!("statement: {}", &statement);
+
+        let mut params = column_value_pairs
+            .into_iter()
+            .map(|it| it.value)
+            .collect::<Vec<_>>();
+
+        params.push(id.to_value());
+
+        if self
+            .database
+            .connection()
+            .await?
+            .execute(&statement, &params)?
+            == 0
+        {
+            return Err(RepositoryError::NotFound(id));
+        }
+
+        return Ok(());
+    }
+
+    async fn get_all(&self) -> Result<Vec<T>, RepositoryError> {
+        let statement = format!("SELECT * FROM {}", T::TABLE);
+        info!("statement: {}", &statement);
+
+        return self
+            .database
+            .connection()
+            .await?
+            .query(&statement, &[], |row| T::try_from(row));
+    }
+
+    async fn query(&self, query: &Query<T>) -> Result<Vec<T>, RepositoryError> {
+        let mut builder = QueryBuilder::default();
+        let statement = builder.select(T::TABLE, query);
+        info!("statement: {}", &statement);
+
+        return self
+            .database
+            .connection()
+            .await?
+            .query(&statement, builder.params(), |row| T::try_from(row));
+    }
+
+    async fn count(&self, query: &Query<T>) -> Result<usize, RepositoryError> {
+        let mut builder = QueryBuilder::default();
+        let statement = builder.count(T::TABLE, query);
+        info!("statement: {}", &statement);
+
+        let count: i64 = self
+            .database
+            .connection()
+            .await?
+            .query(&statement, builder.params(), |row| Ok(row.get(0)?))?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        return Ok(usize::try_from(count).map_err(anyhow::Error::from)?);
+    }
+}
@@ -0,0 +1,63 @@
+// Schema of the live SQLite tables, see `crate::persistence::schema`
+
+use anyhow::Result;
+
+use crate::persistence::model::tables;
+use crate::persistence::schema::{Dialect, LiveColumn, LiveIndex, LiveTable, SchemaDrift};
+
+use super::Database;
+
+/// Differences of the tables of the database from the models
+pub async fn check(database: Database) -> Result<Vec<SchemaDrift>> {
+    let connection = database.get().await;
+
+    let mut result = Vec::new();
+    for table in tables() {
+        let live = load_table(&connection, table.name)?;
+        result.extend(table.diff(live.as_ref(), Dialect::Sqlite));
+    }
+
+    return Ok(result);
+}
+
+/// None if the table doesn't exist
+fn load_table(connection: &rusqlite::Connection, table: &str) -> Result<Option<LiveTable>> {
+    let columns = connection
+        .prepare("SELECT name, type, \"notnull\" = 0 AND pk = 0 FROM pragma_table_info($1)")?
+        .query_map([table], |row| {
+            Ok(LiveColumn {
+                name: row.get(0)?,
+                sql_type: row.get(1)?,
+                nullable: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    let references = connection
+        .prepare("SELECT \"from\", \"table\" FROM pragma_foreign_key_list($1)")?
+        .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // unique keys are enforced by automatic indexes, the one of the primary key is left out
+    let names = connection
+        .prepare("SELECT name, \"unique\" FROM pragma_index_list($1) WHERE origin != 'pk'")?
+        .query_map([table], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut indexes = Vec::new();
+    for (name, unique) in names {
+        let columns = connection
+            .prepare("SELECT name FROM pragma_index_info($1) ORDER BY seqno")?
+            .query_map([&name], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        indexes.push(LiveIndex { columns, unique });
+    }
+
+    return Ok(Some(LiveTable {
+        columns,
+        references,
+        indexes,
+    }));
+}
@@ -0,0 +1,177 @@
+// Groups the repository operations of a piece of work into one transaction. `transaction` runs a
+// future with a unit of work bound to its task, the repositories look it up on every operation:
+// the Postgres and SQLite ones run their statements on a single connection of the unit on which
+// BEGIN was issued, the in-memory ones record the previous state of every record they change and
+// restore it on rollback. Changes of in-memory repositories are visible to other tasks before the
+// commit. The SQLite connection is held by the unit until it is done, so the work must not wait
+// for other tasks which use the SQLite repositories.
+//
+// The unit of work is bound to the task, operations of spawned tasks aren't part of it.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use log::warn;
+
+use super::repository::RepositoryError;
+
+tokio::task_local! {
+    static CURRENT: Arc<UnitOfWork>;
+}
+
+/// Restores the records of an in-memory repository changed by one operation
+type Undo = Box<dyn FnOnce() + Send>;
+
+/// Connection with an open transaction, `failed` is set once a statement fails since Postgres
+/// answers COMMIT of a failed transaction with a rollback instead of an error
+#[cfg(feature = "postgres")]
+pub struct PostgresTransaction {
+    pub connection: deadpool_postgres::Object,
+    pub failed: bool,
+}
+
+/// The shared SQLite connection with an open transaction, see `PostgresTransaction`
+#[cfg(feature = "sqlite")]
+pub struct SqliteTransaction {
+    pub connection: tokio::sync::OwnedMutexGuard<rusqlite::Connection>,
+    pub failed: bool,
+}
+
+#[derive(Default)]
+pub struct UnitOfWork {
+    undo: Mutex<Vec<Undo>>,
+    #[cfg(feature = "postgres")]
+    pub(super) postgres: Arc<tokio::sync::Mutex<Option<PostgresTransaction>>>,
+    #[cfg(feature = "sqlite")]
+    pub(super) sqlite: Arc<tokio::sync::Mutex<Option<SqliteTransaction>>>,
+}
+
+impl UnitOfWork {
+    /// Unit of work of the current task, None outside of `transaction`
+    pub fn current() -> Option<Arc<UnitOfWork>> {
+        return CURRENT.try_with(|it| it.clone()).ok();
+    }
+
+    /// `undo` runs on rollback, the ones registered later run first
+    pub fn on_rollback(&self, undo: impl FnOnce() + Send + 'static) {
+        self.undo.lock().unwrap().push(Box::new(undo));
+    }
+
+    async fn commit(&self) -> Result<(), RepositoryError> {
+        #[cfg(feature = "postgres")]
+        if let Some(transaction) = self.postgres.lock().await.take() {
+            if transaction.failed {
+                Self::rollback_postgres(transaction).await;
+                self.undo();
+                return Err(Self::failed());
+            }
+
+            if let Err(error) = transaction.connection.batch_execute("COMMIT").await {
+                self.undo();
+                return Err(error.into());
+            }
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some(transaction) = self.sqlite.lock().await.take() {
+            if transaction.failed {
+                Self::rollback_sqlite(transaction);
+                self.undo();
+                return Err(Self::failed());
+            }
+
+            // unlike Postgres, SQLite keeps the transaction open if COMMIT fails
+            if let Err(error) = transaction.connection.execute_batch("COMMIT") {
+                Self::rollback_sqlite(transaction);
+                self.undo();
+                return Err(error.into());
+            }
+        }
+
+        self.undo.lock().unwrap().clear();
+        return Ok(());
+    }
+
+    async fn rollback(&self) {
+        #[cfg(feature = "postgres")]
+        if let Some(transaction) = self.postgres.lock().await.take() {
+            Self::rollback_postgres(transaction).await;
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some(transaction) = self.sqlite.lock().await.take() {
+            Self::rollback_sqlite(transaction);
+        }
+
+        self.undo();
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    fn failed() -> RepositoryError {
+        return RepositoryError::Other(anyhow::anyhow!(
+            "transaction is rolled back, a statement of it failed"
+        ));
+    }
+
+    #[cfg(feature = "postgres")]
+    async fn rollback_postgres(transaction: PostgresTransaction) {
+        if let Err(error) = transaction.connection.batch_execute("ROLLBACK").await {
+            // the server rolls the transaction back once the connection is closed
+            warn!("can't roll back transaction: {}", error);
+            drop(deadpool_postgres::Object::take(transaction.connection));
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn rollback_sqlite(transaction: SqliteTransaction) {
+        if let Err(error) = transaction.connection.execute_batch("ROLLBACK") {
+            warn!("can't roll back transaction: {}", error);
+        }
+    }
+
+    fn undo(&self) {
+        let undo = std::mem::take(&mut *self.undo.lock().unwrap());
+        for it in undo.into_iter().rev() {
+            it();
+        }
+    }
+}
+
+impl Drop for UnitOfWork {
+    /// The work was cancelled or panicked before it was committed or rolled back
+    fn drop(&mut self) {
+        #[cfg(feature = "postgres")]
+        if let Some(transaction) = self.postgres.try_lock().ok().and_then(|mut it| it.take()) {
+            drop(deadpool_postgres::Object::take(transaction.connection));
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some(transaction) = self.sqlite.try_lock().ok().and_then(|mut it| it.take()) {
+            Self::rollback_sqlite(transaction);
+        }
+
+        self.undo();
+    }
+}
+
+/// Runs `work` in a unit of work: its repository operations are committed if it succeeds and
+/// rolled back if it fails. Work started inside of another unit of work joins it.
+pub async fn transaction<T>(work: impl Future<Output = Result<T>>) -> Result<T> {
+    if UnitOfWork::current().is_some() {
+        return work.await;
+    }
+
+    let unit = Arc::new(UnitOfWork::default());
+    return match CURRENT.scope(unit.clone(), work).await {
+        Ok(result) => {
+            unit.commit().await?;
+            Ok(result)
+        }
+        Err(error) => {
+            unit.rollback().await;
+            Err(error)
+        }
+    };
+}
@@ -5,13 +5,19 @@ use tokio_cron_scheduler::JobScheduler;
 
 use crate::{
     persistence::{
+        journal::Journals,
         model::{
-            instrument::Instrument, instrument_data::InstrumentData, oceancolor::OceanColorMapping,
-            satellite::Satellite, satellite_instrument::SatelliteInstrument,
+            ground_station::GroundStation, instrument::Instrument, instrument_data::InstrumentData,
+            oceancolor::OceanColorMapping, satellite::Satellite,
+            satellite_catalog::SatelliteCatalog, satellite_element_set::SatelliteElementSet,
+            satellite_instrument::SatelliteInstrument,
         },
         Repository,
     },
-    service::{CelestrakService, InstrumentDataService, OceanColorService, SatelliteService},
+    service::{
+        CelestrakService, ElementSetService, GroundStationService, InstrumentDataService,
+        InstrumentService, OceanColorService, PropagationService, SatcatService, SatelliteService,
+    },
 };
 
 pub struct AppContext {
@@ -21,20 +27,34 @@ pub struct AppContext {
     pub instrument_data_repository: Repository<InstrumentData>,
 
     pub oceancolor_mapping_repository: Repository<OceanColorMapping>,
+    pub ground_station_repository: Repository<GroundStation>,
+    pub satellite_element_set_repository: Repository<SatelliteElementSet>,
+    pub satellite_catalog_repository: Repository<SatelliteCatalog>,
 
     pub satellite_service: SatelliteService,
     pub celestrak_service: CelestrakService,
     pub instrument_data_service: InstrumentDataService,
     pub oceancolor_service: OceanColorService,
+    pub element_set_service: ElementSetService,
+    pub propagation_service: PropagationService,
+    pub ground_station_service: GroundStationService,
+    pub satcat_service: SatcatService,
+    pub instrument_service: InstrumentService,
 
     pub job_scheduler: JobScheduler,
+    /// Of the in-memory repositories, empty with a database
+    pub journals: Journals,
 }
 
 pub fn create_router(ctx: Arc<AppContext>) -> Router {
     let satellite_router = crate::controller::satellite::create_router(ctx.clone());
     let satellite_data_router = crate::controller::instrument_data::create_router(ctx.clone());
+    let ground_station_router = crate::controller::ground_station::create_router(ctx.clone());
+    let instrument_router = crate::controller::instrument::create_router(ctx.clone());
 
     return Router::new()
         .merge(satellite_router)
-        .merge(satellite_data_router);
+        .merge(satellite_data_router)
+        .merge(ground_station_router)
+        .merge(instrument_router);
 }
@@ -1,21 +1,30 @@
-use std::collections::HashMap;
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use super::job::Job;
 use crate::persistence::Repository;
 use crate::persistence::{model::satellite::Satellite, repository::HasId};
+use crate::utils::element_set::ElementSet;
+use crate::utils::omm::{self, OmmError};
+use crate::utils::tle::{self, TleError, TLE};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use log::{error, info, trace, warn};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
+#[derive(Clone)]
 pub enum Query {
     /// Catalog Number (1 to 9 digits). Allows return of data for a single catalog number.
     CATNR(u32),
 
+    /// Several catalog numbers, sent as a comma-separated CATNR list.
+    CATNRS(Vec<u32>),
+
     /// International Designator (yyyy-nnn). Allows return of data for all objects associated with a particular launch.
     INTDES(String),
 
@@ -29,24 +38,44 @@ pub enum Query {
     SPECIAL(String),
 }
 
-#[derive(Debug)]
-pub struct TLE {
-    pub name: String,
-    pub tle1: String,
-    pub tle2: String,
-}
-
-impl TLE {
-    pub fn new(name: &str, tle1: &str, tle2: &str) -> TLE {
-        return Self {
-            name: String::from(name),
-            tle1: String::from(tle1),
-            tle2: String::from(tle2),
+impl Query {
+    /// Query parameter of the Celestrak APIs
+    pub fn as_param(&self) -> (&'static str, String) {
+        return match self {
+            Query::CATNR(catnr) => ("CATNR", catnr.to_string()),
+            Query::CATNRS(catnrs) => ("CATNR", catnrs.iter().join(",")),
+            Query::INTDES(intdes) => ("INTDES", intdes.clone()),
+            Query::GROUP(group) => ("GROUP", group.clone()),
+            Query::NAME(name) => ("NAME", name.clone()),
+            Query::SPECIAL(special) => ("SPECIAL", special.clone()),
         };
     }
+}
+
+/// Encoding of the returned element sets.
+#[derive(Clone, Copy)]
+pub enum Format {
+    /// Three-line element sets, only for catalog numbers up to 99999.
+    TLE,
 
-    pub fn get_catnr(&self) -> Result<u32> {
-        return Ok(u32::from_str(&self.tle1[2..7])?);
+    /// CCSDS OMM encoded as JSON.
+    JSON,
+
+    /// CCSDS OMM encoded as XML.
+    XML,
+
+    /// CCSDS OMM keywords as CSV columns.
+    CSV,
+}
+
+impl Format {
+    fn as_param(&self) -> &'static str {
+        return match self {
+            Format::TLE => "TLE",
+            Format::JSON => "JSON",
+            Format::XML => "XML",
+            Format::CSV => "CSV",
+        };
     }
 }
 
@@ -56,74 +85,159 @@ pub enum CelestrakError {
     InvalidQuery,
     #[error("invalid output format")]
     InvalidOutputFormat,
+    #[error("invalid element set: {0}")]
+    InvalidElementSet(#[from] TleError),
+    #[error("invalid orbit mean-elements message: {0}")]
+    InvalidOmm(#[from] OmmError),
+    #[error("historical element sets aren't provided")]
+    HistoryNotSupported,
 }
 
 #[async_trait]
 pub trait CelestrakService {
-    async fn gp_query(&self, query: Query) -> Result<Vec<TLE>>;
+    async fn gp_query(&self, query: Query, format: Format) -> Result<Vec<ElementSet>>;
+
+    /// Element sets of an object with epochs in [from; to], sorted by epoch
+    async fn gp_history(
+        &self,
+        _catnr: u32,
+        _from: DateTime<Utc>,
+        _to: DateTime<Utc>,
+    ) -> Result<Vec<ElementSet>> {
+        return Err(anyhow!(CelestrakError::HistoryNotSupported));
+    }
 }
 
+/// Maximum number of catalog numbers requested at once
+const CATNR_BATCH_SIZE: usize = 100;
+
+/// Refreshes element sets of stored satellites. Configured catalogs (GROUP/SPECIAL queries)
+/// are fetched first, satellites which are not in any of them are requested in CATNR batches.
 pub struct CelestrakJob {
     celestrak_service: super::CelestrakService,
+    satellite_service: super::SatelliteService,
     satellite_repository: Repository<Satellite>,
+    catalogs: Vec<Query>,
 }
 
 impl CelestrakJob {
     pub fn new(
         celestrak_service: super::CelestrakService,
+        satellite_service: super::SatelliteService,
         satellite_repository: Repository<Satellite>,
+        catalogs: Vec<Query>,
     ) -> Self {
         return Self {
             celestrak_service,
+            satellite_service,
             satellite_repository,
+            catalogs,
         };
     }
+
+    /// Element sets with the requested catalog numbers, a failed query doesn't stop the others
+    async fn fetch(&self, catnrs: &HashSet<u32>) -> HashMap<u32, ElementSet> {
+        let mut result = HashMap::new();
+
+        for catalog in &self.catalogs {
+            match self
+                .celestrak_service
+                .gp_query(catalog.clone(), Format::TLE)
+                .await
+            {
+                Ok(element_sets) => result.extend(
+                    element_sets
+                        .into_iter()
+                        .filter(|it| catnrs.contains(&it.get_catnr()))
+                        .map(|it| (it.get_catnr(), it)),
+                ),
+                Err(err) => error!("celestrak catalog query failed: {}", err),
+            }
+        }
+
+        // TLE isn't provided for catalog numbers which don't fit into 5 digits
+        let (short, long): (Vec<u32>, Vec<u32>) = catnrs
+            .iter()
+            .filter(|it| !result.contains_key(it))
+            .sorted()
+            .partition(|it| **it < 100000);
+
+        for (catnrs, format) in [(short, Format::TLE), (long, Format::JSON)] {
+            for batch in catnrs.chunks(CATNR_BATCH_SIZE) {
+                match self
+                    .celestrak_service
+                    .gp_query(Query::CATNRS(batch.to_vec()), format)
+                    .await
+                {
+                    Ok(element_sets) => {
+                        result.extend(element_sets.into_iter().map(|it| (it.get_catnr(), it)))
+                    }
+                    Err(err) => error!("celestrak catnr query failed: {}", err),
+                }
+            }
+        }
+
+        return result;
+    }
 }
 
 #[async_trait]
 impl Job for CelestrakJob {
-    // TODO: it can be optimized
     async fn job_func(ctx: Arc<RwLock<Self>>) -> Result<()> {
-        let satellites = ctx
-            .read()
-            .await
-            .satellite_repository
-            .read()
-            .await
-            .get_all()
-            .await?;
+        let job = ctx.read().await;
+
+        let satellites = job.satellite_repository.read().await.get_all().await?;
+
+        let catnrs = satellites
+            .iter()
+            .filter_map(|it| it.get_catnr())
+            .filter_map(|it| u32::try_from(it).ok())
+            .collect::<HashSet<_>>();
+        let mut fetched = job.fetch(&catnrs).await;
 
-        for mut satellite in satellites {
+        for satellite in satellites {
             let id = satellite.get_id().context("satellite ID expected")?;
 
-            let tle = if let Some(catnr) = satellite.get_catnr() {
-                ctx.read()
-                    .await
-                    .celestrak_service
-                    .gp_query(Query::CATNR((catnr).try_into()?))
-                    .await?
-                    .into_iter()
-                    .exactly_one()?
-            } else {
-                trace!("there no catnr for satellite with id({})", id);
-                continue;
+            let element_set = match satellite
+                .get_catnr()
+                .and_then(|it| u32::try_from(it).ok())
+                .and_then(|it| fetched.remove(&it))
+            {
+                Some(element_set) => element_set,
+                None => {
+                    trace!("there no element set for satellite with id({})", id);
+                    continue;
+                }
             };
 
-            satellite.set_tle1(tle.tle1);
-            satellite.set_tle2(tle.tle2);
+            let current = match satellite.get_element_set() {
+                Ok(current) => current,
+                Err(error) => {
+                    error!("tle of satellite with id({}) is invalid: {:#}", id, error);
+                    continue;
+                }
+            };
+            if let Some(current) = current {
+                if current.get_epoch() >= element_set.get_epoch() {
+                    trace!("tle for satellite with id({}) is up to date", id);
+                    continue;
+                }
+            }
 
-            if !ctx
-                .read()
-                .await
-                .satellite_repository
-                .write()
+            // the satellite is read again and written with its history in one unit of work, the
+            // changes made since the satellites were read are kept
+            match job
+                .satellite_service
+                .update(id, None, Some(element_set))
                 .await
-                .update(satellite)
-                .await?
             {
-                warn!("tle for satellite with id({}) is not updated", id);
-            } else {
-                info!("tle for satellite with id({}) is updated", id);
+                Ok(Some(_)) => info!("tle for satellite with id({}) is updated", id),
+                // deleted since it was read
+                Ok(None) => warn!("tle for satellite with id({}) is not updated", id),
+                Err(error) => error!(
+                    "tle for satellite with id({}) is not updated: {:#}",
+                    id, error
+                ),
             }
         }
 
@@ -131,29 +245,31 @@ impl Job for CelestrakJob {
     }
 }
 
-pub struct CelestrakServiceDefault {}
+pub const CELESTRAK_URL: &str = "https://celestrak.org";
+
+pub struct CelestrakServiceDefault {
+    base_url: String,
+}
 
 impl CelestrakServiceDefault {
-    pub fn new() -> Self {
-        return Self {};
+    /// `base_url` is the scheme and host of Celestrak or a stand-in, e.g. `CELESTRAK_URL`
+    pub fn new(base_url: &str) -> Self {
+        return Self {
+            base_url: String::from(base_url.trim_end_matches('/')),
+        };
     }
 }
 
 #[async_trait]
 impl CelestrakService for CelestrakServiceDefault {
-    async fn gp_query(&self, query: Query) -> Result<Vec<TLE>> {
+    async fn gp_query(&self, query: Query, format: Format) -> Result<Vec<ElementSet>> {
         let mut params = HashMap::<&str, String>::new();
-        match query {
-            Query::CATNR(catnr) => params.insert("CATNR", catnr.to_string()),
-            Query::INTDES(intdes) => params.insert("INTDES", intdes),
-            Query::GROUP(group) => params.insert("GROUP", group),
-            Query::NAME(name) => params.insert("NAME", name),
-            Query::SPECIAL(special) => params.insert("SPECIAL", special),
-        };
-        params.insert("FORMAT", String::from("TLE"));
+        let (key, value) = query.as_param();
+        params.insert(key, value);
+        params.insert("FORMAT", String::from(format.as_param()));
 
         let response = reqwest::Client::new()
-            .get("https://celestrak.org/NORAD/elements/gp.php")
+            .get(format!("{}/NORAD/elements/gp.php", self.base_url))
             .query(&params)
             .send()
             .await?;
@@ -170,15 +286,157 @@ impl CelestrakService for CelestrakServiceDefault {
             }
         }
 
-        if lines.len() % 3 != 0 {
-            return Err(anyhow!(CelestrakError::InvalidOutputFormat));
+        let omms = match format {
+            Format::TLE => {
+                if lines.len() % 3 != 0 {
+                    return Err(anyhow!(CelestrakError::InvalidOutputFormat));
+                }
+
+                let tles = lines
+                    .chunks_exact(3)
+                    .map(|slice| TLE::new(slice[0], slice[1], slice[2]).map(ElementSet::from))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(CelestrakError::InvalidElementSet)?;
+
+                return Ok(tles);
+            }
+            Format::JSON => omm::parse_json(&text),
+            Format::XML => omm::parse_xml(&text),
+            Format::CSV => omm::parse_csv(&text),
+        }
+        .map_err(CelestrakError::InvalidOmm)?;
+
+        return Ok(omms.into_iter().map(ElementSet::from).collect());
+    }
+}
+
+/// Detects the encoding of a catalog file: OMM as JSON array, XML or CSV with a header row, else 3LE
+fn parse_catalog(text: &str) -> Result<Vec<ElementSet>, CelestrakError> {
+    let start = text.trim_start();
+
+    let omms = if start.starts_with('[') {
+        omm::parse_json(text)
+    } else if start.starts_with('<') {
+        omm::parse_xml(text)
+    } else if start.starts_with("OBJECT_NAME") || start.starts_with("CCSDS_OMM_VERS") {
+        omm::parse_csv(text)
+    } else {
+        return Ok(tle::parse_3le(text)?
+            .into_iter()
+            .map(ElementSet::from)
+            .collect());
+    }?;
+
+    return Ok(omms.into_iter().map(ElementSet::from).collect());
+}
+
+/// Modification time and length of a file, None if it can't be read
+type FileStamp = Option<(SystemTime, u64)>;
+
+#[derive(Default)]
+struct FileCatalog {
+    /// None until the files are loaded for the first time
+    stamps: Option<Vec<FileStamp>>,
+    element_sets: Vec<ElementSet>,
+}
+
+/// Answers queries from local 3LE and OMM (JSON, XML, CSV) files, e.g. a Celestrak snapshot for
+/// air-gapped deployments. The files are read again once any of them changes on disk.
+/// A snapshot has no groups, so GROUP and SPECIAL queries return all of it.
+pub struct CelestrakServiceFile {
+    paths: Vec<PathBuf>,
+    catalog: RwLock<FileCatalog>,
+}
+
+impl CelestrakServiceFile {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        return Self {
+            paths,
+            catalog: RwLock::new(FileCatalog::default()),
+        };
+    }
+
+    fn get_stamps(&self) -> Vec<FileStamp> {
+        return self
+            .paths
+            .iter()
+            .map(|path| {
+                let metadata = std::fs::metadata(path).ok()?;
+                return Some((metadata.modified().ok()?, metadata.len()));
+            })
+            .collect();
+    }
+
+    /// Newest element set of every catalog number found in the files
+    async fn get_element_sets(&self) -> Result<Vec<ElementSet>> {
+        // stamps are taken before reading, so a change during the reload triggers another one
+        let stamps = self.get_stamps();
+        {
+            let catalog = self.catalog.read().await;
+            if catalog.stamps.as_ref() == Some(&stamps) {
+                return Ok(catalog.element_sets.clone());
+            }
+        }
+
+        let mut newest = HashMap::<u32, ElementSet>::new();
+        for path in &self.paths {
+            let text = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("failed to read {}", path.display()))?;
+
+            for element_set in parse_catalog(&text)? {
+                let catnr = element_set.get_catnr();
+                if newest
+                    .get(&catnr)
+                    .is_none_or(|it| it.get_epoch() < element_set.get_epoch())
+                {
+                    newest.insert(catnr, element_set);
+                }
+            }
         }
 
-        let tles = lines
-            .chunks_exact(3)
-            .map(|slice| TLE::new(slice[0], slice[1], slice[2]))
+        let element_sets = newest
+            .into_values()
+            .sorted_by_key(|it| it.get_catnr())
             .collect::<Vec<_>>();
+        info!("loaded {} element sets from files", element_sets.len());
+
+        *self.catalog.write().await = FileCatalog {
+            stamps: Some(stamps),
+            element_sets: element_sets.clone(),
+        };
+
+        return Ok(element_sets);
+    }
+}
+
+#[async_trait]
+impl CelestrakService for CelestrakServiceFile {
+    /// Element sets are returned in the encoding of the file, regardless of `format`
+    async fn gp_query(&self, query: Query, _format: Format) -> Result<Vec<ElementSet>> {
+        let element_sets = self.get_element_sets().await?;
 
-        return Ok(tles);
+        return Ok(match query {
+            Query::CATNR(catnr) => element_sets
+                .into_iter()
+                .filter(|it| it.get_catnr() == catnr)
+                .collect(),
+            Query::CATNRS(catnrs) => element_sets
+                .into_iter()
+                .filter(|it| catnrs.contains(&it.get_catnr()))
+                .collect(),
+            Query::INTDES(intdes) => element_sets
+                .into_iter()
+                .filter(|it| it.get_international_designator().starts_with(&intdes))
+                .collect(),
+            Query::NAME(name) => {
+                let name = name.to_uppercase();
+                element_sets
+                    .into_iter()
+                    .filter(|it| it.get_name().to_uppercase().contains(&name))
+                    .collect()
+            }
+            Query::GROUP(_) | Query::SPECIAL(_) => element_sets,
+        });
     }
 }
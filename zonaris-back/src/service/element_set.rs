@@ -0,0 +1,203 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{
+    persistence::{
+        model::{satellite::Satellite, satellite_element_set::SatelliteElementSet},
+        query::Query,
+        repository::{HasId, Id, RepositoryError},
+        Repository,
+    },
+    utils::element_set::ElementSet,
+};
+
+#[derive(Error, Debug)]
+pub enum ElementSetError {
+    #[error("satellite with id {0} has no element set")]
+    Missing(Id),
+}
+
+#[async_trait]
+pub trait ElementSetService {
+    /// false if element set with the same epoch is already recorded for the satellite
+    async fn add(&self, satellite_id: Id, element_set: &ElementSet) -> Result<bool>;
+
+    /// None if satellite with given id not found, element sets are sorted by epoch and filtered by [from; to]
+    async fn get_history(
+        &self,
+        satellite_id: Id,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<ElementSet>>>;
+
+    /// None if satellite with given id not found, else the element set with the epoch closest to `time`,
+    /// `ElementSetError::Missing` if the satellite has none
+    async fn get_closest(
+        &self,
+        satellite_id: Id,
+        time: DateTime<Utc>,
+    ) -> Result<Option<ElementSet>>;
+
+    /// Removes the recorded history of the satellite, returns the number of removed element sets
+    async fn delete_all(&self, satellite_id: Id) -> Result<usize>;
+}
+
+pub struct ElementSetServiceDefault {
+    satellite_repository: Repository<Satellite>,
+    satellite_element_set_repository: Repository<SatelliteElementSet>,
+}
+
+impl ElementSetServiceDefault {
+    pub fn new(
+        satellite_repository: Repository<Satellite>,
+        satellite_element_set_repository: Repository<SatelliteElementSet>,
+    ) -> Self {
+        return Self {
+            satellite_repository,
+            satellite_element_set_repository,
+        };
+    }
+
+    async fn get_satellite(&self, satellite_id: Id) -> Result<Option<Satellite>> {
+        return Ok(self
+            .satellite_repository
+            .read()
+            .await
+            .get(satellite_id)
+            .await?);
+    }
+
+    /// Recorded element sets of the satellite matching `query`
+    async fn get_records(
+        &self,
+        satellite_id: Id,
+        query: Query<SatelliteElementSet>,
+    ) -> Result<Vec<SatelliteElementSet>> {
+        return Ok(self
+            .satellite_element_set_repository
+            .read()
+            .await
+            .query(&query.filter(SatelliteElementSet::SATELLITE_ID.eq(satellite_id)))
+            .await?);
+    }
+}
+
+fn decode(satellite: &Satellite, records: Vec<SatelliteElementSet>) -> Result<Vec<ElementSet>> {
+    let mut result = Vec::new();
+    for record in records {
+        if let Some(element_set) = record.get_element_set(satellite.get_name())? {
+            result.push(element_set);
+        }
+    }
+
+    return Ok(result);
+}
+
+#[async_trait]
+impl ElementSetService for ElementSetServiceDefault {
+    async fn add(&self, satellite_id: Id, element_set: &ElementSet) -> Result<bool> {
+        let query = Query::new()
+            .filter(SatelliteElementSet::SATELLITE_ID.eq(satellite_id))
+            .filter(SatelliteElementSet::EPOCH.eq(*element_set.get_epoch()));
+        // the lock is held from the check to the insert, the unique key of the SQL backends
+        // catches the adds of other processes
+        let mut repository = self.satellite_element_set_repository.write().await;
+        if repository.count(&query).await? > 0 {
+            return Ok(false);
+        }
+
+        return match repository
+            .add(SatelliteElementSet::new(satellite_id, element_set))
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(RepositoryError::Conflict(_)) => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    async fn get_history(
+        &self,
+        satellite_id: Id,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<ElementSet>>> {
+        let satellite = match self.get_satellite(satellite_id).await? {
+            Some(satellite) => satellite,
+            None => return Ok(None),
+        };
+
+        let mut query = Query::new().order_by(SatelliteElementSet::EPOCH.asc());
+        if let Some(from) = from {
+            query = query.filter(SatelliteElementSet::EPOCH.ge(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(SatelliteElementSet::EPOCH.le(to));
+        }
+        let mut element_sets = decode(&satellite, self.get_records(satellite_id, query).await?)?;
+
+        // the current element set may be not recorded yet (e.g. satellite was just added)
+        if let Some(current) = satellite.get_element_set()? {
+            let epoch = *current.get_epoch();
+            if from.map(|from| epoch >= from).unwrap_or(true)
+                && to.map(|to| epoch <= to).unwrap_or(true)
+                && !element_sets.iter().any(|it| *it.get_epoch() == epoch)
+            {
+                element_sets.push(current);
+                element_sets.sort_by_key(|it| *it.get_epoch());
+            }
+        }
+
+        return Ok(Some(element_sets));
+    }
+
+    async fn get_closest(
+        &self,
+        satellite_id: Id,
+        time: DateTime<Utc>,
+    ) -> Result<Option<ElementSet>> {
+        let satellite = match self.get_satellite(satellite_id).await? {
+            Some(satellite) => satellite,
+            None => return Ok(None),
+        };
+
+        // the closest recorded element set is the last one before `time` or the first one after
+        let before = Query::new()
+            .filter(SatelliteElementSet::EPOCH.le(time))
+            .order_by(SatelliteElementSet::EPOCH.desc())
+            .limit(1);
+        let after = Query::new()
+            .filter(SatelliteElementSet::EPOCH.ge(time))
+            .order_by(SatelliteElementSet::EPOCH.asc())
+            .limit(1);
+
+        let mut records = self.get_records(satellite_id, before).await?;
+        records.extend(self.get_records(satellite_id, after).await?);
+
+        let mut candidates = decode(&satellite, records)?;
+        // the current element set may be not recorded yet
+        candidates.extend(satellite.get_element_set()?);
+
+        let closest = candidates
+            .into_iter()
+            .min_by_key(|it| (*it.get_epoch() - time).abs())
+            .ok_or(ElementSetError::Missing(satellite_id))?;
+
+        return Ok(Some(closest));
+    }
+
+    async fn delete_all(&self, satellite_id: Id) -> Result<usize> {
+        let records = self.get_records(satellite_id, Query::new()).await?;
+
+        let mut repository = self.satellite_element_set_repository.write().await;
+        for record in &records {
+            if let Some(id) = record.get_id() {
+                repository.delete(id).await?;
+            }
+        }
+
+        return Ok(records.len());
+    }
+}
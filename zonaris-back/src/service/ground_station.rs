@@ -0,0 +1,281 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    persistence::{model::ground_station::GroundStation, repository::Id, Repository},
+    utils::{geodesy::Geodetic, sgp4::Sgp4},
+};
+
+use super::propagation::{create_model, look_angles_at};
+use super::ElementSetService;
+
+const SCAN_STEP_SECONDS: i64 = 30;
+const REFINE_PRECISION_MILLISECONDS: i64 = 100;
+
+/// How far outside of the requested window the rise and set of a pass in progress are looked for.
+const MAX_PASS_DURATION_HOURS: i64 = 24;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PassEvent {
+    pub time: DateTime<Utc>,
+    pub azimuth: f64,
+    pub elevation: f64,
+}
+
+/// Acquisition of signal, time of closest approach (maximum elevation) and loss of signal.
+#[derive(Clone, Copy, Debug)]
+pub struct Pass {
+    pub aos: PassEvent,
+    pub tca: PassEvent,
+    pub los: PassEvent,
+}
+
+struct PassFinder<'a> {
+    model: &'a Sgp4,
+    observer: &'a Geodetic,
+    min_elevation: f64,
+}
+
+impl<'a> PassFinder<'a> {
+    fn event(&self, time: DateTime<Utc>) -> Result<PassEvent> {
+        let angles = look_angles_at(self.model, self.observer, time)?;
+        return Ok(PassEvent {
+            time,
+            azimuth: angles.azimuth,
+            elevation: angles.elevation,
+        });
+    }
+
+    fn is_visible(&self, time: DateTime<Utc>) -> Result<bool> {
+        return Ok(self.event(time)?.elevation > self.min_elevation);
+    }
+
+    /// Bisects the moment the visibility changes, `from` and `to` should have different visibility.
+    fn refine_crossing(
+        &self,
+        mut from: DateTime<Utc>,
+        mut to: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>> {
+        let visible_at_from = self.is_visible(from)?;
+
+        while to - from > Duration::milliseconds(REFINE_PRECISION_MILLISECONDS) {
+            let middle = from + (to - from) / 2;
+            if self.is_visible(middle)? == visible_at_from {
+                from = middle;
+            } else {
+                to = middle;
+            }
+        }
+
+        return Ok(from + (to - from) / 2);
+    }
+
+    /// Rise time of the pass which is in progress at `time`, clipped to `limit`.
+    fn find_rise_before(&self, time: DateTime<Utc>, limit: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let step = Duration::seconds(SCAN_STEP_SECONDS);
+        let mut current = time;
+
+        while current > limit {
+            let previous = std::cmp::max(current - step, limit);
+            if !self.is_visible(previous)? {
+                return self.refine_crossing(previous, current);
+            }
+            current = previous;
+        }
+
+        return Ok(limit);
+    }
+
+    /// Golden-section search of the maximum elevation between rise and set.
+    fn find_culmination(&self, aos: DateTime<Utc>, los: DateTime<Utc>) -> Result<PassEvent> {
+        let step = Duration::seconds(SCAN_STEP_SECONDS);
+
+        let mut best = self.event(aos)?;
+        let mut time = aos;
+        while time < los {
+            time = std::cmp::min(time + step, los);
+            let event = self.event(time)?;
+            if event.elevation > best.elevation {
+                best = event;
+            }
+        }
+
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let mut a = std::cmp::max(best.time - step, aos);
+        let mut b = std::cmp::min(best.time + step, los);
+
+        while b - a > Duration::milliseconds(REFINE_PRECISION_MILLISECONDS) {
+            let offset = (b - a).num_milliseconds() as f64 * ratio;
+            let c = b - Duration::milliseconds(offset as i64);
+            let d = a + Duration::milliseconds(offset as i64);
+
+            if self.event(c)?.elevation > self.event(d)?.elevation {
+                b = d;
+            } else {
+                a = c;
+            }
+        }
+
+        let refined = self.event(a + (b - a) / 2)?;
+        return Ok(if refined.elevation > best.elevation {
+            refined
+        } else {
+            best
+        });
+    }
+
+    fn build_pass(&self, aos: DateTime<Utc>, los: DateTime<Utc>) -> Result<Pass> {
+        return Ok(Pass {
+            aos: self.event(aos)?,
+            tca: self.find_culmination(aos, los)?,
+            los: self.event(los)?,
+        });
+    }
+}
+
+/// Predicts passes of the satellite over the observer which are in progress during [from; to].
+/// Rise and set of passes overlapping the window boundaries are searched outside of the window.
+pub fn predict_passes(
+    model: &Sgp4,
+    observer: &Geodetic,
+    min_elevation: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Pass>> {
+    let finder = PassFinder {
+        model,
+        observer,
+        min_elevation,
+    };
+
+    let step = Duration::seconds(SCAN_STEP_SECONDS);
+    let limit = Duration::hours(MAX_PASS_DURATION_HOURS);
+
+    let mut passes = Vec::new();
+    let mut time = from;
+    let mut visible = finder.is_visible(time)?;
+    let mut aos = if visible {
+        Some(finder.find_rise_before(time, from - limit)?)
+    } else {
+        None
+    };
+
+    while time < to || (aos.is_some() && time < to + limit) {
+        let next = time + step;
+        let next_visible = finder.is_visible(next)?;
+
+        if !visible && next_visible {
+            let rise = finder.refine_crossing(time, next)?;
+            if rise > to {
+                break;
+            }
+            aos = Some(rise);
+        } else if visible && !next_visible {
+            let set = finder.refine_crossing(time, next)?;
+            if let Some(rise) = aos.take() {
+                passes.push(finder.build_pass(rise, set)?);
+            }
+        }
+
+        time = next;
+        visible = next_visible;
+    }
+
+    // the satellite doesn't set in a reasonable time (e.g. geostationary)
+    if let Some(rise) = aos {
+        passes.push(finder.build_pass(rise, time)?);
+    }
+
+    return Ok(passes);
+}
+
+#[async_trait]
+pub trait GroundStationService {
+    async fn get_all(&self) -> Result<Vec<GroundStation>>;
+    async fn add(&self, ground_station: GroundStation) -> Result<Id>;
+
+    /// None if ground station or satellite with given id not found
+    async fn get_passes(
+        &self,
+        ground_station_id: Id,
+        satellite_id: Id,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<Vec<Pass>>>;
+}
+
+pub struct GroundStationServiceDefault {
+    ground_station_repository: Repository<GroundStation>,
+    element_set_service: ElementSetService,
+}
+
+impl GroundStationServiceDefault {
+    pub fn new(
+        ground_station_repository: Repository<GroundStation>,
+        element_set_service: ElementSetService,
+    ) -> Self {
+        return Self {
+            ground_station_repository,
+            element_set_service,
+        };
+    }
+}
+
+#[async_trait]
+impl GroundStationService for GroundStationServiceDefault {
+    async fn get_all(&self) -> Result<Vec<GroundStation>> {
+        return Ok(self
+            .ground_station_repository
+            .read()
+            .await
+            .get_all()
+            .await?);
+    }
+
+    async fn add(&self, ground_station: GroundStation) -> Result<Id> {
+        return Ok(self
+            .ground_station_repository
+            .write()
+            .await
+            .add(ground_station)
+            .await?);
+    }
+
+    async fn get_passes(
+        &self,
+        ground_station_id: Id,
+        satellite_id: Id,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<Vec<Pass>>> {
+        let ground_station = match self
+            .ground_station_repository
+            .read()
+            .await
+            .get(ground_station_id)
+            .await?
+        {
+            Some(ground_station) => ground_station,
+            None => return Ok(None),
+        };
+
+        let element_set = match self
+            .element_set_service
+            .get_closest(satellite_id, from + (to - from) / 2)
+            .await?
+        {
+            Some(element_set) => element_set,
+            None => return Ok(None),
+        };
+
+        let model = create_model(&element_set)?;
+        return Ok(Some(predict_passes(
+            &model,
+            &ground_station.get_location(),
+            ground_station.get_min_elevation(),
+            from,
+            to,
+        )?));
+    }
+}
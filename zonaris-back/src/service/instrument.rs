@@ -0,0 +1,263 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::persistence::{
+    model::{
+        instrument::Instrument, instrument_data::InstrumentData, oceancolor::OceanColorMapping,
+        satellite::Satellite, satellite_instrument::SatelliteInstrument,
+    },
+    query::Query,
+    repository::{HasId, Id, RepositoryError},
+    Repository,
+};
+
+#[derive(Error, Debug)]
+pub enum InstrumentError {
+    #[error("satellite with id {0} not found")]
+    SatelliteNotFound(Id),
+    #[error("instrument with id {0} not found")]
+    InstrumentNotFound(Id),
+    #[error("instrument {instrument_id} is already linked to satellite {satellite_id}")]
+    AlreadyLinked { satellite_id: Id, instrument_id: Id },
+    #[error("instrument is linked to {0} satellites")]
+    HasSatellites(usize),
+    #[error("link is referenced by {0} instrument data records or data source mappings")]
+    LinkInUse(usize),
+}
+
+#[async_trait]
+pub trait InstrumentService {
+    async fn get_all(&self) -> Result<Vec<Instrument>>;
+    async fn get(&self, id: Id) -> Result<Option<Instrument>>;
+    async fn add(&self, instrument: Instrument) -> Result<Id>;
+
+    /// None if instrument with given id not found
+    async fn update(&self, id: Id, name: &str) -> Result<Option<Instrument>>;
+
+    /// false if instrument with given id not found, fails if it is linked to a satellite
+    async fn delete(&self, id: Id) -> Result<bool>;
+
+    /// None if satellite with given id not found, else the links together with their instruments
+    async fn get_by_satellite_id(
+        &self,
+        satellite_id: Id,
+    ) -> Result<Option<Vec<(SatelliteInstrument, Instrument)>>>;
+
+    /// Id of the created link
+    async fn link(&self, satellite_id: Id, instrument_id: Id) -> Result<Id>;
+
+    /// false if the instrument isn't linked to the satellite, fails if data refers to the link
+    async fn unlink(&self, satellite_id: Id, instrument_id: Id) -> Result<bool>;
+}
+
+pub struct InstrumentServiceDefault {
+    instrument_repository: Repository<Instrument>,
+    satellite_repository: Repository<Satellite>,
+    satellite_instrument_repository: Repository<SatelliteInstrument>,
+    instrument_data_repository: Repository<InstrumentData>,
+    oceancolor_mapping_repository: Repository<OceanColorMapping>,
+}
+
+impl InstrumentServiceDefault {
+    pub fn new(
+        instrument_repository: Repository<Instrument>,
+        satellite_repository: Repository<Satellite>,
+        satellite_instrument_repository: Repository<SatelliteInstrument>,
+        instrument_data_repository: Repository<InstrumentData>,
+        oceancolor_mapping_repository: Repository<OceanColorMapping>,
+    ) -> Self {
+        return Self {
+            instrument_repository,
+            satellite_repository,
+            satellite_instrument_repository,
+            instrument_data_repository,
+            oceancolor_mapping_repository,
+        };
+    }
+
+    async fn get_links(
+        &self,
+        query: Query<SatelliteInstrument>,
+    ) -> Result<Vec<SatelliteInstrument>> {
+        return Ok(self
+            .satellite_instrument_repository
+            .read()
+            .await
+            .query(&query)
+            .await?);
+    }
+
+    async fn find_link(
+        &self,
+        satellite_id: Id,
+        instrument_id: Id,
+    ) -> Result<Option<SatelliteInstrument>> {
+        let query = Query::new()
+            .filter(SatelliteInstrument::SATELLITE_ID.eq(satellite_id))
+            .filter(SatelliteInstrument::INSTRUMENT_ID.eq(instrument_id))
+            .limit(1);
+        return Ok(self.get_links(query).await?.pop());
+    }
+}
+
+#[async_trait]
+impl InstrumentService for InstrumentServiceDefault {
+    async fn get_all(&self) -> Result<Vec<Instrument>> {
+        return Ok(self.instrument_repository.read().await.get_all().await?);
+    }
+
+    async fn get(&self, id: Id) -> Result<Option<Instrument>> {
+        return Ok(self.instrument_repository.read().await.get(id).await?);
+    }
+
+    async fn add(&self, instrument: Instrument) -> Result<Id> {
+        return Ok(self
+            .instrument_repository
+            .write()
+            .await
+            .add(instrument)
+            .await?);
+    }
+
+    async fn update(&self, id: Id, name: &str) -> Result<Option<Instrument>> {
+        let mut instrument = match self.instrument_repository.read().await.get(id).await? {
+            Some(instrument) => instrument,
+            None => return Ok(None),
+        };
+        instrument.set_name(String::from(name));
+
+        match self
+            .instrument_repository
+            .write()
+            .await
+            .update(instrument.clone())
+            .await
+        {
+            Ok(()) => {}
+            Err(RepositoryError::NotFound(_)) => return Ok(None),
+            Err(error) => return Err(error.into()),
+        }
+
+        return Ok(Some(instrument));
+    }
+
+    async fn delete(&self, id: Id) -> Result<bool> {
+        let satellites = self
+            .satellite_instrument_repository
+            .read()
+            .await
+            .count(&Query::new().filter(SatelliteInstrument::INSTRUMENT_ID.eq(id)))
+            .await?;
+        if satellites > 0 {
+            return Err(anyhow!(InstrumentError::HasSatellites(satellites)));
+        }
+
+        return match self.instrument_repository.write().await.delete(id).await {
+            Ok(()) => Ok(true),
+            Err(RepositoryError::NotFound(_)) => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    async fn get_by_satellite_id(
+        &self,
+        satellite_id: Id,
+    ) -> Result<Option<Vec<(SatelliteInstrument, Instrument)>>> {
+        if self
+            .satellite_repository
+            .read()
+            .await
+            .get(satellite_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let links = self
+            .get_links(Query::new().filter(SatelliteInstrument::SATELLITE_ID.eq(satellite_id)))
+            .await?;
+
+        let instrument_repository = self.instrument_repository.read().await;
+        let mut result = Vec::new();
+        for link in links {
+            let instrument = instrument_repository
+                .get(*link.get_instrument_id())
+                .await?
+                .context("linked instrument expected")?;
+            result.push((link, instrument));
+        }
+
+        return Ok(Some(result));
+    }
+
+    async fn link(&self, satellite_id: Id, instrument_id: Id) -> Result<Id> {
+        if self
+            .satellite_repository
+            .read()
+            .await
+            .get(satellite_id)
+            .await?
+            .is_none()
+        {
+            return Err(anyhow!(InstrumentError::SatelliteNotFound(satellite_id)));
+        }
+        if self.get(instrument_id).await?.is_none() {
+            return Err(anyhow!(InstrumentError::InstrumentNotFound(instrument_id)));
+        }
+
+        if self.find_link(satellite_id, instrument_id).await?.is_some() {
+            return Err(anyhow!(InstrumentError::AlreadyLinked {
+                satellite_id,
+                instrument_id,
+            }));
+        }
+
+        return Ok(self
+            .satellite_instrument_repository
+            .write()
+            .await
+            .add(SatelliteInstrument::new(satellite_id, instrument_id))
+            .await?);
+    }
+
+    async fn unlink(&self, satellite_id: Id, instrument_id: Id) -> Result<bool> {
+        let link_id = match self
+            .find_link(satellite_id, instrument_id)
+            .await?
+            .and_then(|it| it.get_id())
+        {
+            Some(link_id) => link_id,
+            None => return Ok(false),
+        };
+
+        let data = self
+            .instrument_data_repository
+            .read()
+            .await
+            .count(&Query::new().filter(InstrumentData::SATELLITE_INSTRUMENT_ID.eq(link_id)))
+            .await?;
+        let mappings = self
+            .oceancolor_mapping_repository
+            .read()
+            .await
+            .count(&Query::new().filter(OceanColorMapping::SATELLITE_INSTRUMENT_ID.eq(link_id)))
+            .await?;
+        if data + mappings > 0 {
+            return Err(anyhow!(InstrumentError::LinkInUse(data + mappings)));
+        }
+
+        return match self
+            .satellite_instrument_repository
+            .write()
+            .await
+            .delete(link_id)
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(RepositoryError::NotFound(_)) => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+}
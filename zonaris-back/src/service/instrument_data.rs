@@ -1,10 +1,9 @@
-use std::collections::HashSet;
-
 use anyhow::Result;
 use async_trait::async_trait;
 
 use crate::persistence::{
     model::{instrument_data::InstrumentData, satellite_instrument::SatelliteInstrument},
+    query::Query,
     repository::{HasId, Id},
     Repository,
 };
@@ -45,24 +44,25 @@ impl InstrumentDataService for InstrumentDataServiceDefault {
         return Ok(self.instrument_data_repository.read().await.get(id).await?);
     }
 
-    // TODO: it's should be done on repository level and repository should give public api for this
     async fn get_by_satellite_id(&self, satellite_id: Id) -> Result<Vec<InstrumentData>> {
-        let satellite_instrument_ids = {
-            let lock = self.satellite_instrument_repository.read().await;
-            lock.get_all()
-                .await?
-                .into_iter()
-                .filter(|it| *it.get_satellite_id() == satellite_id)
-                .filter_map(|it| it.get_id())
-                .collect::<HashSet<_>>()
-        };
-
-        let lock = self.instrument_data_repository.read().await;
-        return Ok(lock
-            .get_all()
+        let satellite_instrument_ids = self
+            .satellite_instrument_repository
+            .read()
+            .await
+            .query(&Query::new().filter(SatelliteInstrument::SATELLITE_ID.eq(satellite_id)))
             .await?
             .into_iter()
-            .filter(|it| satellite_instrument_ids.contains(&it.get_satellite_instrument_id()))
-            .collect());
+            .filter_map(|it| it.get_id());
+
+        return Ok(self
+            .instrument_data_repository
+            .read()
+            .await
+            .query(
+                &Query::new().filter(
+                    InstrumentData::SATELLITE_INSTRUMENT_ID.is_in(satellite_instrument_ids),
+                ),
+            )
+            .await?);
     }
 }
@@ -1,15 +1,29 @@
 use std::sync::Arc;
 
 pub mod celestrak;
+pub mod element_set;
+pub mod ground_station;
+pub mod instrument;
 pub mod instrument_data;
 pub mod job;
 pub mod oceancolor;
+pub mod propagation;
+pub mod satcat;
 pub mod satellite;
+pub mod seed;
+pub mod snapshot;
+pub mod spacetrack;
 
 #[cfg(test)]
 mod tests;
 
 pub type SatelliteService = Arc<dyn self::satellite::SatelliteService + Send + Sync>;
 pub type CelestrakService = Arc<dyn self::celestrak::CelestrakService + Send + Sync>;
-pub type InstrumentDataService = Arc<dyn self::instrument_data::InstrumentDataService + Send + Sync>;
+pub type InstrumentDataService =
+    Arc<dyn self::instrument_data::InstrumentDataService + Send + Sync>;
 pub type OceanColorService = Arc<dyn self::oceancolor::OceanColorService + Send + Sync>;
+pub type GroundStationService = Arc<dyn self::ground_station::GroundStationService + Send + Sync>;
+pub type ElementSetService = Arc<dyn self::element_set::ElementSetService + Send + Sync>;
+pub type PropagationService = Arc<dyn self::propagation::PropagationService + Send + Sync>;
+pub type InstrumentService = Arc<dyn self::instrument::InstrumentService + Send + Sync>;
+pub type SatcatService = Arc<dyn self::satcat::SatcatService + Send + Sync>;
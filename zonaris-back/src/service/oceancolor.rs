@@ -1,4 +1,4 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
 
 use anyhow::{anyhow, Result};
 use axum::async_trait;
@@ -49,6 +49,7 @@ pub trait OceanColorService {
 
 pub struct OceanColorJob {
     not_found_duration: chrono::Duration,
+    images_dir: PathBuf,
     last_date: Option<NaiveDateTime>,
     oceancolor_mapping_repository: Repository<OceanColorMapping>,
     instrument_data_service: InstrumentDataService,
@@ -58,55 +59,33 @@ pub struct OceanColorJob {
 impl OceanColorJob {
     pub fn new(
         not_found_duration: chrono::Duration,
+        images_dir: PathBuf,
         oceancolor_mapping_repository: Repository<OceanColorMapping>,
         instrument_data_service: InstrumentDataService,
         ocean_color_service: super::OceanColorService,
     ) -> Self {
         return Self {
             not_found_duration,
+            images_dir,
             last_date: None,
             oceancolor_mapping_repository,
             instrument_data_service,
             ocean_color_service,
         };
     }
-}
-
-#[async_trait]
-impl Job for OceanColorJob {
-    async fn job_func(ctx: Arc<RwLock<Self>>) -> Result<()> {
-        let edate = Utc::now().naive_utc();
-
-        let sdate = {
-            let mut job_state = ctx.write().await;
-
-            let r = if let Some(last_date) = job_state.last_date {
-                last_date
-            } else {
-                Utc::now()
-                    .checked_sub_signed(job_state.not_found_duration)
-                    .unwrap()
-                    .naive_utc()
-            };
-
-            job_state.last_date = Some(edate);
 
-            r
-        };
-
-        let mappings = ctx
-            .read()
-            .await
+    /// Renders and records the granules of every mapping found in (sdate; edate), returns their number
+    pub async fn ingest(&self, sdate: NaiveDateTime, edate: NaiveDateTime) -> Result<usize> {
+        let mappings = self
             .oceancolor_mapping_repository
             .read()
             .await
             .get_all()
             .await?;
 
+        let mut count = 0;
         for mapping in mappings {
-            let items = ctx
-                .read()
-                .await
+            let items = self
                 .ocean_color_service
                 .search(sdate, edate, &mapping)
                 .await?;
@@ -121,29 +100,59 @@ impl Job for OceanColorJob {
             let current_time = Utc::now();
             let subfolder = current_time.format("%Y%m%d").to_string();
             let fileset = current_time.format("%H%M%S").to_string();
-            let base_path = format!("images/{}", subfolder);
+            let base_path = self.images_dir.join(subfolder);
 
             std::fs::create_dir_all(&base_path)?;
 
             for (idx, item) in items.into_iter().enumerate() {
-                let img = ctx.read().await.ocean_color_service.get(item).await?;
-                let img_path = format!("{}/{}_{}.png", base_path, fileset, idx);
+                let img = self.ocean_color_service.get(item).await?;
+                let img_path = base_path.join(format!("{}_{}.png", fileset, idx));
                 img.save(&img_path)?;
 
-                let satellite_data =
-                    InstrumentData::new(*mapping.get_satellite_instrument_id(), img_path);
-                if !ctx
-                    .read()
-                    .await
+                let satellite_data = InstrumentData::new(
+                    *mapping.get_satellite_instrument_id(),
+                    img_path.to_string_lossy().to_string(),
+                );
+                if !self
                     .instrument_data_service
                     .add_data(satellite_data)
                     .await?
                 {
                     error!("failed to add new data");
+                } else {
+                    count += 1;
                 }
             }
         }
 
+        return Ok(count);
+    }
+}
+
+#[async_trait]
+impl Job for OceanColorJob {
+    async fn job_func(ctx: Arc<RwLock<Self>>) -> Result<()> {
+        let edate = Utc::now().naive_utc();
+
+        let sdate = {
+            let mut job_state = ctx.write().await;
+
+            let r = if let Some(last_date) = job_state.last_date {
+                last_date
+            } else {
+                Utc::now()
+                    .checked_sub_signed(job_state.not_found_duration)
+                    .unwrap()
+                    .naive_utc()
+            };
+
+            job_state.last_date = Some(edate);
+
+            r
+        };
+
+        ctx.read().await.ingest(sdate, edate).await?;
+
         return Ok(());
     }
 }
@@ -197,14 +206,19 @@ where
     }
 }
 
+pub const OCEANCOLOR_URL: &str = "https://oceandata.sci.gsfc.nasa.gov";
+
 pub struct OceanColorServiceDefault {
     ocean_color_authorization: String,
+    base_url: String,
 }
 
 impl OceanColorServiceDefault {
-    pub fn new(ocean_color_authorization: &str) -> OceanColorServiceDefault {
+    /// `base_url` is the scheme and host of the OB.DAAC or a stand-in, e.g. `OCEANCOLOR_URL`
+    pub fn new(ocean_color_authorization: &str, base_url: &str) -> OceanColorServiceDefault {
         return OceanColorServiceDefault {
             ocean_color_authorization: String::from(ocean_color_authorization),
+            base_url: String::from(base_url.trim_end_matches('/')),
         };
     }
 }
@@ -233,7 +247,7 @@ impl OceanColorService for OceanColorServiceDefault {
         params.insert("subType", "1");
 
         let response = reqwest::Client::new()
-            .post("https://oceandata.sci.gsfc.nasa.gov/api/file_search")
+            .post(format!("{}/api/file_search", self.base_url))
             .form(&params)
             .send()
             .await?;
@@ -242,17 +256,14 @@ impl OceanColorService for OceanColorServiceDefault {
             .text()
             .await?
             .lines()
-            .map(|line| String::from(line))
+            .map(String::from)
             .collect::<Vec<_>>();
 
         if names.len() == 1 && names[0] == "No Results Found" {
             return Ok(Vec::new());
         }
 
-        return Ok(names
-            .into_iter()
-            .map(|name| SearchItem::new(name))
-            .collect::<Vec<_>>());
+        return Ok(names.into_iter().map(SearchItem::new).collect::<Vec<_>>());
     }
 
     async fn get(&self, item: SearchItem) -> Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
@@ -273,8 +284,7 @@ impl OceanColorService for OceanColorServiceDefault {
         let mut redirect_policy = reqwest::redirect::Policy::default();
         redirect_policy.set_filter(Box::new(AllowCrossOrigin::<DefaultFilter>::default()));
 
-        let getfile_baseurl =
-            reqwest::Url::from_str("https://oceandata.sci.gsfc.nasa.gov/cgi/getfile/")?;
+        let getfile_baseurl = reqwest::Url::from_str(&format!("{}/cgi/getfile/", self.base_url))?;
 
         // CHECK THIS: https://oceancolor.gsfc.nasa.gov/data/download_methods/
         let response = reqwest::ClientBuilder::new()
@@ -290,7 +300,7 @@ impl OceanColorService for OceanColorServiceDefault {
             response
                 .url()
                 .path_segments()
-                .and_then(|segments| segments.last())
+                .and_then(|mut segments| segments.next_back())
                 .and_then(|name| if name.is_empty() { None } else { Some(name) })
                 .ok_or(anyhow!(
                     "filename extraction from url failed. url: {}",
@@ -0,0 +1,294 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    persistence::repository::Id,
+    utils::{
+        element_set::ElementSet,
+        geodesy::{look_angles, teme_to_ecef, teme_to_geodetic, Geodetic, LookAngles},
+        sgp4::{Elements, Sgp4, TemeState},
+    },
+};
+
+use super::ElementSetService;
+
+/// Speed of light in vacuum, km/s
+const SPEED_OF_LIGHT: f64 = 299_792.458;
+
+pub struct SatellitePosition {
+    pub time: DateTime<Utc>,
+    pub teme: TemeState,
+    pub geodetic: Geodetic,
+}
+
+/// Builds an SGP4 (or SDP4 for deep-space orbits) model from the element set.
+pub fn create_model(element_set: &ElementSet) -> Result<Sgp4> {
+    return Ok(Sgp4::new(&Elements::from(element_set))?);
+}
+
+pub fn propagate(model: &Sgp4, time: DateTime<Utc>) -> Result<SatellitePosition> {
+    let teme = model.propagate_to(&time)?;
+
+    return Ok(SatellitePosition {
+        time,
+        teme,
+        geodetic: teme_to_geodetic(&teme, &time),
+    });
+}
+
+pub fn look_angles_at(
+    model: &Sgp4,
+    observer: &Geodetic,
+    time: DateTime<Utc>,
+) -> Result<LookAngles> {
+    let teme = model.propagate_to(&time)?;
+    return Ok(look_angles(observer, &teme_to_ecef(&teme, &time)));
+}
+
+/// Sampling instants in [from; to] with the given step. The last instant is always `to`.
+fn sample_times(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    step: Duration,
+) -> Result<Vec<DateTime<Utc>>> {
+    if step <= Duration::zero() {
+        return Err(anyhow!("step should be positive"));
+    }
+
+    let mut times = Vec::new();
+    let mut time = from;
+
+    while time <= to {
+        times.push(time);
+
+        if time == to {
+            break;
+        }
+
+        time = std::cmp::min(time + step, to);
+    }
+
+    return Ok(times);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrackPoint {
+    pub time: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Samples the sub-satellite point in [from; to] with the given step. The last sample is always taken at `to`.
+pub fn sample_ground_track(
+    model: &Sgp4,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    step: Duration,
+) -> Result<Vec<TrackPoint>> {
+    let mut points = Vec::new();
+
+    for time in sample_times(from, to, step)? {
+        let geodetic = propagate(model, time)?.geodetic;
+        points.push(TrackPoint {
+            time,
+            latitude: geodetic.latitude,
+            longitude: geodetic.longitude,
+        });
+    }
+
+    return Ok(points);
+}
+
+/// Splits a ground track into line strings which never cross the antimeridian.
+/// A vertex interpolated (in longitude, latitude and time) on ±180° closes one line string and opens the next one.
+pub fn split_at_antimeridian(points: &[TrackPoint]) -> Vec<Vec<TrackPoint>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<TrackPoint> = Vec::new();
+
+    for point in points {
+        if let Some(previous) = line.last().copied() {
+            let delta = point.longitude - previous.longitude;
+
+            if delta.abs() > 180.0 {
+                let (boundary, unwrapped) = if delta < 0.0 {
+                    (180.0, point.longitude + 360.0)
+                } else {
+                    (-180.0, point.longitude - 360.0)
+                };
+
+                let fraction = (boundary - previous.longitude) / (unwrapped - previous.longitude);
+                let latitude = previous.latitude + fraction * (point.latitude - previous.latitude);
+                let nanoseconds = (point.time - previous.time)
+                    .num_nanoseconds()
+                    .map(|it| (it as f64 * fraction) as i64)
+                    .unwrap_or(0);
+                let time = previous.time + Duration::nanoseconds(nanoseconds);
+
+                line.push(TrackPoint {
+                    time,
+                    latitude,
+                    longitude: boundary,
+                });
+                lines.push(std::mem::take(&mut line));
+                line.push(TrackPoint {
+                    time,
+                    latitude,
+                    longitude: -boundary,
+                });
+            }
+        }
+
+        line.push(*point);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    return lines;
+}
+
+/// Look angles of the satellite and Doppler shift of the downlink as received by the observer.
+#[derive(Clone, Copy, Debug)]
+pub struct TrackingPoint {
+    pub time: DateTime<Utc>,
+    pub look_angles: LookAngles,
+    /// Hz, positive while the satellite approaches
+    pub doppler_shift: f64,
+}
+
+/// First-order Doppler shift (Hz) of a carrier with the given frequency (Hz) and range rate (km/s).
+pub fn doppler_shift(frequency: f64, range_rate: f64) -> f64 {
+    return -frequency * range_rate / SPEED_OF_LIGHT;
+}
+
+/// Samples look angles and Doppler shift in [from; to] with the given step. The last sample is always taken at `to`.
+pub fn sample_tracking(
+    model: &Sgp4,
+    observer: &Geodetic,
+    frequency: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    step: Duration,
+) -> Result<Vec<TrackingPoint>> {
+    let mut points = Vec::new();
+
+    for time in sample_times(from, to, step)? {
+        let look_angles = look_angles_at(model, observer, time)?;
+        points.push(TrackingPoint {
+            time,
+            look_angles,
+            doppler_shift: doppler_shift(frequency, look_angles.range_rate),
+        });
+    }
+
+    return Ok(points);
+}
+
+#[async_trait]
+pub trait PropagationService {
+    /// None if satellite with given id not found
+    async fn get_position(
+        &self,
+        satellite_id: Id,
+        time: DateTime<Utc>,
+    ) -> Result<Option<SatellitePosition>>;
+
+    /// None if satellite with given id not found
+    async fn get_ground_track(
+        &self,
+        satellite_id: Id,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: Duration,
+    ) -> Result<Option<Vec<Vec<TrackPoint>>>>;
+
+    /// None if satellite with given id not found
+    async fn get_tracking(
+        &self,
+        satellite_id: Id,
+        observer: Geodetic,
+        frequency: f64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: Duration,
+    ) -> Result<Option<Vec<TrackingPoint>>>;
+}
+
+pub struct PropagationServiceDefault {
+    element_set_service: ElementSetService,
+}
+
+impl PropagationServiceDefault {
+    pub fn new(element_set_service: ElementSetService) -> Self {
+        return Self {
+            element_set_service,
+        };
+    }
+
+    /// Model built from the element set with the epoch closest to `time`
+    async fn get_model(&self, satellite_id: Id, time: DateTime<Utc>) -> Result<Option<Sgp4>> {
+        let element_set = self
+            .element_set_service
+            .get_closest(satellite_id, time)
+            .await?;
+
+        return match element_set {
+            Some(element_set) => Ok(Some(create_model(&element_set)?)),
+            None => Ok(None),
+        };
+    }
+}
+
+#[async_trait]
+impl PropagationService for PropagationServiceDefault {
+    async fn get_position(
+        &self,
+        satellite_id: Id,
+        time: DateTime<Utc>,
+    ) -> Result<Option<SatellitePosition>> {
+        let model = match self.get_model(satellite_id, time).await? {
+            Some(model) => model,
+            None => return Ok(None),
+        };
+
+        return Ok(Some(propagate(&model, time)?));
+    }
+
+    async fn get_ground_track(
+        &self,
+        satellite_id: Id,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: Duration,
+    ) -> Result<Option<Vec<Vec<TrackPoint>>>> {
+        let model = match self.get_model(satellite_id, from + (to - from) / 2).await? {
+            Some(model) => model,
+            None => return Ok(None),
+        };
+
+        let points = sample_ground_track(&model, from, to, step)?;
+
+        return Ok(Some(split_at_antimeridian(&points)));
+    }
+
+    async fn get_tracking(
+        &self,
+        satellite_id: Id,
+        observer: Geodetic,
+        frequency: f64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: Duration,
+    ) -> Result<Option<Vec<TrackingPoint>>> {
+        let model = match self.get_model(satellite_id, from + (to - from) / 2).await? {
+            Some(model) => model,
+            None => return Ok(None),
+        };
+
+        return Ok(Some(sample_tracking(
+            &model, &observer, frequency, from, to, step,
+        )?));
+    }
+}
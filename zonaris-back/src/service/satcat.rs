@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{error, info, warn};
+use tokio::sync::RwLock;
+
+use super::celestrak::{CelestrakError, Query};
+use super::job::Job;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_catalog::SatelliteCatalog;
+use crate::persistence::query::Query as RepositoryQuery;
+use crate::persistence::repository::{HasId, RepositoryError};
+use crate::persistence::Repository;
+use crate::utils::satcat::{self, SatcatRecord};
+
+#[async_trait]
+pub trait SatcatService {
+    async fn satcat_query(&self, query: Query) -> Result<Vec<SatcatRecord>>;
+}
+
+/// Maximum number of catalog numbers requested at once
+const CATNR_BATCH_SIZE: usize = 100;
+
+/// Keeps `SatelliteCatalog` of the stored satellites in sync with the SATCAT
+pub struct SatcatJob {
+    satcat_service: super::SatcatService,
+    satellite_repository: Repository<Satellite>,
+    satellite_catalog_repository: Repository<SatelliteCatalog>,
+}
+
+impl SatcatJob {
+    pub fn new(
+        satcat_service: super::SatcatService,
+        satellite_repository: Repository<Satellite>,
+        satellite_catalog_repository: Repository<SatelliteCatalog>,
+    ) -> Self {
+        return Self {
+            satcat_service,
+            satellite_repository,
+            satellite_catalog_repository,
+        };
+    }
+}
+
+#[async_trait]
+impl Job for SatcatJob {
+    async fn job_func(ctx: Arc<RwLock<Self>>) -> Result<()> {
+        let job = ctx.read().await;
+
+        let satellites = job.satellite_repository.read().await.get_all().await?;
+
+        let catnrs = satellites
+            .iter()
+            .filter_map(|it| it.get_catnr())
+            .filter_map(|it| u32::try_from(it).ok())
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+
+        let mut records = HashMap::new();
+        for batch in catnrs.chunks(CATNR_BATCH_SIZE) {
+            match job
+                .satcat_service
+                .satcat_query(Query::CATNRS(batch.to_vec()))
+                .await
+            {
+                Ok(batch) => records.extend(batch.into_iter().map(|it| (it.get_catnr(), it))),
+                Err(err) => error!("satcat query failed: {}", err),
+            }
+        }
+
+        let ids = satellites.iter().filter_map(|it| it.get_id());
+        let mut catalogs = job
+            .satellite_catalog_repository
+            .read()
+            .await
+            .query(&RepositoryQuery::new().filter(SatelliteCatalog::SATELLITE_ID.is_in(ids)))
+            .await?
+            .into_iter()
+            .map(|it| (*it.get_satellite_id(), it))
+            .collect::<HashMap<_, _>>();
+
+        for satellite in satellites {
+            let id = satellite.get_id().context("satellite ID expected")?;
+
+            let record = match satellite
+                .get_catnr()
+                .and_then(|it| u32::try_from(it).ok())
+                .and_then(|it| records.get(&it))
+            {
+                Some(record) => record,
+                None => continue,
+            };
+
+            match catalogs.remove(&id) {
+                Some(mut catalog) => {
+                    if !catalog.set_record(record) {
+                        continue;
+                    }
+
+                    match job
+                        .satellite_catalog_repository
+                        .write()
+                        .await
+                        .update(catalog)
+                        .await
+                    {
+                        Ok(()) => {}
+                        // deleted since it was read
+                        Err(RepositoryError::NotFound(_)) => {
+                            warn!("catalog of satellite with id({}) is not updated", id);
+                            continue;
+                        }
+                        Err(error) => return Err(error.into()),
+                    }
+                }
+                None => {
+                    job.satellite_catalog_repository
+                        .write()
+                        .await
+                        .add(SatelliteCatalog::new(id, record))
+                        .await?;
+                }
+            }
+
+            info!("catalog of satellite with id({}) is updated", id);
+        }
+
+        return Ok(());
+    }
+}
+
+pub struct SatcatServiceDefault {
+    base_url: String,
+}
+
+impl SatcatServiceDefault {
+    /// `base_url` is the scheme and host of Celestrak or a stand-in, e.g. `CELESTRAK_URL`
+    pub fn new(base_url: &str) -> Self {
+        return Self {
+            base_url: String::from(base_url.trim_end_matches('/')),
+        };
+    }
+}
+
+#[async_trait]
+impl SatcatService for SatcatServiceDefault {
+    async fn satcat_query(&self, query: Query) -> Result<Vec<SatcatRecord>> {
+        let (key, value) = query.as_param();
+
+        let text = reqwest::Client::new()
+            .get(format!("{}/satcat/records.php", self.base_url))
+            .query(&[(key, value.as_str()), ("FORMAT", "JSON")])
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        // errors and empty results are reported as plain text
+        if !text.trim_start().starts_with('[') {
+            if text.starts_with("No ") {
+                return Ok(Vec::new());
+            }
+            return Err(anyhow!(CelestrakError::InvalidQuery));
+        }
+
+        return Ok(satcat::parse_json(&text)?);
+    }
+}
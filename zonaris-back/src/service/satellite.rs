@@ -1,29 +1,306 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use axum::async_trait;
+use thiserror::Error;
 
-use crate::persistence::{model::satellite::Satellite, Repository};
+use super::celestrak::{Format, Query};
+use crate::persistence::{
+    model::{
+        satellite::Satellite, satellite_catalog::SatelliteCatalog,
+        satellite_instrument::SatelliteInstrument,
+    },
+    query::Query as RepositoryQuery,
+    repository::{HasId, Id},
+    unit_of_work::transaction,
+    Repository,
+};
+use crate::utils::element_set::ElementSet;
+
+#[derive(Error, Debug)]
+pub enum SatelliteError {
+    #[error("no object matches {0}")]
+    NotFound(String),
+    #[error("{query} matches {count} objects")]
+    Ambiguous { query: String, count: usize },
+    #[error("satellite with catalog number {catnr} already exists with id {id}")]
+    AlreadyExists { catnr: u32, id: Id },
+    #[error("satellite has {0} linked instruments")]
+    HasInstruments(usize),
+}
 
 #[async_trait]
 pub trait SatelliteService {
     async fn get_all(&self) -> Result<Vec<Satellite>>;
+
+    /// SATCAT metadata of the satellites which have it
+    async fn get_all_catalogs(&self) -> Result<Vec<SatelliteCatalog>>;
+
+    /// None if the satellite has no SATCAT metadata
+    async fn get_catalog(&self, satellite_id: Id) -> Result<Option<SatelliteCatalog>>;
+
+    /// Creates a satellite with the current elements of the single object matching `query`
+    /// (CATNR or INTDES), the name of the element set is used if `name` is None
+    async fn add(&self, query: Query, name: Option<String>) -> Result<Id>;
+
+    /// Current elements of the single object matching `query` (CATNR or INTDES), nothing is
+    /// written
+    async fn find_element_set(&self, query: Query) -> Result<ElementSet>;
+
+    /// Creates a satellite with the element set without fetching anything, the name of the
+    /// element set is used if `name` is None
+    async fn create(&self, element_set: ElementSet, name: Option<String>) -> Result<Id>;
+
+    /// Creates the satellite with the catalog number or replaces its elements with the current
+    /// ones, returns its id and whether it was created
+    async fn fetch(&self, catnr: u32) -> Result<(Id, bool)>;
+
+    /// None if satellite with given id not found, the replaced element set is kept in the history
+    async fn update(
+        &self,
+        id: Id,
+        name: Option<String>,
+        element_set: Option<ElementSet>,
+    ) -> Result<Option<Satellite>>;
+
+    /// false if satellite with given id not found, its history and catalog are removed as well
+    async fn delete(&self, id: Id) -> Result<bool>;
 }
 
 pub struct SatelliteServiceDefault {
     satellite_repository: Repository<Satellite>,
+    satellite_catalog_repository: Repository<SatelliteCatalog>,
+    satellite_instrument_repository: Repository<SatelliteInstrument>,
+    celestrak_service: super::CelestrakService,
+    element_set_service: super::ElementSetService,
 }
 
 impl SatelliteServiceDefault {
-    pub fn new(satellite_repository: Repository<Satellite>) -> SatelliteServiceDefault {
+    pub fn new(
+        satellite_repository: Repository<Satellite>,
+        satellite_catalog_repository: Repository<SatelliteCatalog>,
+        satellite_instrument_repository: Repository<SatelliteInstrument>,
+        celestrak_service: super::CelestrakService,
+        element_set_service: super::ElementSetService,
+    ) -> SatelliteServiceDefault {
         SatelliteServiceDefault {
             satellite_repository,
+            satellite_catalog_repository,
+            satellite_instrument_repository,
+            celestrak_service,
+            element_set_service,
         }
     }
+
+    async fn find_by_catnr(&self, catnr: u32) -> Result<Option<Satellite>> {
+        let query = RepositoryQuery::new()
+            .filter(Satellite::CATNR.eq(i64::from(catnr)))
+            .limit(1);
+        return Ok(self
+            .satellite_repository
+            .read()
+            .await
+            .query(&query)
+            .await?
+            .pop());
+    }
+
+    /// Fails if another satellite has the catalog number
+    async fn check_catnr(&self, catnr: u32, id: Option<Id>) -> Result<()> {
+        if let Some(existing) = self.find_by_catnr(catnr).await? {
+            let existing_id = existing.get_id().context("satellite ID expected")?;
+            if Some(existing_id) != id {
+                return Err(anyhow!(SatelliteError::AlreadyExists {
+                    catnr,
+                    id: existing_id,
+                }));
+            }
+        }
+
+        return Ok(());
+    }
 }
 
 #[async_trait]
 impl SatelliteService for SatelliteServiceDefault {
     async fn get_all(&self) -> Result<Vec<Satellite>> {
         let satellite_repository = self.satellite_repository.read().await;
-        return satellite_repository.get_all().await;
+        return Ok(satellite_repository.get_all().await?);
+    }
+
+    async fn get_all_catalogs(&self) -> Result<Vec<SatelliteCatalog>> {
+        let satellite_catalog_repository = self.satellite_catalog_repository.read().await;
+        return Ok(satellite_catalog_repository.get_all().await?);
+    }
+
+    async fn get_catalog(&self, satellite_id: Id) -> Result<Option<SatelliteCatalog>> {
+        let query = RepositoryQuery::new()
+            .filter(SatelliteCatalog::SATELLITE_ID.eq(satellite_id))
+            .limit(1);
+        return Ok(self
+            .satellite_catalog_repository
+            .read()
+            .await
+            .query(&query)
+            .await?
+            .pop());
+    }
+
+    async fn add(&self, query: Query, name: Option<String>) -> Result<Id> {
+        let element_set = self.find_element_set(query).await?;
+        return self.create(element_set, name).await;
+    }
+
+    async fn find_element_set(&self, query: Query) -> Result<ElementSet> {
+        let (description, format, piece) = match &query {
+            // TLE isn't provided for catalog numbers which don't fit into 5 digits
+            Query::CATNR(catnr) if *catnr < 100000 => {
+                (format!("CATNR {}", catnr), Format::TLE, None)
+            }
+            Query::CATNR(catnr) => (format!("CATNR {}", catnr), Format::JSON, None),
+            // the exact piece if the designator has one, e.g. 1998-067A
+            Query::INTDES(intdes) => (
+                format!("INTDES {}", intdes),
+                Format::JSON,
+                Some(intdes.clone()).filter(|it| it.len() > "yyyy-nnn".len()),
+            ),
+            _ => return Err(anyhow!("only CATNR and INTDES queries are supported")),
+        };
+
+        let mut element_sets = self.celestrak_service.gp_query(query, format).await?;
+        if let Some(piece) = piece {
+            element_sets.retain(|it| it.get_international_designator() == piece);
+        }
+
+        return match element_sets.len() {
+            0 => Err(anyhow!(SatelliteError::NotFound(description))),
+            1 => Ok(element_sets.remove(0)),
+            count => Err(anyhow!(SatelliteError::Ambiguous {
+                query: description,
+                count,
+            })),
+        };
+    }
+
+    async fn create(&self, element_set: ElementSet, name: Option<String>) -> Result<Id> {
+        self.check_catnr(element_set.get_catnr(), None).await?;
+
+        let mut satellite = Satellite::from(element_set);
+        if let Some(name) = name {
+            satellite.set_name(name);
+        }
+
+        return Ok(self
+            .satellite_repository
+            .write()
+            .await
+            .add(satellite)
+            .await?);
+    }
+
+    async fn fetch(&self, catnr: u32) -> Result<(Id, bool)> {
+        let existing = match self.find_by_catnr(catnr).await? {
+            Some(satellite) => satellite.get_id(),
+            None => None,
+        };
+
+        return match existing {
+            Some(id) => {
+                let element_set = self.find_element_set(Query::CATNR(catnr)).await?;
+                self.update(id, None, Some(element_set))
+                    .await?
+                    .context("satellite is not updated")?;
+                Ok((id, false))
+            }
+            None => Ok((self.add(Query::CATNR(catnr), None).await?, true)),
+        };
+    }
+
+    async fn update(
+        &self,
+        id: Id,
+        name: Option<String>,
+        element_set: Option<ElementSet>,
+    ) -> Result<Option<Satellite>> {
+        // the satellite is read and written back in the unit of work with its history so that a
+        // concurrent change in between isn't overwritten
+        return transaction(async {
+            let mut satellite = match self.satellite_repository.read().await.get(id).await? {
+                Some(satellite) => satellite,
+                None => return Ok(None),
+            };
+
+            if let Some(element_set) = element_set {
+                self.check_catnr(element_set.get_catnr(), Some(id)).await?;
+
+                // previous element sets are kept in the history instead of being overwritten
+                if let Some(current) = satellite.get_element_set()? {
+                    self.element_set_service.add(id, &current).await?;
+                }
+                self.element_set_service.add(id, &element_set).await?;
+
+                satellite.set_element_set(element_set);
+            }
+
+            if let Some(name) = name {
+                satellite.set_name(name);
+            }
+
+            self.satellite_repository
+                .write()
+                .await
+                .update(satellite.clone())
+                .await?;
+
+            Ok(Some(satellite))
+        })
+        .await;
+    }
+
+    async fn delete(&self, id: Id) -> Result<bool> {
+        // instruments linked after the check would be left without their satellite
+        return transaction(async {
+            if self
+                .satellite_repository
+                .read()
+                .await
+                .get(id)
+                .await?
+                .is_none()
+            {
+                return Ok(false);
+            }
+
+            let instruments = self
+                .satellite_instrument_repository
+                .read()
+                .await
+                .count(&RepositoryQuery::new().filter(SatelliteInstrument::SATELLITE_ID.eq(id)))
+                .await?;
+            if instruments > 0 {
+                return Err(anyhow!(SatelliteError::HasInstruments(instruments)));
+            }
+
+            self.element_set_service.delete_all(id).await?;
+
+            let catalogs = self
+                .satellite_catalog_repository
+                .read()
+                .await
+                .query(&RepositoryQuery::new().filter(SatelliteCatalog::SATELLITE_ID.eq(id)))
+                .await?
+                .into_iter()
+                .filter_map(|it| it.get_id())
+                .collect::<Vec<_>>();
+            for catalog in catalogs {
+                self.satellite_catalog_repository
+                    .write()
+                    .await
+                    .delete(catalog)
+                    .await?;
+            }
+
+            self.satellite_repository.write().await.delete(id).await?;
+            Ok(true)
+        })
+        .await;
     }
 }
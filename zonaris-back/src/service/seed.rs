@@ -0,0 +1,334 @@
+// Declarative initial data: satellites, instruments, the links between them and the data
+// provider mappings of the links. Applying a seed is idempotent, records are matched by their
+// natural keys (catalog number, instrument name, satellite and instrument of a link) and only
+// missing or changed ones are written.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use serde::Deserialize;
+
+use super::celestrak::Query;
+use crate::persistence::{
+    model::{instrument::Instrument, oceancolor::OceanColorMapping},
+    query::Query as RepositoryQuery,
+    repository::{HasId, Id},
+    unit_of_work::transaction,
+    Repository,
+};
+use crate::utils::element_set::ElementSet;
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Seed {
+    #[serde(default)]
+    pub satellites: Vec<SeedSatellite>,
+    #[serde(default)]
+    pub instruments: Vec<SeedInstrument>,
+    #[serde(default)]
+    pub links: Vec<SeedLink>,
+    #[serde(default)]
+    pub oceancolor: Vec<SeedOceanColorMapping>,
+}
+
+/// Elements are fetched by catalog number when the satellite is created
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedSatellite {
+    pub catnr: u32,
+    /// Name of the element set if omitted
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedInstrument {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedLink {
+    pub catnr: u32,
+    pub instrument: String,
+}
+
+/// OceanColor sensor and data product of a linked instrument
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedOceanColorMapping {
+    pub catnr: u32,
+    pub instrument: String,
+    pub sensor_id: i32,
+    pub data_id: i32,
+}
+
+impl Seed {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        return Ok(toml::from_str(text)?);
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        return Ok(serde_yaml::from_str(text)?);
+    }
+
+    /// The format is chosen by the extension: .toml, .yaml or .yml
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("can't read seed file {}", path.display()))?;
+
+        let seed = match path.extension().and_then(|it| it.to_str()) {
+            Some("toml") => Seed::from_toml(&text),
+            Some("yaml") | Some("yml") => Seed::from_yaml(&text),
+            _ => Err(anyhow!("expected .toml, .yaml or .yml extension")),
+        };
+
+        return seed.with_context(|| format!("invalid seed file {}", path.display()));
+    }
+}
+
+/// Number of records written by `Seeder::apply`, both zero if everything was already seeded
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SeedReport {
+    pub created: usize,
+    pub updated: usize,
+}
+
+pub struct Seeder {
+    satellite_service: super::SatelliteService,
+    instrument_service: super::InstrumentService,
+    oceancolor_mapping_repository: Repository<OceanColorMapping>,
+}
+
+impl Seeder {
+    pub fn new(
+        satellite_service: super::SatelliteService,
+        instrument_service: super::InstrumentService,
+        oceancolor_mapping_repository: Repository<OceanColorMapping>,
+    ) -> Self {
+        return Self {
+            satellite_service,
+            instrument_service,
+            oceancolor_mapping_repository,
+        };
+    }
+
+    /// Everything or nothing is written
+    pub async fn apply(&self, seed: &Seed) -> Result<SeedReport> {
+        let mut report = SeedReport::default();
+
+        // the unit of work holds a transaction (the only connection with SQLite), the elements
+        // are fetched before it starts so that it doesn't wait for Celestrak
+        let element_sets = self.fetch_element_sets(seed).await?;
+
+        transaction(async {
+            let satellites = self
+                .apply_satellites(seed, &element_sets, &mut report)
+                .await?;
+            let instruments = self.apply_instruments(seed, &mut report).await?;
+            let links = self
+                .apply_links(seed, &satellites, &instruments, &mut report)
+                .await?;
+            self.apply_oceancolor(seed, &links, &mut report).await
+        })
+        .await?;
+
+        info!(
+            "seed applied: {} records created, {} updated",
+            report.created, report.updated
+        );
+        return Ok(report);
+    }
+
+    /// Ids and names of the satellites by catalog number
+    async fn get_satellites(&self) -> Result<HashMap<u32, (Id, String)>> {
+        let mut result = HashMap::new();
+        for satellite in self.satellite_service.get_all().await? {
+            if let (Some(catnr), Some(id)) = (satellite.get_catnr(), satellite.get_id()) {
+                result.insert(u32::try_from(catnr)?, (id, satellite.get_name().clone()));
+            }
+        }
+
+        return Ok(result);
+    }
+
+    /// Current elements of the seeded satellites which don't exist yet, by catalog number
+    async fn fetch_element_sets(&self, seed: &Seed) -> Result<HashMap<u32, ElementSet>> {
+        let existing = self.get_satellites().await?;
+
+        let mut result = HashMap::new();
+        for satellite in &seed.satellites {
+            if existing.contains_key(&satellite.catnr) {
+                continue;
+            }
+
+            let element_set = self
+                .satellite_service
+                .find_element_set(Query::CATNR(satellite.catnr))
+                .await
+                .with_context(|| format!("can't seed satellite {}", satellite.catnr))?;
+            result.insert(satellite.catnr, element_set);
+        }
+
+        return Ok(result);
+    }
+
+    /// Satellite ids by catalog number
+    async fn apply_satellites(
+        &self,
+        seed: &Seed,
+        element_sets: &HashMap<u32, ElementSet>,
+        report: &mut SeedReport,
+    ) -> Result<HashMap<u32, Id>> {
+        let existing = self.get_satellites().await?;
+
+        let mut result = HashMap::new();
+        for satellite in &seed.satellites {
+            let id = match existing.get(&satellite.catnr) {
+                Some((id, name)) => {
+                    if let Some(seed_name) = satellite.name.as_ref().filter(|it| *it != name) {
+                        self.satellite_service
+                            .update(*id, Some(seed_name.clone()), None)
+                            .await?;
+                        report.updated += 1;
+                    }
+                    *id
+                }
+                None => {
+                    // missing if the satellite was deleted after the elements were fetched
+                    let element_set = element_sets
+                        .get(&satellite.catnr)
+                        .with_context(|| format!("satellite {} is not fetched", satellite.catnr))?;
+                    let id = self
+                        .satellite_service
+                        .create(element_set.clone(), satellite.name.clone())
+                        .await
+                        .with_context(|| format!("can't seed satellite {}", satellite.catnr))?;
+                    report.created += 1;
+                    id
+                }
+            };
+            result.insert(satellite.catnr, id);
+        }
+
+        return Ok(result);
+    }
+
+    /// Instrument ids by name
+    async fn apply_instruments(
+        &self,
+        seed: &Seed,
+        report: &mut SeedReport,
+    ) -> Result<HashMap<String, Id>> {
+        let mut result = HashMap::new();
+        for instrument in self.instrument_service.get_all().await? {
+            if let Some(id) = instrument.get_id() {
+                result.insert(instrument.get_name().clone(), id);
+            }
+        }
+
+        for instrument in &seed.instruments {
+            if result.contains_key(&instrument.name) {
+                continue;
+            }
+
+            let id = self
+                .instrument_service
+                .add(Instrument::new(&instrument.name))
+                .await?;
+            report.created += 1;
+            result.insert(instrument.name.clone(), id);
+        }
+
+        return Ok(result);
+    }
+
+    /// Link ids by catalog number and instrument name
+    async fn apply_links(
+        &self,
+        seed: &Seed,
+        satellites: &HashMap<u32, Id>,
+        instruments: &HashMap<String, Id>,
+        report: &mut SeedReport,
+    ) -> Result<HashMap<(u32, String), Id>> {
+        let mut result = HashMap::new();
+        for link in &seed.links {
+            let satellite_id = *satellites
+                .get(&link.catnr)
+                .with_context(|| format!("satellite {} is not seeded", link.catnr))?;
+            let instrument_id = *instruments
+                .get(&link.instrument)
+                .with_context(|| format!("instrument {} is not seeded", link.instrument))?;
+
+            let existing = self
+                .instrument_service
+                .get_by_satellite_id(satellite_id)
+                .await?
+                .context("seeded satellite expected")?
+                .into_iter()
+                .find(|(_, instrument)| instrument.get_id() == Some(instrument_id))
+                .and_then(|(link, _)| link.get_id());
+
+            let id = match existing {
+                Some(id) => id,
+                None => {
+                    report.created += 1;
+                    self.instrument_service
+                        .link(satellite_id, instrument_id)
+                        .await?
+                }
+            };
+            result.insert((link.catnr, link.instrument.clone()), id);
+        }
+
+        return Ok(result);
+    }
+
+    async fn apply_oceancolor(
+        &self,
+        seed: &Seed,
+        links: &HashMap<(u32, String), Id>,
+        report: &mut SeedReport,
+    ) -> Result<()> {
+        let mut repository = self.oceancolor_mapping_repository.write().await;
+        for mapping in &seed.oceancolor {
+            let link_id = *links
+                .get(&(mapping.catnr, mapping.instrument.clone()))
+                .with_context(|| {
+                    format!(
+                        "link of satellite {} and instrument {} is not seeded",
+                        mapping.catnr, mapping.instrument
+                    )
+                })?;
+
+            let query = RepositoryQuery::new()
+                .filter(OceanColorMapping::SATELLITE_INSTRUMENT_ID.eq(link_id))
+                .limit(1);
+            match repository.query(&query).await?.pop() {
+                Some(current)
+                    if *current.get_sensor_id() == mapping.sensor_id
+                        && *current.get_data_id() == mapping.data_id => {}
+                Some(mut current) => {
+                    current.set_sensor_id(mapping.sensor_id);
+                    current.set_data_id(mapping.data_id);
+                    repository.update(current).await?;
+                    report.updated += 1;
+                }
+                None => {
+                    repository
+                        .add(OceanColorMapping::new(
+                            link_id,
+                            mapping.sensor_id,
+                            mapping.data_id,
+                        ))
+                        .await?;
+                    report.created += 1;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+}
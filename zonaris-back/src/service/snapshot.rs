@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use super::job::Job;
+use crate::persistence::journal::Journals;
+
+/// Snapshots the in-memory repositories, which empties their write logs
+pub struct SnapshotJob {
+    journals: Journals,
+}
+
+impl SnapshotJob {
+    pub fn new(journals: Journals) -> Self {
+        return Self { journals };
+    }
+}
+
+#[async_trait]
+impl Job for SnapshotJob {
+    async fn job_func(ctx: Arc<RwLock<Self>>) -> Result<()> {
+        let journals = ctx.read().await.journals.clone();
+        // the records are locked while they are written
+        tokio::task::spawn_blocking(move || journals.snapshot()).await??;
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,194 @@
+// Space-Track.org API (https://www.space-track.org/documentation#/api) as an element set source.
+// Requests are authenticated with the session cookie set on login.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use log::info;
+use reqwest::{StatusCode, Url};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use super::celestrak::{CelestrakError, CelestrakService, Format, Query};
+use crate::utils::element_set::ElementSet;
+use crate::utils::omm;
+use crate::utils::tle;
+
+pub const SPACETRACK_URL: &str = "https://www.space-track.org";
+
+const EPOCH_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Error, Debug)]
+pub enum SpaceTrackError {
+    #[error("login failed")]
+    LoginFailed,
+    #[error("session isn't accepted after login")]
+    Unauthorized,
+    #[error("query isn't supported by space-track")]
+    UnsupportedQuery,
+}
+
+/// Queries the `gp` and `gp_history` classes. Space-Track has no Celestrak groups,
+/// so GROUP and SPECIAL queries are rejected.
+pub struct CelestrakServiceSpaceTrack {
+    base_url: String,
+    identity: String,
+    password: String,
+    client: reqwest::Client,
+    logged_in: Mutex<bool>,
+}
+
+impl CelestrakServiceSpaceTrack {
+    /// `base_url` is the scheme and host of Space-Track or a stand-in, e.g. `SPACETRACK_URL`
+    pub fn new(base_url: &str, identity: &str, password: &str) -> Result<Self> {
+        return Ok(Self {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            identity: String::from(identity),
+            password: String::from(password),
+            client: reqwest::ClientBuilder::new().cookie_store(true).build()?,
+            logged_in: Mutex::new(false),
+        });
+    }
+
+    async fn login(&self) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/ajaxauth/login", self.base_url))
+            .form(&[
+                ("identity", self.identity.as_str()),
+                ("password", self.password.as_str()),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        // failed logins are answered with 200 and {"Login":"Failed"}
+        if !status.is_success() || text.contains("Failed") {
+            return Err(anyhow!(SpaceTrackError::LoginFailed));
+        }
+
+        info!("logged in to space-track as {}", self.identity);
+        return Ok(());
+    }
+
+    /// Runs a query of the `basicspacedata` controller given as path segments.
+    /// Logs in if there is no session yet and once more if the session expired.
+    async fn query(&self, segments: &[&str]) -> Result<String> {
+        let mut url = Url::parse(&format!("{}/basicspacedata/query", self.base_url))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("invalid space-track url {}", self.base_url))?
+            .extend(segments);
+
+        for _ in 0..2 {
+            {
+                let mut logged_in = self.logged_in.lock().await;
+                if !*logged_in {
+                    self.login().await?;
+                    *logged_in = true;
+                }
+            }
+
+            let response = self.client.get(url.clone()).send().await?;
+            if response.status() == StatusCode::UNAUTHORIZED {
+                *self.logged_in.lock().await = false;
+                continue;
+            }
+
+            return Ok(response.error_for_status()?.text().await?);
+        }
+
+        return Err(anyhow!(SpaceTrackError::Unauthorized));
+    }
+}
+
+/// Space-Track XML isn't an NDM container, so JSON is requested instead
+fn format_param(format: Format) -> &'static str {
+    return match format {
+        Format::TLE => "3le",
+        Format::JSON | Format::XML => "json",
+        Format::CSV => "csv",
+    };
+}
+
+fn parse(text: &str, format: Format) -> Result<Vec<ElementSet>> {
+    let element_sets = match format {
+        Format::TLE => tle::parse_3le(text)
+            .map_err(CelestrakError::InvalidElementSet)?
+            .into_iter()
+            .map(ElementSet::from)
+            .collect(),
+        Format::JSON | Format::XML => omm::parse_json(text)
+            .map_err(CelestrakError::InvalidOmm)?
+            .into_iter()
+            .map(ElementSet::from)
+            .collect(),
+        Format::CSV => omm::parse_csv(text)
+            .map_err(CelestrakError::InvalidOmm)?
+            .into_iter()
+            .map(ElementSet::from)
+            .collect(),
+    };
+
+    return Ok(element_sets);
+}
+
+#[async_trait]
+impl CelestrakService for CelestrakServiceSpaceTrack {
+    async fn gp_query(&self, query: Query, format: Format) -> Result<Vec<ElementSet>> {
+        // "~~" matches a part of the value, "^" its beginning
+        let (field, value) = match query {
+            Query::CATNR(catnr) => ("NORAD_CAT_ID", catnr.to_string()),
+            Query::CATNRS(catnrs) => ("NORAD_CAT_ID", catnrs.iter().join(",")),
+            Query::INTDES(intdes) => ("OBJECT_ID", format!("^{}", intdes)),
+            Query::NAME(name) => ("OBJECT_NAME", format!("~~{}", name)),
+            Query::GROUP(_) | Query::SPECIAL(_) => {
+                return Err(anyhow!(SpaceTrackError::UnsupportedQuery))
+            }
+        };
+
+        let text = self
+            .query(&[
+                "class",
+                "gp",
+                field,
+                &value,
+                "orderby",
+                "NORAD_CAT_ID asc",
+                "format",
+                format_param(format),
+            ])
+            .await
+            .context("space-track gp query failed")?;
+
+        return parse(&text, format);
+    }
+
+    async fn gp_history(
+        &self,
+        catnr: u32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ElementSet>> {
+        let epoch = format!("{}--{}", from.format(EPOCH_FORMAT), to.format(EPOCH_FORMAT));
+
+        let text = self
+            .query(&[
+                "class",
+                "gp_history",
+                "NORAD_CAT_ID",
+                &catnr.to_string(),
+                "EPOCH",
+                &epoch,
+                "orderby",
+                "EPOCH asc",
+                "format",
+                "json",
+            ])
+            .await
+            .context("space-track gp_history query failed")?;
+
+        return parse(&text, Format::JSON);
+    }
+}
@@ -0,0 +1,231 @@
+// Backends the repository tests run against: in-memory, SQLite on a temporary file with the
+// `sqlite` feature and Postgres with the `postgres` feature if TEST_DATABASE_URL is set. Every
+// test gets a fresh database, the Postgres one is a schema of its own which is dropped afterwards.
+
+use std::future::Future;
+
+use anyhow::{Context, Result};
+
+use crate::persistence::repository::HasId;
+use crate::persistence::schema::Table;
+use crate::persistence::{create_inmemory_repository, Repository};
+
+#[cfg(feature = "postgres")]
+pub trait PostgresEntity:
+    TryFrom<tokio_postgres::Row, Error = anyhow::Error>
+    + TryInto<Vec<crate::persistence::postgres::repository::ColumnValuePair>, Error = anyhow::Error>
+{
+}
+
+#[cfg(feature = "postgres")]
+impl<T> PostgresEntity for T where
+    T: TryFrom<tokio_postgres::Row, Error = anyhow::Error>
+        + TryInto<
+            Vec<crate::persistence::postgres::repository::ColumnValuePair>,
+            Error = anyhow::Error,
+        >
+{
+}
+
+#[cfg(not(feature = "postgres"))]
+pub trait PostgresEntity {}
+
+#[cfg(not(feature = "postgres"))]
+impl<T> PostgresEntity for T {}
+
+#[cfg(feature = "sqlite")]
+pub trait SqliteEntity:
+    for<'a> TryFrom<&'a rusqlite::Row<'a>, Error = anyhow::Error>
+    + TryInto<Vec<crate::persistence::sqlite::repository::ColumnValuePair>, Error = anyhow::Error>
+{
+}
+
+#[cfg(feature = "sqlite")]
+impl<T> SqliteEntity for T where
+    T: for<'a> TryFrom<&'a rusqlite::Row<'a>, Error = anyhow::Error>
+        + TryInto<Vec<crate::persistence::sqlite::repository::ColumnValuePair>, Error = anyhow::Error>
+{
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub trait SqliteEntity {}
+
+#[cfg(not(feature = "sqlite"))]
+impl<T> SqliteEntity for T {}
+
+/// Entities every backend of the build can store
+pub trait Entity:
+    HasId + Table + Clone + Send + Sync + 'static + PostgresEntity + SqliteEntity
+{
+}
+
+impl<T> Entity for T where
+    T: HasId + Table + Clone + Send + Sync + 'static + PostgresEntity + SqliteEntity
+{
+}
+
+pub enum Backend {
+    InMemory,
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        database: crate::persistence::sqlite::Database,
+        /// Removed with the database file once the test is done
+        _directory: tempfile::TempDir,
+    },
+    #[cfg(feature = "postgres")]
+    Postgres {
+        pool: crate::persistence::postgres::Pool,
+        _schema: postgres::TestSchema,
+    },
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Backend::InMemory => "in-memory",
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite { .. } => "sqlite",
+            #[cfg(feature = "postgres")]
+            Backend::Postgres { .. } => "postgres",
+        };
+    }
+
+    pub fn repository<T: Entity>(&self) -> Repository<T> {
+        return match self {
+            Backend::InMemory => create_inmemory_repository::<T>(),
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite { database, .. } => {
+                crate::persistence::sqlite::create_sqlite_repository::<T>(database.clone())
+            }
+            #[cfg(feature = "postgres")]
+            Backend::Postgres { pool, .. } => {
+                crate::persistence::postgres::create_postgres_repository::<T>(pool.clone())
+            }
+        };
+    }
+}
+
+/// Fresh, migrated databases of every backend of the build
+pub async fn backends() -> Result<Vec<Backend>> {
+    // the backends of the features are added to it
+    #[allow(unused_mut)]
+    let mut result = vec![Backend::InMemory];
+
+    #[cfg(feature = "sqlite")]
+    {
+        use crate::persistence::sqlite::{migration, Database};
+
+        let directory = tempfile::tempdir()?;
+        let database = Database::open(&directory.path().join("zonaris.db"))?;
+        migration::migrate(database.clone()).await?;
+        result.push(Backend::Sqlite {
+            database,
+            _directory: directory,
+        });
+    }
+
+    #[cfg(feature = "postgres")]
+    if let Ok(url) = std::env::var("TEST_DATABASE_URL") {
+        let (pool, schema) = postgres::connect(&url).await?;
+        result.push(Backend::Postgres {
+            pool,
+            _schema: schema,
+        });
+    }
+
+    return Ok(result);
+}
+
+/// Runs `test` against every backend, the failures name it
+pub async fn each_backend<F>(test: impl Fn(Backend) -> F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
+    for backend in backends().await? {
+        let name = backend.name();
+        // shown with the output of a test which panics
+        println!("{} backend", name);
+        test(backend)
+            .await
+            .with_context(|| format!("{} backend", name))?;
+    }
+
+    return Ok(());
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use anyhow::Result;
+    use tokio_postgres::NoTls;
+
+    use crate::config::DatabaseConfig;
+    use crate::persistence::postgres::{migration, Pool};
+
+    static SCHEMAS: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct TestSchema {
+        url: String,
+        name: String,
+    }
+
+    pub async fn connect(url: &str) -> Result<(Pool, TestSchema)> {
+        let name = format!(
+            "zonaris_test_{}_{}",
+            std::process::id(),
+            SCHEMAS.fetch_add(1, Ordering::Relaxed)
+        );
+        execute(url, &format!("CREATE SCHEMA {}", name)).await?;
+        let schema = TestSchema {
+            url: String::from(url),
+            name,
+        };
+
+        // the tables are created in and looked up from the schema
+        let url = if url.contains("://") {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!(
+                "{}{}options=-c%20search_path%3D{}",
+                url, separator, schema.name
+            )
+        } else {
+            format!("{} options='-c search_path={}'", url, schema.name)
+        };
+        let pool = Pool::connect(&DatabaseConfig {
+            url: Some(url),
+            ..DatabaseConfig::default()
+        })
+        .await?;
+        migration::migrate(pool.clone()).await?;
+
+        return Ok((pool, schema));
+    }
+
+    async fn execute(url: &str, statement: &str) -> Result<()> {
+        let (client, connection) = tokio_postgres::connect(url, NoTls).await?;
+        tokio::spawn(connection);
+        client.batch_execute(statement).await?;
+        return Ok(());
+    }
+
+    impl Drop for TestSchema {
+        /// The runtime of the test may be shutting down, the schema is dropped on a runtime of
+        /// its own
+        fn drop(&mut self) {
+            let url = self.url.clone();
+            let statement = format!("DROP SCHEMA {} CASCADE", self.name);
+            let result = std::thread::spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(execute(&url, &statement))
+            })
+            .join();
+
+            if let Ok(Err(error)) = result {
+                eprintln!("can't drop schema {}: {}", self.name, error);
+            }
+        }
+    }
+}
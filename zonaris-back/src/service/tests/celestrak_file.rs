@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use crate::service::celestrak::{CelestrakService, CelestrakServiceFile, Format, Query};
+use crate::utils::element_set::ElementSet;
+use crate::utils::omm::OMM;
+use crate::utils::tle::{checksum, TLE};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+/// ISS element set with the catalog number, the international designator and the epoch replaced
+fn tle(name: &str, catnr: u32, intdes: &str, day: &str) -> TLE {
+    let catnr = format!("{:05}", catnr);
+
+    let mut tle1 = ISS_TLE1
+        .replace("25544", &catnr)
+        .replace("98067A", intdes)
+        .replace("264.51782528", day);
+    tle1.replace_range(68..69, &checksum(&tle1).to_string());
+
+    let mut tle2 = ISS_TLE2.replace("25544", &catnr);
+    tle2.replace_range(68..69, &checksum(&tle2).to_string());
+
+    return TLE::new(name, &tle1, &tle2).unwrap();
+}
+
+fn to_3le(tles: &[TLE]) -> String {
+    return tles
+        .iter()
+        .map(|it| format!("{}\n{}\n{}\n", it.get_name(), it.get_tle1(), it.get_tle2()))
+        .collect();
+}
+
+async fn query(service: &CelestrakServiceFile, query: Query) -> Vec<u32> {
+    let mut catnrs = service
+        .gp_query(query, Format::TLE)
+        .await
+        .unwrap()
+        .iter()
+        .map(|it| it.get_catnr())
+        .collect::<Vec<_>>();
+    catnrs.sort();
+    return catnrs;
+}
+
+#[tokio::test]
+async fn queries_are_answered_from_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let stations = dir.path().join("stations.txt");
+    let weather = dir.path().join("weather.json");
+
+    std::fs::write(
+        &stations,
+        to_3le(&[
+            tle("ISS (ZARYA)", 25544, "98067A", "264.51782528"),
+            tle("CSS (TIANHE)", 48274, "21035A", "264.51782528"),
+        ]),
+    )
+    .unwrap();
+    let noaa = OMM::from(&tle("NOAA 19", 33591, "09005A", "264.51782528"));
+    std::fs::write(&weather, format!("[{}]", noaa.to_json())).unwrap();
+
+    let service = CelestrakServiceFile::new(vec![stations, weather]);
+
+    assert_eq!(query(&service, Query::CATNR(33591)).await, vec![33591]);
+    assert_eq!(
+        query(&service, Query::CATNRS(vec![25544, 48274, 1])).await,
+        vec![25544, 48274]
+    );
+    assert_eq!(
+        query(&service, Query::NAME(String::from("tianhe"))).await,
+        vec![48274]
+    );
+    assert_eq!(
+        query(&service, Query::INTDES(String::from("1998-067"))).await,
+        vec![25544]
+    );
+    assert_eq!(
+        query(&service, Query::INTDES(String::from("2009-005"))).await,
+        vec![33591]
+    );
+    assert_eq!(
+        query(&service, Query::GROUP(String::from("stations"))).await,
+        vec![25544, 33591, 48274]
+    );
+}
+
+#[tokio::test]
+async fn changed_files_are_reloaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path: PathBuf = dir.path().join("celestrak.txt");
+
+    let older = tle("ISS (ZARYA)", 25544, "98067A", "264.51782528");
+    std::fs::write(&path, to_3le(std::slice::from_ref(&older))).unwrap();
+
+    let service = CelestrakServiceFile::new(vec![path.clone()]);
+    let first = service
+        .gp_query(Query::CATNR(25544), Format::TLE)
+        .await
+        .unwrap();
+    assert_eq!(first[0].get_epoch(), older.get_epoch());
+
+    // the same object in two sets of the snapshot, the newer one wins
+    let newer = tle("ISS (ZARYA)", 25544, "98067A", "265.00000000");
+    std::fs::write(&path, to_3le(&[newer.clone(), older])).unwrap();
+
+    let second = service
+        .gp_query(Query::CATNR(25544), Format::TLE)
+        .await
+        .unwrap();
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].get_epoch(), newer.get_epoch());
+    assert!(matches!(second[0], ElementSet::TLE(_)));
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(service
+        .gp_query(Query::CATNR(25544), Format::TLE)
+        .await
+        .is_err());
+}
@@ -0,0 +1,178 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use itertools::Itertools;
+use tokio::sync::RwLock;
+
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_catalog::SatelliteCatalog;
+use crate::persistence::model::satellite_element_set::SatelliteElementSet;
+use crate::persistence::model::satellite_instrument::SatelliteInstrument;
+use crate::persistence::repository::{Id, Repository};
+use crate::persistence::{create_inmemory_repository, InMemoryRepository};
+use crate::service::celestrak::{CelestrakJob, CelestrakService, Format, Query};
+use crate::service::element_set::ElementSetServiceDefault;
+use crate::service::job::Job;
+use crate::service::satellite::SatelliteServiceDefault;
+use crate::utils::element_set::ElementSet;
+use crate::utils::tle::{checksum, TLE};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+/// ISS element set with the catalog number and the epoch (day of 2008) replaced
+fn element_set(catnr: u32, day: &str) -> ElementSet {
+    let catnr = format!("{:05}", catnr);
+
+    let mut tle1 = ISS_TLE1
+        .replace("25544", &catnr)
+        .replace("264.51782528", day);
+    tle1.replace_range(68..69, &checksum(&tle1).to_string());
+
+    let mut tle2 = ISS_TLE2.replace("25544", &catnr);
+    tle2.replace_range(68..69, &checksum(&tle2).to_string());
+
+    return ElementSet::TLE(TLE::new(&catnr, &tle1, &tle2).unwrap());
+}
+
+/// Serves the "stations" group and element sets by catalog number, records the queries. The
+/// satellite of `renamed` is renamed while the group is fetched.
+struct CelestrakServiceMock {
+    group: Vec<ElementSet>,
+    catalog: Vec<ElementSet>,
+    queries: Mutex<Vec<String>>,
+    renamed: Option<(InMemoryRepository<Satellite>, Id)>,
+}
+
+#[async_trait]
+impl CelestrakService for CelestrakServiceMock {
+    async fn gp_query(&self, query: Query, _format: Format) -> Result<Vec<ElementSet>> {
+        if let (Query::GROUP(_), Some((repository, id))) = (&query, &self.renamed) {
+            let mut satellite = repository.read().await.get(*id).await?.unwrap();
+            satellite.set_name(String::from("RENAMED"));
+            repository.write().await.update(satellite).await?;
+        }
+
+        let (description, result) = match query {
+            Query::GROUP(group) => (
+                format!("GROUP={}", group),
+                if group == "stations" {
+                    self.group.clone()
+                } else {
+                    Vec::new()
+                },
+            ),
+            Query::CATNRS(catnrs) => (
+                format!("CATNR={}", catnrs.iter().join(",")),
+                self.catalog
+                    .iter()
+                    .filter(|it| catnrs.contains(&it.get_catnr()))
+                    .cloned()
+                    .collect(),
+            ),
+            _ => (String::from("other"), Vec::new()),
+        };
+
+        self.queries.lock().unwrap().push(description);
+        return Ok(result);
+    }
+}
+
+#[tokio::test]
+async fn catalogs_and_batches_replace_per_satellite_queries() {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let satellite_element_set_repository = create_inmemory_repository::<SatelliteElementSet>();
+
+    let mut ids = Vec::new();
+    for catnr in [25544, 25994, 27424, 41335] {
+        let satellite = Satellite::from(element_set(catnr, "264.51782528"));
+        ids.push(
+            satellite_repository
+                .write()
+                .await
+                .add(satellite)
+                .await
+                .unwrap(),
+        );
+    }
+
+    // 25544 is unchanged and 25994 is newer in the group, 27424 is newer outside of it,
+    // 41335 isn't served at all
+    let celestrak_service = Arc::new(CelestrakServiceMock {
+        group: vec![
+            element_set(25544, "264.51782528"),
+            element_set(25994, "265.00000000"),
+            element_set(99999, "265.00000000"),
+        ],
+        catalog: vec![element_set(27424, "266.00000000")],
+        queries: Mutex::new(Vec::new()),
+        renamed: Some((satellite_repository.clone(), ids[1])),
+    });
+
+    let satellite_service = Arc::new(SatelliteServiceDefault::new(
+        satellite_repository.clone(),
+        create_inmemory_repository::<SatelliteCatalog>(),
+        create_inmemory_repository::<SatelliteInstrument>(),
+        celestrak_service.clone(),
+        Arc::new(ElementSetServiceDefault::new(
+            satellite_repository.clone(),
+            satellite_element_set_repository.clone(),
+        )),
+    ));
+    let job = CelestrakJob::new(
+        celestrak_service.clone(),
+        satellite_service,
+        satellite_repository.clone(),
+        vec![Query::GROUP(String::from("stations"))],
+    );
+    CelestrakJob::job_func(Arc::new(RwLock::new(job)))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *celestrak_service.queries.lock().unwrap(),
+        vec!["GROUP=stations", "CATNR=27424,41335"]
+    );
+
+    let mut epochs = Vec::new();
+    let mut names = Vec::new();
+    for id in &ids {
+        let satellite = satellite_repository
+            .read()
+            .await
+            .get(*id)
+            .await
+            .unwrap()
+            .unwrap();
+        epochs.push(*satellite.get_element_set().unwrap().unwrap().get_epoch());
+        names.push(satellite.get_name().to_string());
+    }
+    assert_eq!(
+        epochs,
+        vec![
+            *element_set(25544, "264.51782528").get_epoch(),
+            *element_set(25994, "265.00000000").get_epoch(),
+            *element_set(27424, "266.00000000").get_epoch(),
+            *element_set(41335, "264.51782528").get_epoch(),
+        ]
+    );
+    // the rename made while the element sets were fetched isn't overwritten
+    assert_eq!(names, vec!["25544", "RENAMED", "27424", "41335"]);
+
+    // only changed satellites get the previous and the new element set recorded
+    let recorded = satellite_element_set_repository
+        .read()
+        .await
+        .get_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|it| *it.get_satellite_id())
+        .collect::<Vec<_>>();
+    let count = |id| recorded.iter().filter(|it| **it == id).count();
+    assert_eq!(
+        ids.iter().map(|id| count(*id)).collect::<Vec<_>>(),
+        vec![0, 2, 2, 0]
+    );
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use chrono::{TimeZone, Utc};
+use clap::Parser;
+
+use crate::cli::{Cli, Command, MigrateCommand};
+
+fn parse(args: &[&str]) -> Result<Command, clap::Error> {
+    return Ok(Cli::try_parse_from([&["zonaris"], args].concat())?.command());
+}
+
+#[test]
+fn subcommands_are_parsed() {
+    assert_eq!(
+        parse(&[]).unwrap(),
+        Command::Serve {
+            skip_migrate: false,
+            skip_seed: false
+        }
+    );
+    assert_eq!(
+        parse(&["serve", "--skip-migrate"]).unwrap(),
+        Command::Serve {
+            skip_migrate: true,
+            skip_seed: false
+        }
+    );
+    assert_eq!(
+        parse(&["migrate"]).unwrap(),
+        Command::Migrate { action: None }
+    );
+    assert_eq!(
+        parse(&["migrate", "down", "--to", "0"]).unwrap(),
+        Command::Migrate {
+            action: Some(MigrateCommand::Down { to: 0 })
+        }
+    );
+    assert_eq!(
+        parse(&["migrate", "check"]).unwrap(),
+        Command::Migrate {
+            action: Some(MigrateCommand::Check)
+        }
+    );
+    assert_eq!(
+        parse(&["fetch-tle", "25544"]).unwrap(),
+        Command::FetchTle { catnr: 25544 }
+    );
+    assert_eq!(
+        parse(&[
+            "ingest-oceancolor",
+            "--from",
+            "2024-01-01T00:00:00Z",
+            "--to",
+            "2024-01-02T00:00:00Z"
+        ])
+        .unwrap(),
+        Command::IngestOceancolor {
+            from: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            to: Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()),
+        }
+    );
+    assert_eq!(
+        parse(&["render", "granule.nc", "--out", "x.png"]).unwrap(),
+        Command::Render {
+            file: PathBuf::from("granule.nc"),
+            var: String::from("sst4"),
+            out: PathBuf::from("x.png"),
+        }
+    );
+
+    assert!(parse(&["fetch-tle", "ISS"]).is_err());
+    assert!(parse(&["ingest-oceancolor", "--from", "yesterday"]).is_err());
+    assert!(parse(&["render", "granule.nc"]).is_err());
+}
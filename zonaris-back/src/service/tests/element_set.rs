@@ -0,0 +1,299 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::RwLock;
+
+use crate::persistence::create_inmemory_repository;
+use crate::persistence::model::ground_station::GroundStation;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_element_set::SatelliteElementSet;
+use crate::persistence::query::Query;
+use crate::persistence::repository::{Id, Repository as RepositoryTrait, RepositoryError};
+use crate::persistence::Repository;
+use crate::service::element_set::{ElementSetError, ElementSetService, ElementSetServiceDefault};
+use crate::service::ground_station::{
+    predict_passes, GroundStationService, GroundStationServiceDefault,
+};
+use crate::service::propagation::{propagate, PropagationService, PropagationServiceDefault};
+use crate::utils::element_set::ElementSet;
+use crate::utils::sgp4::{Elements, Sgp4};
+use crate::utils::tle::{checksum, TLE};
+
+use super::backend::{each_backend, Backend};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+/// ISS element set with the epoch (day of 2008) and mean anomaly replaced
+fn iss(day: &str, mean_anomaly: &str) -> ElementSet {
+    let mut tle1 = ISS_TLE1.replace("264.51782528", day);
+    tle1.replace_range(68..69, &checksum(&tle1).to_string());
+
+    let mut tle2 = ISS_TLE2.replace("325.0288", mean_anomaly);
+    tle2.replace_range(68..69, &checksum(&tle2).to_string());
+
+    return ElementSet::TLE(TLE::new("ISS (ZARYA)", &tle1, &tle2).unwrap());
+}
+
+async fn create_service(satellite: Satellite) -> (ElementSetServiceDefault, Id) {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let id = satellite_repository
+        .write()
+        .await
+        .add(satellite)
+        .await
+        .unwrap();
+
+    let service = ElementSetServiceDefault::new(
+        satellite_repository,
+        create_inmemory_repository::<SatelliteElementSet>(),
+    );
+
+    return (service, id);
+}
+
+fn epochs(element_sets: &[ElementSet]) -> Vec<DateTime<Utc>> {
+    return element_sets.iter().map(|it| *it.get_epoch()).collect();
+}
+
+#[tokio::test]
+async fn history_contains_recorded_and_current_element_sets() {
+    let current = iss("264.51782528", "325.0288");
+    let (service, id) = create_service(Satellite::from(current.clone())).await;
+
+    let older = iss("260.00000000", "100.0000");
+    let oldest = iss("250.00000000", "200.0000");
+    assert!(service.add(id, &older).await.unwrap());
+    assert!(service.add(id, &oldest).await.unwrap());
+    assert!(!service.add(id, &older).await.unwrap());
+
+    let history = service.get_history(id, None, None).await.unwrap().unwrap();
+    assert_eq!(
+        epochs(&history),
+        vec![
+            *oldest.get_epoch(),
+            *older.get_epoch(),
+            *current.get_epoch()
+        ]
+    );
+
+    let filtered = service
+        .get_history(id, Some(*older.get_epoch()), Some(*older.get_epoch()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(epochs(&filtered), vec![*older.get_epoch()]);
+
+    // current element set is not duplicated once it's recorded
+    assert!(service.add(id, &current).await.unwrap());
+    let history = service.get_history(id, None, None).await.unwrap().unwrap();
+    assert_eq!(history.len(), 3);
+}
+
+/// Element set repository whose check misses the element sets another process adds before the
+/// insert
+struct Racing(Repository<SatelliteElementSet>);
+
+#[async_trait]
+impl RepositoryTrait<SatelliteElementSet> for Racing {
+    async fn get(&self, id: Id) -> Result<Option<SatelliteElementSet>, RepositoryError> {
+        return self.0.read().await.get(id).await;
+    }
+
+    async fn add(&mut self, entity: SatelliteElementSet) -> Result<Id, RepositoryError> {
+        return self.0.write().await.add(entity).await;
+    }
+
+    async fn delete(&mut self, id: Id) -> Result<(), RepositoryError> {
+        return self.0.write().await.delete(id).await;
+    }
+
+    async fn update(&mut self, entity: SatelliteElementSet) -> Result<(), RepositoryError> {
+        return self.0.write().await.update(entity).await;
+    }
+
+    async fn get_all(&self) -> Result<Vec<SatelliteElementSet>, RepositoryError> {
+        return self.0.read().await.get_all().await;
+    }
+
+    async fn query(
+        &self,
+        query: &Query<SatelliteElementSet>,
+    ) -> Result<Vec<SatelliteElementSet>, RepositoryError> {
+        return self.0.read().await.query(query).await;
+    }
+
+    async fn count(&self, _: &Query<SatelliteElementSet>) -> Result<usize, RepositoryError> {
+        return Ok(0);
+    }
+}
+
+#[tokio::test]
+async fn element_set_added_by_another_process_is_not_an_error() -> anyhow::Result<()> {
+    return each_backend(|backend| async move {
+        // only the unique key of the SQL backends catches it
+        if matches!(backend, Backend::InMemory) {
+            return Ok(());
+        }
+
+        let current = iss("264.51782528", "325.0288");
+        let satellite_repository = backend.repository::<Satellite>();
+        let id = satellite_repository
+            .write()
+            .await
+            .add(Satellite::from(current))
+            .await?;
+        let service = ElementSetServiceDefault::new(
+            satellite_repository,
+            Arc::new(RwLock::new(Racing(backend.repository()))),
+        );
+
+        let older = iss("260.00000000", "100.0000");
+        assert!(service.add(id, &older).await?);
+        assert!(!service.add(id, &older).await?);
+
+        let history = service.get_history(id, None, None).await?.unwrap();
+        assert_eq!(history.len(), 2);
+        Ok(())
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn closest_epoch_is_selected() {
+    let current = iss("264.51782528", "325.0288");
+    let (service, id) = create_service(Satellite::from(current.clone())).await;
+
+    let older = iss("260.00000000", "100.0000");
+    service.add(id, &older).await.unwrap();
+
+    for (time, expected) in [
+        (*older.get_epoch() - Duration::days(30), &older),
+        (*older.get_epoch() + Duration::days(2), &older),
+        (*older.get_epoch() + Duration::days(3), &current),
+        (*current.get_epoch() + Duration::days(30), &current),
+    ] {
+        let closest = service.get_closest(id, time).await.unwrap().unwrap();
+        assert_eq!(closest.get_epoch(), expected.get_epoch());
+    }
+}
+
+#[tokio::test]
+async fn unknown_satellite_has_no_history() {
+    let (service, id) = create_service(Satellite::from(iss("264.51782528", "325.0288"))).await;
+
+    let unknown = {
+        let mut unknown = id;
+        unknown += 1;
+        unknown
+    };
+
+    assert!(service
+        .get_history(unknown, None, None)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(service
+        .get_closest(unknown, Utc::now())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn satellite_without_element_set_has_no_closest() {
+    let satellite = serde_json::from_value::<Satellite>(serde_json::json!({
+        "id": null,
+        "name": "ISS (ZARYA)",
+        "catnr": null,
+        "tle1": null,
+        "tle2": null,
+        "omm": null
+    }))
+    .unwrap();
+    let (service, id) = create_service(satellite).await;
+
+    let error = service.get_closest(id, Utc::now()).await.err().unwrap();
+    assert!(matches!(
+        error.downcast_ref::<ElementSetError>(),
+        Some(ElementSetError::Missing(it)) if *it == id
+    ));
+
+    // recorded element sets are enough
+    let older = iss("260.00000000", "100.0000");
+    service.add(id, &older).await.unwrap();
+    let closest = service.get_closest(id, Utc::now()).await.unwrap().unwrap();
+    assert_eq!(closest.get_epoch(), older.get_epoch());
+}
+
+#[tokio::test]
+async fn past_position_uses_closest_element_set() {
+    let current = iss("264.51782528", "325.0288");
+    let (service, id) = create_service(Satellite::from(current.clone())).await;
+
+    let older = iss("260.00000000", "100.0000");
+    service.add(id, &older).await.unwrap();
+
+    let propagation_service = PropagationServiceDefault::new(Arc::new(service));
+
+    for (element_set, time) in [
+        (&older, *older.get_epoch() + Duration::hours(1)),
+        (&current, *current.get_epoch() - Duration::hours(1)),
+    ] {
+        let model = Sgp4::new(&Elements::from(element_set)).unwrap();
+        let expected = propagate(&model, time).unwrap().teme.position;
+
+        let actual = propagation_service
+            .get_position(id, time)
+            .await
+            .unwrap()
+            .unwrap()
+            .teme
+            .position;
+
+        assert_eq!(actual, expected);
+    }
+}
+
+#[tokio::test]
+async fn past_passes_use_closest_element_set() {
+    let current = iss("264.51782528", "325.0288");
+    let (service, id) = create_service(Satellite::from(current)).await;
+
+    let older = iss("260.00000000", "100.0000");
+    service.add(id, &older).await.unwrap();
+
+    let ground_station = GroundStation::new("Baikonur", 45.96, 63.31, 0.1, 5.0);
+    let ground_station_repository = create_inmemory_repository::<GroundStation>();
+    let ground_station_id = ground_station_repository
+        .write()
+        .await
+        .add(ground_station.clone())
+        .await
+        .unwrap();
+    let ground_station_service =
+        GroundStationServiceDefault::new(ground_station_repository, Arc::new(service));
+
+    let from = *older.get_epoch();
+    let to = from + Duration::days(1);
+    let expected = predict_passes(
+        &Sgp4::new(&Elements::from(&older)).unwrap(),
+        &ground_station.get_location(),
+        ground_station.get_min_elevation(),
+        from,
+        to,
+    )
+    .unwrap();
+    assert!(!expected.is_empty());
+
+    let actual = ground_station_service
+        .get_passes(ground_station_id, id, from, to)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        actual.iter().map(|it| it.tca.time).collect::<Vec<_>>(),
+        expected.iter().map(|it| it.tca.time).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+
+use crate::controller::utils::AppError;
+use crate::persistence::create_inmemory_repository;
+use crate::persistence::model::instrument::Instrument;
+use crate::persistence::model::satellite_instrument::SatelliteInstrument;
+use crate::persistence::repository::{HasId, Repository, RepositoryError};
+
+use super::backend::{each_backend, Backend};
+
+#[tokio::test]
+async fn repository_errors() -> Result<()> {
+    return each_backend(|backend| async move {
+        let instruments = backend.repository::<Instrument>();
+        let mut repository = instruments.write().await;
+
+        let id = repository.add(Instrument::new("MODIS")).await?;
+        let instrument = repository.get(id).await?.unwrap();
+        repository.delete(id).await?;
+        assert!(matches!(
+            repository.delete(id).await,
+            Err(RepositoryError::NotFound(it)) if it == id
+        ));
+        assert!(matches!(
+            repository.update(instrument).await,
+            Err(RepositoryError::NotFound(it)) if it == id
+        ));
+
+        // the SQL backends check the references
+        if !matches!(backend, Backend::InMemory) {
+            let satellite_instruments = backend.repository::<SatelliteInstrument>();
+            assert!(matches!(
+                satellite_instruments
+                    .write()
+                    .await
+                    .add(SatelliteInstrument::new(id, id))
+                    .await,
+                Err(RepositoryError::ForeignKey(_))
+            ));
+        }
+
+        return Ok(());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn inmemory_ids_are_unique() {
+    let repository = create_inmemory_repository::<Instrument>();
+    let mut repository = repository.write().await;
+
+    let id = repository.add(Instrument::new("MODIS")).await.unwrap();
+    let mut duplicate = Instrument::new("OLCI");
+    duplicate.set_id(id);
+    assert!(matches!(
+        repository.add(duplicate).await,
+        Err(RepositoryError::Conflict(_))
+    ));
+}
+
+#[tokio::test]
+async fn errors_are_answered_with_problem_details() {
+    let id = serde_json::from_str("7").unwrap();
+    let cases = [
+        (
+            AppError::from(RepositoryError::NotFound(id)),
+            StatusCode::NOT_FOUND,
+        ),
+        (
+            AppError::from(RepositoryError::ForeignKey(String::from("satellite_id"))),
+            StatusCode::CONFLICT,
+        ),
+        (
+            AppError::from(RepositoryError::Stale(String::from("serialization"))),
+            StatusCode::CONFLICT,
+        ),
+        // the repository error is found behind the context of the services
+        (
+            AppError::from(
+                Err::<(), _>(RepositoryError::Unavailable(String::from("timeout")))
+                    .context("can't fetch satellite")
+                    .unwrap_err(),
+            ),
+            StatusCode::SERVICE_UNAVAILABLE,
+        ),
+        (
+            AppError::BadRequest(String::from("expected non-empty name")),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            AppError::from(anyhow::anyhow!("connection string hunter2")),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    ];
+
+    for (error, status) in cases {
+        let response = error.into_response();
+        assert_eq!(response.status(), status);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem["type"], "about:blank");
+        assert_eq!(problem["status"], status.as_u16());
+        assert_eq!(problem["title"], status.canonical_reason().unwrap());
+        // internal errors are logged, not shown
+        assert!(!problem["detail"].as_str().unwrap().contains("hunter2"));
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn sqlite_write_lock_of_another_process_is_unavailable() -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    use crate::persistence::sqlite::{create_sqlite_repository, migration, Database};
+
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("zonaris.db");
+    let database = Database::open(&path)?;
+    migration::migrate(database.clone()).await?;
+
+    let other = rusqlite::Connection::open(&path)?;
+    other.execute_batch("BEGIN IMMEDIATE")?;
+
+    // the thread of the runtime isn't blocked until the lock is released
+    let started = Instant::now();
+    let instruments = create_sqlite_repository::<Instrument>(database);
+    assert!(matches!(
+        instruments
+            .write()
+            .await
+            .add(Instrument::new("MODIS"))
+            .await,
+        Err(RepositoryError::Unavailable(_))
+    ));
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    other.execute_batch("ROLLBACK")?;
+    instruments
+        .write()
+        .await
+        .add(Instrument::new("MODIS"))
+        .await?;
+
+    return Ok(());
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use crate::service::propagation::{sample_ground_track, split_at_antimeridian, TrackPoint};
+use crate::utils::sgp4::from_tle;
+
+const VANGUARD_TLE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+const VANGUARD_TLE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+fn start() -> DateTime<Utc> {
+    return Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+}
+
+fn point(seconds: i64, latitude: f64, longitude: f64) -> TrackPoint {
+    return TrackPoint {
+        time: start() + Duration::seconds(seconds),
+        latitude,
+        longitude,
+    };
+}
+
+#[test]
+fn track_without_crossing_is_single_line() {
+    let points = vec![
+        point(0, 0.0, 10.0),
+        point(60, 1.0, 20.0),
+        point(120, 2.0, 30.0),
+    ];
+
+    let lines = split_at_antimeridian(&points);
+
+    assert_eq!(lines, vec![points]);
+}
+
+#[test]
+fn eastward_crossing_is_split_at_180() {
+    let points = vec![point(0, 0.0, 170.0), point(60, 10.0, -170.0)];
+
+    let lines = split_at_antimeridian(&points);
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], vec![points[0], point(30, 5.0, 180.0)]);
+    assert_eq!(lines[1], vec![point(30, 5.0, -180.0), points[1]]);
+}
+
+#[test]
+fn westward_crossing_is_split_at_minus_180() {
+    let points = vec![point(0, 0.0, -175.0), point(60, -6.0, 175.0)];
+
+    let lines = split_at_antimeridian(&points);
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], vec![points[0], point(30, -3.0, -180.0)]);
+    assert_eq!(lines[1], vec![point(30, -3.0, 180.0), points[1]]);
+}
+
+#[test]
+fn sampled_track_never_jumps_over_antimeridian() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let from = model.epoch();
+    let to = from + Duration::hours(12);
+
+    let points = sample_ground_track(&model, from, to, Duration::seconds(60)).unwrap();
+    assert_eq!(points.first().unwrap().time, from);
+    assert_eq!(points.last().unwrap().time, to);
+
+    let lines = split_at_antimeridian(&points);
+    assert!(lines.len() > 1);
+
+    for line in &lines {
+        for pair in line.windows(2) {
+            assert!((pair[1].longitude - pair[0].longitude).abs() <= 180.0);
+            assert!(pair[1].time >= pair[0].time);
+        }
+    }
+}
+
+#[test]
+fn non_positive_step_is_rejected() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let from = model.epoch();
+
+    assert!(
+        sample_ground_track(&model, from, from + Duration::hours(1), Duration::zero()).is_err()
+    );
+}
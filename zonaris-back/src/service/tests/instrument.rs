@@ -0,0 +1,111 @@
+use crate::persistence::create_inmemory_repository;
+use crate::persistence::model::instrument::Instrument;
+use crate::persistence::model::instrument_data::InstrumentData;
+use crate::persistence::model::oceancolor::OceanColorMapping;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_instrument::SatelliteInstrument;
+use crate::persistence::repository::{HasId, Id, Repository};
+use crate::service::instrument::{InstrumentError, InstrumentService, InstrumentServiceDefault};
+
+const TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+fn is_error(error: &anyhow::Error, expected: fn(&InstrumentError) -> bool) -> bool {
+    return error
+        .downcast_ref::<InstrumentError>()
+        .is_some_and(expected);
+}
+
+#[tokio::test]
+async fn instruments_and_links() {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let instrument_data_repository = create_inmemory_repository::<InstrumentData>();
+    let service = InstrumentServiceDefault::new(
+        create_inmemory_repository::<Instrument>(),
+        satellite_repository.clone(),
+        create_inmemory_repository::<SatelliteInstrument>(),
+        instrument_data_repository.clone(),
+        create_inmemory_repository::<OceanColorMapping>(),
+    );
+
+    let satellite = satellite_repository
+        .write()
+        .await
+        .add(Satellite::new("ISS", TLE1, TLE2).unwrap())
+        .await
+        .unwrap();
+    let modis = service.add(Instrument::new("MODIS")).await.unwrap();
+    let olci = service.add(Instrument::new("OCLI")).await.unwrap();
+
+    let renamed = service.update(olci, "OLCI").await.unwrap().unwrap();
+    assert_eq!(renamed.get_name(), "OLCI");
+    assert_eq!(service.get_all().await.unwrap().len(), 2);
+
+    let link = service.link(satellite, modis).await.unwrap();
+    service.link(satellite, olci).await.unwrap();
+
+    let error = service.link(satellite, modis).await.unwrap_err();
+    assert!(is_error(&error, |it| matches!(
+        it,
+        InstrumentError::AlreadyLinked { .. }
+    )));
+    let missing = serde_json::from_str::<Id>("-1").unwrap();
+    let error = service.link(missing, modis).await.unwrap_err();
+    assert!(is_error(&error, |it| matches!(
+        it,
+        InstrumentError::SatelliteNotFound(_)
+    )));
+
+    let instruments = service
+        .get_by_satellite_id(satellite)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(instruments.len(), 2);
+    assert!(instruments
+        .iter()
+        .any(|(it, instrument)| it.get_id() == Some(link) && instrument.get_name() == "MODIS"));
+    assert!(service
+        .get_by_satellite_id(missing)
+        .await
+        .unwrap()
+        .is_none());
+
+    // linked instruments and links with data can't be removed
+    let error = service.delete(modis).await.unwrap_err();
+    assert!(is_error(&error, |it| matches!(
+        it,
+        InstrumentError::HasSatellites(1)
+    )));
+
+    let data = instrument_data_repository
+        .write()
+        .await
+        .add(InstrumentData::new(link, String::from("granule.nc")))
+        .await
+        .unwrap();
+    let error = service.unlink(satellite, modis).await.unwrap_err();
+    assert!(is_error(&error, |it| matches!(
+        it,
+        InstrumentError::LinkInUse(1)
+    )));
+
+    instrument_data_repository
+        .write()
+        .await
+        .delete(data)
+        .await
+        .unwrap();
+    assert!(service.unlink(satellite, modis).await.unwrap());
+    assert!(!service.unlink(satellite, modis).await.unwrap());
+    assert!(service.delete(modis).await.unwrap());
+    assert!(service.get(modis).await.unwrap().is_none());
+
+    let instruments = service
+        .get_by_satellite_id(satellite)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(instruments.len(), 1);
+    assert_eq!(instruments[0].1.get_name(), "OLCI");
+}
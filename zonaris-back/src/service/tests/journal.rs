@@ -0,0 +1,125 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+use crate::persistence::journal::Journals;
+use crate::persistence::model::instrument::Instrument;
+use crate::persistence::repository::{HasId, Id, Repository};
+use crate::persistence::unit_of_work::transaction;
+use crate::persistence::InMemoryRepository;
+
+fn open(directory: &Path) -> Result<(Journals, InMemoryRepository<Instrument>)> {
+    let journals = Journals::new(Some(directory))?;
+    let repository = journals.repository::<Instrument>()?;
+    return Ok((journals, repository));
+}
+
+async fn names(repository: &InMemoryRepository<Instrument>) -> Result<Vec<String>> {
+    let mut instruments = repository.read().await.get_all().await?;
+    instruments.sort_by_key(|it| it.get_id());
+    return Ok(instruments.iter().map(|it| it.get_name().clone()).collect());
+}
+
+#[tokio::test]
+async fn records_are_reloaded_from_the_log_and_the_snapshot() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+
+    {
+        let (_journals, repository) = open(directory.path())?;
+        let mut instruments = repository.write().await;
+        instruments.add(Instrument::new("MODIS")).await?;
+        let olci = instruments.add(Instrument::new("OCLI")).await?;
+        let viirs = instruments.add(Instrument::new("VIIRS")).await?;
+        let mut renamed = instruments.get(olci).await?.unwrap();
+        renamed.set_name(String::from("OLCI"));
+        instruments.update(renamed).await?;
+        instruments.delete(viirs).await?;
+        // dropped without a snapshot as if the process crashed
+    }
+
+    let (journals, repository) = open(directory.path())?;
+    assert_eq!(names(&repository).await?, ["MODIS", "OLCI"]);
+
+    // the id of the deleted record isn't reused
+    let seawifs = repository
+        .write()
+        .await
+        .add(Instrument::new("SeaWiFS"))
+        .await?;
+    assert_eq!(seawifs, serde_json::from_str::<Id>("3")?);
+    repository.write().await.delete(seawifs).await?;
+    journals.snapshot()?;
+
+    let (_journals, repository) = open(directory.path())?;
+    assert_eq!(names(&repository).await?, ["MODIS", "OLCI"]);
+    let msi = repository.write().await.add(Instrument::new("MSI")).await?;
+    assert_eq!(msi, serde_json::from_str::<Id>("4")?);
+
+    return Ok(());
+}
+
+#[tokio::test]
+async fn torn_entry_is_dropped() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+
+    {
+        let (_journals, repository) = open(directory.path())?;
+        repository
+            .write()
+            .await
+            .add(Instrument::new("MODIS"))
+            .await?;
+    }
+
+    // the write of the last entry was interrupted
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(directory.path().join("instrument.log"))?
+        .write_all(br#"{"Put":{"id":1,"na"#)?;
+
+    {
+        let (_journals, repository) = open(directory.path())?;
+        assert_eq!(names(&repository).await?, ["MODIS"]);
+        repository
+            .write()
+            .await
+            .add(Instrument::new("OLCI"))
+            .await?;
+    }
+
+    let (_journals, repository) = open(directory.path())?;
+    assert_eq!(names(&repository).await?, ["MODIS", "OLCI"]);
+
+    return Ok(());
+}
+
+#[tokio::test]
+async fn rolled_back_changes_are_not_reloaded() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+
+    {
+        let (_journals, repository) = open(directory.path())?;
+        let modis = repository
+            .write()
+            .await
+            .add(Instrument::new("MODIS"))
+            .await?;
+
+        let result: Result<()> = transaction(async {
+            let mut instruments = repository.write().await;
+            instruments.delete(modis).await?;
+            instruments.add(Instrument::new("OLCI")).await?;
+
+            Err(anyhow!("failed after the writes"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(names(&repository).await?, ["MODIS"]);
+    }
+
+    let (_journals, repository) = open(directory.path())?;
+    assert_eq!(names(&repository).await?, ["MODIS"]);
+
+    return Ok(());
+}
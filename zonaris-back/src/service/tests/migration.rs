@@ -0,0 +1,106 @@
+use chrono::{TimeZone, Utc};
+
+use crate::persistence::migration::{
+    check_drift, status_of, AppliedMigration, Migration, MigrationError, MigrationState,
+};
+
+const TEST_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        up: "CREATE TABLE a (id SERIAL PRIMARY KEY);",
+        down: "DROP TABLE a;",
+    },
+    Migration {
+        version: 2,
+        name: "b",
+        up: "CREATE TABLE b (id SERIAL PRIMARY KEY);",
+        down: "DROP TABLE b;",
+    },
+];
+
+fn applied(migration: &Migration) -> AppliedMigration {
+    return AppliedMigration {
+        version: migration.version,
+        name: String::from(migration.name),
+        checksum: migration.checksum(),
+        applied_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+    };
+}
+
+/// Migrations of every SQL backend of the build
+#[allow(clippy::vec_init_then_push)]
+fn backend_migrations() -> Vec<&'static [Migration]> {
+    let mut result = Vec::new();
+    #[cfg(feature = "postgres")]
+    result.push(crate::persistence::postgres::migration::MIGRATIONS);
+    #[cfg(feature = "sqlite")]
+    result.push(crate::persistence::sqlite::migration::MIGRATIONS);
+    return result;
+}
+
+#[test]
+fn migrations_are_numbered_without_gaps() {
+    for migrations in backend_migrations() {
+        for (idx, migration) in migrations.iter().enumerate() {
+            assert_eq!(migration.version, idx as i32 + 1);
+            assert!(!migration.up.trim().is_empty());
+            assert!(!migration.down.trim().is_empty());
+        }
+    }
+}
+
+#[cfg(all(feature = "postgres", feature = "sqlite"))]
+#[test]
+fn backends_have_the_same_migrations() {
+    let names = |migrations: &[Migration]| {
+        migrations
+            .iter()
+            .map(|it| (it.version, it.name))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(crate::persistence::postgres::migration::MIGRATIONS),
+        names(crate::persistence::sqlite::migration::MIGRATIONS)
+    );
+}
+
+#[test]
+fn drifted_history_is_refused() {
+    let at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    // partially applied history is fine, the rest is pending
+    let history = vec![applied(&TEST_MIGRATIONS[0])];
+    assert!(check_drift(TEST_MIGRATIONS, &history).is_ok());
+    let status = status_of(TEST_MIGRATIONS, &history);
+    assert_eq!(status[0].state, MigrationState::Applied(at));
+    assert_eq!(status[1].state, MigrationState::Pending);
+
+    let mut changed = applied(&TEST_MIGRATIONS[0]);
+    changed.checksum = TEST_MIGRATIONS[1].checksum();
+    assert_eq!(
+        check_drift(TEST_MIGRATIONS, &[changed]),
+        Err(MigrationError::Changed(1))
+    );
+
+    let mut unknown = applied(&TEST_MIGRATIONS[1]);
+    unknown.version = 3;
+    let history = vec![
+        applied(&TEST_MIGRATIONS[0]),
+        applied(&TEST_MIGRATIONS[1]),
+        unknown,
+    ];
+    assert_eq!(
+        check_drift(TEST_MIGRATIONS, &history),
+        Err(MigrationError::Unknown(3))
+    );
+    assert_eq!(
+        status_of(TEST_MIGRATIONS, &history)[2].state,
+        MigrationState::Unknown(at)
+    );
+
+    assert_eq!(
+        check_drift(TEST_MIGRATIONS, &[applied(&TEST_MIGRATIONS[1])]),
+        Err(MigrationError::Skipped(1))
+    );
+}
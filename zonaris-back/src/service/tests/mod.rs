@@ -1 +1,27 @@
 mod allow_cross_origin;
+mod backend;
+mod celestrak_file;
+mod celestrak_job;
+mod cli;
+mod config;
+mod element_set;
+mod error;
+mod ground_track;
+mod instrument;
+mod journal;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+mod migration;
+mod omm;
+mod passes;
+mod propagation;
+mod query;
+mod satcat;
+mod satellite;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+mod schema;
+mod seed;
+mod spacetrack;
+mod standin;
+mod tle;
+mod tracking;
+mod unit_of_work;
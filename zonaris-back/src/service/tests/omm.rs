@@ -0,0 +1,202 @@
+use crate::persistence::model::satellite::Satellite;
+use crate::service::propagation::create_model;
+use crate::utils::element_set::ElementSet;
+use crate::utils::omm::{parse_csv, parse_json, parse_xml, OmmError, OMM};
+use crate::utils::tle::{parse_catnr, TLE};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+const ISS_JSON: &str = r#"[{
+    "OBJECT_NAME": "ISS (ZARYA)",
+    "OBJECT_ID": "1998-067A",
+    "EPOCH": "2008-09-20T12:25:40.104192",
+    "MEAN_MOTION": 15.72125391,
+    "ECCENTRICITY": 0.0006703,
+    "INCLINATION": 51.6416,
+    "RA_OF_ASC_NODE": 247.4627,
+    "ARG_OF_PERICENTER": 130.536,
+    "MEAN_ANOMALY": 325.0288,
+    "EPHEMERIS_TYPE": 0,
+    "CLASSIFICATION_TYPE": "U",
+    "NORAD_CAT_ID": 25544,
+    "ELEMENT_SET_NO": 292,
+    "REV_AT_EPOCH": 56353,
+    "BSTAR": -1.1606e-5,
+    "MEAN_MOTION_DOT": -2.182e-5,
+    "MEAN_MOTION_DDOT": 0
+}]"#;
+
+// Space-Track encodes every value as a string
+const ISS_JSON_STRINGS: &str = r#"[{
+    "OBJECT_NAME": "ISS (ZARYA)",
+    "OBJECT_ID": "1998-067A",
+    "EPOCH": "2008-09-20T12:25:40.104192",
+    "MEAN_MOTION": "15.72125391",
+    "ECCENTRICITY": "0.00067030",
+    "INCLINATION": "51.6416",
+    "RA_OF_ASC_NODE": "247.4627",
+    "ARG_OF_PERICENTER": "130.5360",
+    "MEAN_ANOMALY": "325.0288",
+    "EPHEMERIS_TYPE": "0",
+    "CLASSIFICATION_TYPE": "U",
+    "NORAD_CAT_ID": "25544",
+    "ELEMENT_SET_NO": "292",
+    "REV_AT_EPOCH": "56353",
+    "BSTAR": "-0.000011606",
+    "MEAN_MOTION_DOT": "-0.00002182",
+    "MEAN_MOTION_DDOT": "0.0000000000000",
+    "DECAYED": null
+}]"#;
+
+const ISS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ndm xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+<omm id="CCSDS_OMM_VERS" version="2.0">
+  <header><CREATION_DATE/><ORIGINATOR/></header>
+  <body><segment>
+    <metadata>
+      <OBJECT_NAME>ISS (ZARYA)</OBJECT_NAME>
+      <OBJECT_ID>1998-067A</OBJECT_ID>
+      <CENTER_NAME>EARTH</CENTER_NAME>
+      <REF_FRAME>TEME</REF_FRAME>
+      <TIME_SYSTEM>UTC</TIME_SYSTEM>
+      <MEAN_ELEMENT_THEORY>SGP4</MEAN_ELEMENT_THEORY>
+    </metadata>
+    <data>
+      <meanElements>
+        <EPOCH>2008-09-20T12:25:40.104192</EPOCH>
+        <MEAN_MOTION>15.72125391</MEAN_MOTION>
+        <ECCENTRICITY>.0006703</ECCENTRICITY>
+        <INCLINATION>51.6416</INCLINATION>
+        <RA_OF_ASC_NODE>247.4627</RA_OF_ASC_NODE>
+        <ARG_OF_PERICENTER>130.5360</ARG_OF_PERICENTER>
+        <MEAN_ANOMALY>325.0288</MEAN_ANOMALY>
+      </meanElements>
+      <tleParameters>
+        <EPHEMERIS_TYPE>0</EPHEMERIS_TYPE>
+        <CLASSIFICATION_TYPE>U</CLASSIFICATION_TYPE>
+        <NORAD_CAT_ID>25544</NORAD_CAT_ID>
+        <ELEMENT_SET_NO>292</ELEMENT_SET_NO>
+        <REV_AT_EPOCH>56353</REV_AT_EPOCH>
+        <BSTAR>-.11606E-4</BSTAR>
+        <MEAN_MOTION_DOT>-.2182E-4</MEAN_MOTION_DOT>
+        <MEAN_MOTION_DDOT>0</MEAN_MOTION_DDOT>
+      </tleParameters>
+    </data>
+  </segment></body>
+</omm>
+</ndm>"#;
+
+const ISS_CSV: &str = "OBJECT_NAME,OBJECT_ID,EPOCH,MEAN_MOTION,ECCENTRICITY,INCLINATION,RA_OF_ASC_NODE,ARG_OF_PERICENTER,MEAN_ANOMALY,EPHEMERIS_TYPE,CLASSIFICATION_TYPE,NORAD_CAT_ID,ELEMENT_SET_NO,REV_AT_EPOCH,BSTAR,MEAN_MOTION_DOT,MEAN_MOTION_DDOT
+ISS (ZARYA),1998-067A,2008-09-20T12:25:40.104192,15.72125391,.0006703,51.6416,247.4627,130.5360,325.0288,0,U,25544,292,56353,-.11606E-4,-.2182E-4,0
+";
+
+fn assert_same_elements(omm: &OMM, tle: &TLE) {
+    assert_eq!(omm.get_name(), "ISS (ZARYA)");
+    assert_eq!(omm.get_international_designator(), "1998-067A");
+    assert_eq!(omm.get_catnr(), tle.get_catnr());
+    assert_eq!(omm.get_classification(), tle.get_classification());
+    assert_eq!(omm.get_element_set_number(), tle.get_element_set_number());
+    assert_eq!(omm.get_revolution_number(), tle.get_revolution_number());
+
+    assert!((*omm.get_epoch() - *tle.get_epoch()).num_microseconds() == Some(0));
+    assert_eq!(omm.get_mean_motion(), tle.get_mean_motion());
+    assert_eq!(omm.get_eccentricity(), tle.get_eccentricity());
+    assert_eq!(omm.get_inclination(), tle.get_inclination());
+    assert_eq!(omm.get_right_ascension(), tle.get_right_ascension());
+    assert_eq!(omm.get_argument_of_perigee(), tle.get_argument_of_perigee());
+    assert_eq!(omm.get_mean_anomaly(), tle.get_mean_anomaly());
+    assert!((omm.get_bstar() - tle.get_bstar()).abs() < 1e-15);
+    assert!((omm.get_mean_motion_dot() - tle.get_mean_motion_dot()).abs() < 1e-15);
+}
+
+#[test]
+fn all_encodings_give_same_elements_as_tle() {
+    let tle = TLE::new("ISS (ZARYA)", ISS_TLE1, ISS_TLE2).unwrap();
+
+    for omms in [
+        parse_json(ISS_JSON).unwrap(),
+        parse_json(ISS_JSON_STRINGS).unwrap(),
+        parse_xml(ISS_XML).unwrap(),
+        parse_csv(ISS_CSV).unwrap(),
+    ] {
+        assert_eq!(omms.len(), 1);
+        assert_same_elements(&omms[0], &tle);
+    }
+}
+
+#[test]
+fn nine_digit_catalog_number_is_supported() {
+    let json = ISS_JSON.replace("25544", "270000123");
+    let omm = parse_json(&json).unwrap().remove(0);
+    assert_eq!(omm.get_catnr(), 270000123);
+
+    let satellite = Satellite::from(omm);
+    assert_eq!(satellite.get_catnr(), Some(270000123));
+    assert!(satellite.get_tle1().is_none());
+
+    match satellite.get_element_set().unwrap().unwrap() {
+        ElementSet::OMM(omm) => assert_eq!(omm.get_catnr(), 270000123),
+        ElementSet::TLE(_) => panic!("element set should be stored as OMM"),
+    }
+}
+
+#[test]
+fn alpha5_catalog_number_is_supported() {
+    assert_eq!(parse_catnr("25544"), Some(25544));
+    assert_eq!(parse_catnr("A0000"), Some(100000));
+    assert_eq!(parse_catnr("E8493"), Some(148493));
+    assert_eq!(parse_catnr("J2931"), Some(182931));
+    assert_eq!(parse_catnr("P0001"), Some(230001));
+    assert_eq!(parse_catnr("Z9999"), Some(339999));
+
+    assert_eq!(parse_catnr("I0000"), None);
+    assert_eq!(parse_catnr("O0000"), None);
+    assert_eq!(parse_catnr("a0000"), None);
+    assert_eq!(parse_catnr("A00X0"), None);
+}
+
+#[test]
+fn omm_and_tle_sourced_satellites_propagate_identically() {
+    let from_tle = Satellite::new("ISS (ZARYA)", ISS_TLE1, ISS_TLE2).unwrap();
+    let from_omm = Satellite::from(parse_xml(ISS_XML).unwrap().remove(0));
+
+    let tle_model = create_model(&from_tle.get_element_set().unwrap().unwrap()).unwrap();
+    let omm_model = create_model(&from_omm.get_element_set().unwrap().unwrap()).unwrap();
+
+    for minutes in [0.0, 90.0, 1440.0] {
+        let a = tle_model.propagate(minutes).unwrap().position;
+        let b = omm_model.propagate(minutes).unwrap().position;
+
+        for axis in 0..3 {
+            assert!((a[axis] - b[axis]).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn stored_omm_round_trips() {
+    let omm = parse_json(ISS_JSON).unwrap().remove(0);
+    let restored = OMM::from_json(&omm.to_json()).unwrap();
+
+    let tle = TLE::new("ISS (ZARYA)", ISS_TLE1, ISS_TLE2).unwrap();
+    assert_same_elements(&restored, &tle);
+}
+
+#[test]
+fn malformed_omm_is_rejected() {
+    let missing = ISS_JSON.replace("\"MEAN_MOTION\": 15.72125391,", "");
+    assert!(matches!(
+        parse_json(&missing),
+        Err(OmmError::MissingField("MEAN_MOTION"))
+    ));
+
+    let invalid = ISS_CSV.replace("2008-09-20T12:25:40.104192", "yesterday");
+    assert!(matches!(
+        parse_csv(&invalid),
+        Err(OmmError::InvalidField { field: "EPOCH", .. })
+    ));
+
+    let hyperbolic = ISS_XML.replace(".0006703", "1.5");
+    assert!(parse_xml(&hyperbolic).is_err());
+}
@@ -0,0 +1,128 @@
+use chrono::Duration;
+
+use crate::service::ground_station::predict_passes;
+use crate::service::propagation::{look_angles_at, propagate};
+use crate::utils::geodesy::{geodetic_to_ecef, look_angles, EcefState, Geodetic};
+use crate::utils::sgp4::from_tle;
+
+const VANGUARD_TLE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+const VANGUARD_TLE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+const GEO_TLE1: &str = "1 28884U 05041A   24001.50000000 -.00000289  00000-0  00000-0 0  9992";
+const GEO_TLE2: &str = "2 28884   0.0200 270.0000 0001000  90.0000 100.0000  1.00270000 67894";
+
+fn observer() -> Geodetic {
+    return Geodetic {
+        latitude: 30.0,
+        longitude: 30.0,
+        altitude: 0.2,
+    };
+}
+
+fn satellite_at(offset: [f64; 3]) -> EcefState {
+    let origin = geodetic_to_ecef(&Geodetic {
+        latitude: 0.0,
+        longitude: 0.0,
+        altitude: 0.0,
+    });
+
+    return EcefState {
+        position: [
+            origin[0] + offset[0],
+            origin[1] + offset[1],
+            origin[2] + offset[2],
+        ],
+        velocity: [0.0, 0.0, 0.0],
+    };
+}
+
+#[test]
+fn look_angles_of_topocentric_directions() {
+    let equator = Geodetic {
+        latitude: 0.0,
+        longitude: 0.0,
+        altitude: 0.0,
+    };
+
+    let zenith = look_angles(&equator, &satellite_at([500.0, 0.0, 0.0]));
+    assert!((zenith.elevation - 90.0).abs() < 1e-6);
+    assert!((zenith.range - 500.0).abs() < 1e-6);
+
+    let north = look_angles(&equator, &satellite_at([0.0, 0.0, 100.0]));
+    assert!(north.azimuth.abs() < 1e-6);
+    assert!(north.elevation.abs() < 1e-6);
+
+    let east = look_angles(&equator, &satellite_at([0.0, 100.0, 0.0]));
+    assert!((east.azimuth - 90.0).abs() < 1e-6);
+}
+
+#[test]
+fn passes_are_ordered_and_bounded_by_min_elevation() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let from = model.epoch();
+    let to = from + Duration::days(2);
+    let min_elevation = 10.0;
+
+    let passes = predict_passes(&model, &observer(), min_elevation, from, to).unwrap();
+    assert!(!passes.is_empty());
+
+    for pass in &passes {
+        assert!(pass.aos.time < pass.tca.time && pass.tca.time < pass.los.time);
+        assert!(pass.aos.time <= to && pass.los.time >= from);
+
+        assert!((pass.aos.elevation - min_elevation).abs() < 0.1);
+        assert!((pass.los.elevation - min_elevation).abs() < 0.1);
+        assert!(pass.tca.elevation >= min_elevation);
+
+        let middle = pass.aos.time + (pass.los.time - pass.aos.time) / 2;
+        let angles = look_angles_at(&model, &observer(), middle).unwrap();
+        assert!(angles.elevation <= pass.tca.elevation + 1e-6);
+    }
+
+    for pair in passes.windows(2) {
+        assert!(pair[0].los.time < pair[1].aos.time);
+    }
+}
+
+#[test]
+fn pass_in_progress_is_reported_with_its_rise() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let from = model.epoch();
+    let to = from + Duration::days(1);
+
+    let passes = predict_passes(&model, &observer(), 0.0, from, to).unwrap();
+    let pass = passes[0];
+    let inside = pass.aos.time + (pass.los.time - pass.aos.time) / 2;
+
+    let clipped = predict_passes(&model, &observer(), 0.0, inside, to).unwrap();
+    assert!(
+        (clipped[0].aos.time - pass.aos.time)
+            .num_milliseconds()
+            .abs()
+            < 200
+    );
+    assert!(
+        (clipped[0].los.time - pass.los.time)
+            .num_milliseconds()
+            .abs()
+            < 200
+    );
+}
+
+#[test]
+fn geostationary_satellite_never_sets() {
+    let model = from_tle(GEO_TLE1, GEO_TLE2).unwrap();
+    let from = model.epoch();
+    let to = from + Duration::hours(6);
+
+    let subsatellite = propagate(&model, from).unwrap().geodetic;
+    let observer = Geodetic {
+        latitude: 10.0,
+        longitude: subsatellite.longitude,
+        altitude: 0.0,
+    };
+
+    let passes = predict_passes(&model, &observer, 5.0, from, to).unwrap();
+    assert_eq!(passes.len(), 1);
+    assert!(passes[0].tca.elevation > 70.0);
+}
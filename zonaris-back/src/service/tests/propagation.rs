@@ -0,0 +1,157 @@
+use axum::http::StatusCode;
+use chrono::Duration;
+
+use crate::controller::satellite::propagation_error;
+use crate::service::ground_station::predict_passes;
+use crate::service::propagation::propagate;
+use crate::utils::geodesy::Geodetic;
+use crate::utils::sgp4::{from_tle, Sgp4Error, EARTH_RADIUS_KM};
+
+const VANGUARD_TLE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+const VANGUARD_TLE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+const GEO_TLE1: &str = "1 28884U 05041A   24001.50000000 -.00000289  00000-0  00000-0 0  9992";
+const GEO_TLE2: &str = "2 28884   0.0200 270.0000 0001000  90.0000 100.0000  1.00270000 67894";
+
+const MOLNIYA_TLE1: &str = "1 40296U 14069A   24001.50000000  .00000100  00000-0  10000-3 0  9998";
+const MOLNIYA_TLE2: &str = "2 40296  63.4000 100.0000 7000000 270.0000  10.0000  2.00600000 76544";
+
+// very low orbit with a large drag term, decays within days of its epoch
+const DECAYING_TLE1: &str = "1 99999U 24001A   24001.50000000  .01000000  00000-0  50000-2 0  9992";
+const DECAYING_TLE2: &str = "2 99999  51.6000 100.0000 0010000  90.0000 270.0000 16.20000000 12348";
+
+fn assert_close(actual: [f64; 3], expected: [f64; 3], tolerance: f64) {
+    for i in 0..3 {
+        assert!(
+            (actual[i] - expected[i]).abs() < tolerance,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+fn norm(v: [f64; 3]) -> f64 {
+    return (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+}
+
+// reference values are taken from the SGP4 verification output (AIAA 2006-6753)
+#[test]
+fn vanguard_at_epoch() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let state = model.propagate(0.0).unwrap();
+
+    assert!(!model.is_deep_space());
+    assert_close(
+        state.position,
+        [7022.46529266, -1400.08296755, 0.03995155],
+        1e-3,
+    );
+    assert_close(
+        state.velocity,
+        [1.893841015, 6.405893759, 4.534807250],
+        1e-6,
+    );
+}
+
+#[test]
+fn vanguard_after_six_hours() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let state = model.propagate(360.0).unwrap();
+
+    assert_close(
+        state.position,
+        [-7154.03120202, -3783.17682504, -3536.19412294],
+        1e-3,
+    );
+    assert_close(
+        state.velocity,
+        [4.741887409, -4.151817765, -2.093935425],
+        1e-6,
+    );
+}
+
+#[test]
+fn propagate_to_matches_minutes_since_epoch() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+
+    let by_time = model
+        .propagate_to(&(model.epoch() + Duration::minutes(360)))
+        .unwrap();
+    let by_minutes = model.propagate(360.0).unwrap();
+
+    assert_close(by_time.position, by_minutes.position, 1e-6);
+}
+
+#[test]
+fn geostationary_stays_over_same_point() {
+    let model = from_tle(GEO_TLE1, GEO_TLE2).unwrap();
+    assert!(model.is_deep_space());
+
+    let first = propagate(&model, model.epoch()).unwrap();
+    assert!(first.geodetic.latitude.abs() < 0.1);
+    assert!((first.geodetic.altitude - 35786.0).abs() < 50.0);
+
+    for hours in [6, 12, 18, 24, 72] {
+        let position = propagate(&model, model.epoch() + Duration::hours(hours)).unwrap();
+
+        assert!(position.geodetic.latitude.abs() < 0.1);
+        assert!((position.geodetic.altitude - 35786.0).abs() < 50.0);
+        assert!((position.geodetic.longitude - first.geodetic.longitude).abs() < 1.0);
+    }
+}
+
+#[test]
+fn molniya_stays_between_perigee_and_apogee() {
+    let model = from_tle(MOLNIYA_TLE1, MOLNIYA_TLE2).unwrap();
+    assert!(model.is_deep_space());
+
+    // a = (mu / n^2)^(1/3) for n = 2.006 rev/day
+    let semi_major_axis = 26554.0;
+    let perigee = semi_major_axis * (1.0 - 0.7);
+    let apogee = semi_major_axis * (1.0 + 0.7);
+
+    for minutes in (0..10 * 1440).step_by(30) {
+        let state = model.propagate(minutes as f64).unwrap();
+        let radius = norm(state.position);
+
+        assert!(radius > perigee - 200.0 && radius < apogee + 200.0);
+        assert!(radius > EARTH_RADIUS_KM);
+    }
+}
+
+#[test]
+fn malformed_tle_is_rejected() {
+    assert!(from_tle("1 00005U", VANGUARD_TLE2).is_err());
+    assert!(from_tle(VANGUARD_TLE1, "2 00005  34.2682 348.7242").is_err());
+}
+
+#[test]
+fn decayed_element_set_is_a_client_error() {
+    let model = from_tle(DECAYING_TLE1, DECAYING_TLE2).unwrap();
+    let epoch = model.epoch();
+    assert!(propagate(&model, epoch).is_ok());
+
+    let error = propagate(&model, epoch + Duration::days(365))
+        .err()
+        .unwrap();
+    assert!(error.downcast_ref::<Sgp4Error>().is_some(), "{}", error);
+
+    let error = propagation_error(error);
+    assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // passes of a window after the decay
+    let observer = Geodetic {
+        latitude: 30.0,
+        longitude: 30.0,
+        altitude: 0.2,
+    };
+    let from = epoch + Duration::days(365);
+    let error = predict_passes(&model, &observer, 10.0, from, from + Duration::days(1))
+        .err()
+        .unwrap();
+    assert_eq!(
+        propagation_error(error).status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+}
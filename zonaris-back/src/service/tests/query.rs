@@ -0,0 +1,178 @@
+use anyhow::Result;
+
+use crate::persistence::model::ground_station::GroundStation;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::query::Query;
+use crate::persistence::repository::HasId;
+use crate::persistence::Repository;
+use crate::utils::tle::TLE;
+
+use super::backend::{each_backend, Backend};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+fn names<T>(entities: &[T], name: impl Fn(&T) -> String) -> Vec<String> {
+    return entities.iter().map(name).collect();
+}
+
+async fn ground_stations(backend: &Backend) -> Result<Repository<GroundStation>> {
+    let repository = backend.repository();
+    for (name, latitude) in [
+        ("a", 10.0),
+        ("b", 20.0),
+        ("c", 10.0),
+        ("d", 30.0),
+        ("e", 20.0),
+    ] {
+        repository
+            .write()
+            .await
+            .add(GroundStation::new(name, latitude, 0.0, 0.0, 5.0))
+            .await?;
+    }
+    return Ok(repository);
+}
+
+#[tokio::test]
+async fn filters_order_and_limit() -> Result<()> {
+    return each_backend(|backend| async move {
+        let repository = ground_stations(&backend).await?;
+        let repository = repository.read().await;
+
+        let query = Query::new()
+            .filter(GroundStation::LATITUDE.ge(20.0))
+            .order_by(GroundStation::LATITUDE.desc());
+        let result = repository.query(&query).await?;
+        // ties are ordered by the id, the entities were added in the order of their names
+        assert_eq!(names(&result, |it| it.get_name().clone()), ["d", "b", "e"]);
+        assert_eq!(repository.count(&query).await?, 3);
+
+        let query = Query::new()
+            .filter(
+                GroundStation::NAME
+                    .is_in(["a", "d"])
+                    .or(GroundStation::LATITUDE.eq(20.0)),
+            )
+            .filter(GroundStation::NAME.ne("e"))
+            .order_by(GroundStation::NAME.asc())
+            .offset(1)
+            .limit(2);
+        let result = repository.query(&query).await?;
+        assert_eq!(names(&result, |it| it.get_name().clone()), ["b", "d"]);
+        // the page is ignored by count
+        assert_eq!(repository.count(&query).await?, 3);
+
+        let empty = Vec::<String>::new();
+        let query = Query::new().filter(GroundStation::NAME.is_in(empty));
+        assert!(repository.query(&query).await?.is_empty());
+
+        return Ok(());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn keyset_pages_cover_every_entity_once() -> Result<()> {
+    return each_backend(|backend| async move {
+        let repository = ground_stations(&backend).await?;
+        let repository = repository.read().await;
+
+        let mut result = Vec::new();
+        let mut query = Query::new()
+            .order_by(GroundStation::LATITUDE.asc())
+            .limit(2);
+        loop {
+            let page = repository.page(&query).await?;
+            result.extend(page.items);
+            match page.next {
+                Some(cursor) => {
+                    query = Query::new()
+                        .order_by(GroundStation::LATITUDE.asc())
+                        .limit(2)
+                        .after(cursor)
+                }
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            names(&result, |it| it.get_name().clone()),
+            ["a", "c", "b", "e", "d"]
+        );
+
+        return Ok(());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn comparisons_with_null_match_nothing() -> Result<()> {
+    return each_backend(|backend| async move {
+        let repository = backend.repository();
+        let mut repository = repository.write().await;
+
+        let mut without_catnr = Satellite::from(TLE::new("NO CATNR", ISS_TLE1, ISS_TLE2)?);
+        without_catnr.set_catnr(None);
+        let without_catnr = repository.add(without_catnr).await?;
+        let iss = repository
+            .add(Satellite::new("ISS", ISS_TLE1, ISS_TLE2)?)
+            .await?;
+
+        let ids = |satellites: Vec<Satellite>| {
+            satellites
+                .iter()
+                .map(|it| it.get_id().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let query = Query::new().filter(Satellite::CATNR.ne(0));
+        assert_eq!(ids(repository.query(&query).await?), [iss]);
+        let query = Query::new().filter(Satellite::CATNR.eq(25544).not());
+        assert!(repository.query(&query).await?.is_empty());
+        let query = Query::new().filter(Satellite::CATNR.is_null());
+        assert_eq!(ids(repository.query(&query).await?), [without_catnr]);
+
+        // NULLs are sorted last in ascending and first in descending order
+        let query = Query::new().order_by(Satellite::CATNR.asc());
+        assert_eq!(ids(repository.query(&query).await?), [iss, without_catnr]);
+        let query = Query::new().order_by(Satellite::CATNR.desc());
+        assert_eq!(ids(repository.query(&query).await?), [without_catnr, iss]);
+
+        return Ok(());
+    })
+    .await;
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+#[test]
+fn values_are_passed_as_parameters() {
+    use crate::persistence::query::{Cursor, QueryBuilder, Value};
+
+    let query = Query::new()
+        .filter(
+            GroundStation::NAME
+                .eq("x'; DROP TABLE ground_station; --")
+                .or(GroundStation::LATITUDE.is_null().not()),
+        )
+        .order_by(GroundStation::LATITUDE.desc())
+        .after(Cursor(vec![Value::F64(10.0), Value::I32(3)]))
+        .limit(10)
+        .offset(5);
+
+    let mut builder = QueryBuilder::default();
+    assert_eq!(
+        builder.select("ground_station", &query),
+        "SELECT * FROM ground_station \
+        WHERE (name = $1 OR NOT (latitude IS NULL)) \
+        AND ((latitude < $2) OR (latitude = $2 AND id > $3)) \
+        ORDER BY latitude DESC NULLS FIRST, id ASC NULLS LAST LIMIT 10 OFFSET 5"
+    );
+    assert_eq!(builder.params().len(), 3);
+
+    let mut builder = QueryBuilder::default();
+    assert_eq!(
+        builder.count("ground_station", &query),
+        "SELECT COUNT(*) FROM ground_station WHERE (name = $1 OR NOT (latitude IS NULL))"
+    );
+}
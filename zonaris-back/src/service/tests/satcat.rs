@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use tokio::sync::RwLock;
+
+use crate::persistence::create_inmemory_repository;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_catalog::SatelliteCatalog;
+use crate::persistence::repository::Repository;
+use crate::service::job::Job;
+use crate::service::satcat::{SatcatJob, SatcatServiceDefault};
+use crate::utils::satcat::parse_json;
+use crate::utils::tle::TLE;
+
+use super::standin::spawn_standin;
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+#[test]
+fn missing_values_are_empty() {
+    let records = parse_json(
+        r#"[{"OBJECT_NAME":"FENGYUN 1C DEB","OBJECT_ID":"1999-025AAA","NORAD_CAT_ID":29733,
+        "OBJECT_TYPE":"DEB","OPS_STATUS_CODE":null,"OWNER":"PRC","LAUNCH_DATE":"1999-05-10",
+        "LAUNCH_SITE":"TSC","DECAY_DATE":"2009-12-03","RCS":null}]"#,
+    )
+    .unwrap();
+
+    let record = &records[0];
+    assert_eq!(record.get_catnr(), 29733);
+    assert_eq!(record.get_object_type(), "DEB");
+    assert_eq!(record.get_operational_status(), "");
+    assert_eq!(
+        *record.get_decay_date(),
+        NaiveDate::from_ymd_opt(2009, 12, 3)
+    );
+    assert_eq!(record.get_rcs(), None);
+
+    assert!(parse_json(r#"[{"OBJECT_NAME":"NO CATNR"}]"#).is_err());
+}
+
+#[tokio::test]
+async fn job_adds_and_keeps_single_catalog_per_satellite() {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let satellite_catalog_repository = create_inmemory_repository::<SatelliteCatalog>();
+
+    let id = satellite_repository
+        .write()
+        .await
+        .add(Satellite::from(
+            TLE::new("ISS (ZARYA)", ISS_TLE1, ISS_TLE2).unwrap(),
+        ))
+        .await
+        .unwrap();
+
+    let job = Arc::new(RwLock::new(SatcatJob::new(
+        Arc::new(SatcatServiceDefault::new(&spawn_standin().await)),
+        satellite_repository.clone(),
+        satellite_catalog_repository.clone(),
+    )));
+    SatcatJob::job_func(job.clone()).await.unwrap();
+    SatcatJob::job_func(job).await.unwrap();
+
+    let catalogs = satellite_catalog_repository
+        .read()
+        .await
+        .get_all()
+        .await
+        .unwrap();
+    assert_eq!(catalogs.len(), 1);
+
+    let catalog = &catalogs[0];
+    assert!(*catalog.get_satellite_id() == id);
+    assert_eq!(catalog.get_international_designator(), "1998-067A");
+    assert_eq!(catalog.get_owner(), "ISS");
+    assert_eq!(
+        *catalog.get_launch_date(),
+        NaiveDate::from_ymd_opt(1998, 11, 20)
+    );
+    assert_eq!(catalog.get_launch_site(), "TYMSC");
+    assert_eq!(catalog.get_object_type(), "PAY");
+    assert_eq!(catalog.get_operational_status(), "+");
+    assert_eq!(*catalog.get_decay_date(), None);
+}
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::persistence::create_inmemory_repository;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_catalog::SatelliteCatalog;
+use crate::persistence::model::satellite_element_set::SatelliteElementSet;
+use crate::persistence::model::satellite_instrument::SatelliteInstrument;
+use crate::persistence::repository::{HasId, Id, Repository};
+use crate::service::celestrak::{CelestrakService, Format, Query};
+use crate::service::element_set::{ElementSetService, ElementSetServiceDefault};
+use crate::service::satellite::{SatelliteError, SatelliteService, SatelliteServiceDefault};
+use crate::utils::element_set::ElementSet;
+use crate::utils::satcat::parse_json;
+use crate::utils::tle::{checksum, TLE};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+/// ISS element set with the catalog number, the piece of the designator and the epoch (day of 2008) replaced
+fn element_set(catnr: u32, piece: &str, day: &str) -> ElementSet {
+    let catnr = format!("{:05}", catnr);
+
+    let mut tle1 = ISS_TLE1
+        .replace("25544", &catnr)
+        .replace("98067A", &format!("98067{}", piece))
+        .replace("264.51782528", day);
+    tle1.replace_range(68..69, &checksum(&tle1).to_string());
+
+    let mut tle2 = ISS_TLE2.replace("25544", &catnr);
+    tle2.replace_range(68..69, &checksum(&tle2).to_string());
+
+    return ElementSet::TLE(TLE::new(&format!("OBJECT {}", piece), &tle1, &tle2).unwrap());
+}
+
+/// Two objects of the launch 1998-067
+struct CelestrakServiceMock {
+    catalog: Vec<ElementSet>,
+}
+
+#[async_trait]
+impl CelestrakService for CelestrakServiceMock {
+    async fn gp_query(&self, query: Query, _format: Format) -> Result<Vec<ElementSet>> {
+        return Ok(self
+            .catalog
+            .iter()
+            .filter(|it| match &query {
+                Query::CATNR(catnr) => it.get_catnr() == *catnr,
+                Query::INTDES(intdes) => it.get_international_designator().starts_with(intdes),
+                _ => false,
+            })
+            .cloned()
+            .collect());
+    }
+}
+
+fn id(value: i32) -> Id {
+    return serde_json::from_str::<Id>(&value.to_string()).unwrap();
+}
+
+#[tokio::test]
+async fn satellite_lifecycle() {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let satellite_instrument_repository = create_inmemory_repository::<SatelliteInstrument>();
+    let element_set_service = Arc::new(ElementSetServiceDefault::new(
+        satellite_repository.clone(),
+        create_inmemory_repository::<SatelliteElementSet>(),
+    ));
+    let service = SatelliteServiceDefault::new(
+        satellite_repository.clone(),
+        create_inmemory_repository::<SatelliteCatalog>(),
+        satellite_instrument_repository.clone(),
+        Arc::new(CelestrakServiceMock {
+            catalog: vec![
+                element_set(25544, "A", "264.51782528"),
+                element_set(25545, "B", "264.51782528"),
+            ],
+        }),
+        element_set_service.clone(),
+    );
+
+    // lookup by catalog number, then by designator with a piece
+    let iss = service.add(Query::CATNR(25544), None).await.unwrap();
+    let other = service
+        .add(
+            Query::INTDES(String::from("1998-067B")),
+            Some(String::from("OTHER")),
+        )
+        .await
+        .unwrap();
+
+    let satellites = service.get_all().await.unwrap();
+    assert_eq!(satellites.len(), 2);
+    assert!(satellites
+        .iter()
+        .any(|it| it.get_name() == "OBJECT A" && it.get_catnr() == Some(25544)));
+    assert!(satellites
+        .iter()
+        .any(|it| it.get_name() == "OTHER" && it.get_catnr() == Some(25545)));
+
+    let error = service.add(Query::CATNR(25544), None).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SatelliteError>(),
+        Some(SatelliteError::AlreadyExists { catnr: 25544, .. })
+    ));
+
+    let error = service
+        .add(Query::INTDES(String::from("1998-067")), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SatelliteError>(),
+        Some(SatelliteError::Ambiguous { count: 2, .. })
+    ));
+
+    let error = service.add(Query::CATNR(1), None).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SatelliteError>(),
+        Some(SatelliteError::NotFound(_))
+    ));
+
+    // the replaced element set stays in the history
+    let updated = service
+        .update(
+            iss,
+            Some(String::from("ISS")),
+            Some(element_set(25544, "A", "265.51782528")),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.get_name(), "ISS");
+    assert!(updated
+        .get_tle1()
+        .as_ref()
+        .unwrap()
+        .contains("08265.51782528"));
+    assert_eq!(
+        element_set_service
+            .get_history(iss, None, None)
+            .await
+            .unwrap()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let error = service
+        .update(iss, None, Some(element_set(25545, "A", "265.51782528")))
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error.downcast_ref::<SatelliteError>(),
+        Some(SatelliteError::AlreadyExists { catnr: 25545, .. })
+    ));
+
+    assert!(service.update(id(-1), None, None).await.unwrap().is_none());
+
+    // fetching an existing catalog number replaces the elements only
+    assert!(service.fetch(25544).await.unwrap() == (iss, false));
+    let satellites = service.get_all().await.unwrap();
+    let fetched = satellites
+        .iter()
+        .find(|it| it.get_id() == Some(iss))
+        .unwrap();
+    assert_eq!(fetched.get_name(), "ISS");
+    assert!(fetched
+        .get_tle1()
+        .as_ref()
+        .unwrap()
+        .contains("08264.51782528"));
+
+    // linked instruments prevent the removal
+    let link = satellite_instrument_repository
+        .write()
+        .await
+        .add(SatelliteInstrument::new(iss, id(1)))
+        .await
+        .unwrap();
+    let error = service.delete(iss).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SatelliteError>(),
+        Some(SatelliteError::HasInstruments(1))
+    ));
+
+    satellite_instrument_repository
+        .write()
+        .await
+        .delete(link)
+        .await
+        .unwrap();
+    assert!(service.delete(iss).await.unwrap());
+    assert!(!service.delete(iss).await.unwrap());
+    assert!(element_set_service
+        .get_history(iss, None, None)
+        .await
+        .unwrap()
+        .is_none());
+
+    let satellites = service.get_all().await.unwrap();
+    assert_eq!(satellites.len(), 1);
+    assert_eq!(satellites[0].get_id(), Some(other));
+}
+
+#[tokio::test]
+async fn catalog_is_looked_up_by_satellite() {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let satellite_catalog_repository = create_inmemory_repository::<SatelliteCatalog>();
+    let service = SatelliteServiceDefault::new(
+        satellite_repository.clone(),
+        satellite_catalog_repository.clone(),
+        create_inmemory_repository::<SatelliteInstrument>(),
+        Arc::new(CelestrakServiceMock {
+            catalog: vec![
+                element_set(25544, "A", "264.51782528"),
+                element_set(25545, "B", "264.51782528"),
+            ],
+        }),
+        Arc::new(ElementSetServiceDefault::new(
+            satellite_repository.clone(),
+            create_inmemory_repository::<SatelliteElementSet>(),
+        )),
+    );
+
+    let iss = service.add(Query::CATNR(25544), None).await.unwrap();
+    let other = service.add(Query::CATNR(25545), None).await.unwrap();
+
+    let records = parse_json(
+        r#"[{"OBJECT_NAME":"ISS (ZARYA)","OBJECT_ID":"1998-067A","NORAD_CAT_ID":25544,
+        "OBJECT_TYPE":"PAY","OPS_STATUS_CODE":"+","OWNER":"ISS","LAUNCH_DATE":"1998-11-20",
+        "LAUNCH_SITE":"TYMSC","DECAY_DATE":null,"RCS":null}]"#,
+    )
+    .unwrap();
+    satellite_catalog_repository
+        .write()
+        .await
+        .add(SatelliteCatalog::new(iss, &records[0]))
+        .await
+        .unwrap();
+
+    let catalog = service.get_catalog(iss).await.unwrap().unwrap();
+    assert_eq!(catalog.get_international_designator(), "1998-067A");
+    assert!(service.get_catalog(other).await.unwrap().is_none());
+}
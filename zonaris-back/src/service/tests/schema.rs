@@ -0,0 +1,127 @@
+use anyhow::Result;
+
+use crate::persistence::model::satellite_element_set::SatelliteElementSet;
+use crate::persistence::schema::{Dialect, SchemaDrift, Table};
+
+use super::backend::{each_backend, Backend};
+
+mod station {
+    use table_macro::Table;
+
+    use crate::persistence::model::satellite::Satellite;
+    use crate::persistence::repository::{Id, Reference};
+
+    #[derive(Clone, Table)]
+    #[table(name = "station", unique(station_name, callsign))]
+    pub struct Station {
+        #[id]
+        id: Option<Id>,
+        #[column(name = "station_name", index)]
+        name: String,
+        #[column(sql_type = "TEXT")]
+        callsign: Option<String>,
+        #[references(Satellite)]
+        satellite_id: Reference<Satellite>,
+    }
+}
+
+/// The differences of the tables of the backend from the models, None for the in-memory one
+async fn check(backend: &Backend) -> Result<Option<Vec<SchemaDrift>>> {
+    return Ok(match backend {
+        Backend::InMemory => None,
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite { database, .. } => {
+            Some(crate::persistence::sqlite::schema::check(database.clone()).await?)
+        }
+        #[cfg(feature = "postgres")]
+        Backend::Postgres { pool, .. } => {
+            Some(crate::persistence::postgres::schema::check(pool.clone()).await?)
+        }
+    });
+}
+
+async fn execute(backend: &Backend, statements: &str) -> Result<()> {
+    match backend {
+        Backend::InMemory => {}
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite { database, .. } => database.get().await.execute_batch(statements)?,
+        #[cfg(feature = "postgres")]
+        Backend::Postgres { pool, .. } => pool.get().await?.batch_execute(statements).await?,
+    }
+
+    return Ok(());
+}
+
+#[test]
+fn ddl_is_generated_from_the_attributes() {
+    use station::Station;
+
+    assert_eq!(Station::TABLE, "station");
+    assert_eq!(
+        Station::schema().create_table(Dialect::Postgres),
+        "CREATE TABLE station\n\
+        (\n    \
+            id SERIAL PRIMARY KEY,\n    \
+            station_name VARCHAR NOT NULL,\n    \
+            callsign TEXT NULL DEFAULT NULL,\n    \
+            satellite_id INTEGER NOT NULL REFERENCES satellite (id),\n    \
+            UNIQUE (station_name, callsign)\n\
+        );\n\
+        CREATE INDEX station_station_name_idx ON station (station_name);\n"
+    );
+    assert_eq!(
+        SatelliteElementSet::schema().create_table(Dialect::Sqlite),
+        "CREATE TABLE satellite_element_set\n\
+        (\n    \
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\n    \
+            satellite_id INTEGER NOT NULL REFERENCES satellite (id),\n    \
+            epoch TEXT NOT NULL,\n    \
+            tle1 TEXT NULL DEFAULT NULL,\n    \
+            tle2 TEXT NULL DEFAULT NULL,\n    \
+            omm TEXT NULL DEFAULT NULL,\n    \
+            UNIQUE (satellite_id, epoch)\n\
+        );\n"
+    );
+}
+
+#[tokio::test]
+async fn migrations_match_the_models() -> Result<()> {
+    return each_backend(|backend| async move {
+        if let Some(drifts) = check(&backend).await? {
+            assert_eq!(drifts, vec![]);
+        }
+
+        return Ok(());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn drift_is_reported() -> Result<()> {
+    return each_backend(|backend| async move {
+        execute(
+            &backend,
+            "ALTER TABLE instrument ADD COLUMN vendor TEXT NULL;
+            ALTER TABLE ground_station DROP COLUMN min_elevation;
+            DROP TABLE satellite_catalog;",
+        )
+        .await?;
+
+        if let Some(drifts) = check(&backend).await? {
+            assert_eq!(
+                drifts,
+                vec![
+                    SchemaDrift::ExtraColumn(String::from("instrument"), String::from("vendor")),
+                    SchemaDrift::MissingColumn(
+                        String::from("ground_station"),
+                        String::from("min_elevation")
+                    ),
+                    SchemaDrift::MissingTable(String::from("satellite_catalog")),
+                ]
+            );
+        }
+
+        return Ok(());
+    })
+    .await;
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::persistence::create_inmemory_repository;
+use crate::persistence::model::instrument::Instrument;
+use crate::persistence::model::instrument_data::InstrumentData;
+use crate::persistence::model::oceancolor::OceanColorMapping;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::model::satellite_catalog::SatelliteCatalog;
+use crate::persistence::model::satellite_element_set::SatelliteElementSet;
+use crate::persistence::model::satellite_instrument::SatelliteInstrument;
+use crate::persistence::repository::Repository;
+use crate::persistence::unit_of_work::UnitOfWork;
+use crate::service::celestrak::{CelestrakService, Format, Query};
+use crate::service::element_set::ElementSetServiceDefault;
+use crate::service::instrument::{InstrumentService, InstrumentServiceDefault};
+use crate::service::satellite::{SatelliteService, SatelliteServiceDefault};
+use crate::service::seed::{Seed, SeedReport, Seeder};
+use crate::standin::celestrak::load_catalog;
+use crate::utils::element_set::ElementSet;
+
+const SEED_TOML: &str = r#"
+[[satellites]]
+catnr = 25994
+
+[[satellites]]
+catnr = 41335
+name = "S3A"
+
+[[instruments]]
+name = "MODIS"
+
+[[instruments]]
+name = "OLCI"
+
+[[links]]
+catnr = 25994
+instrument = "MODIS"
+
+[[links]]
+catnr = 41335
+instrument = "OLCI"
+
+[[oceancolor]]
+catnr = 25994
+instrument = "MODIS"
+sensor_id = 8
+data_id = 1102
+"#;
+
+const SEED_YAML: &str = r#"
+satellites:
+  - catnr: 25994
+  - catnr: 41335
+    name: S3A
+instruments:
+  - name: MODIS
+  - name: OLCI
+links:
+  - { catnr: 25994, instrument: MODIS }
+  - { catnr: 41335, instrument: OLCI }
+oceancolor:
+  - { catnr: 25994, instrument: MODIS, sensor_id: 8, data_id: 1102 }
+"#;
+
+/// Canned catalog of the stand-in
+struct CelestrakServiceMock;
+
+#[async_trait]
+impl CelestrakService for CelestrakServiceMock {
+    async fn gp_query(&self, query: Query, _format: Format) -> Result<Vec<ElementSet>> {
+        // the seed doesn't hold its transaction while the elements are fetched
+        assert!(UnitOfWork::current().is_none());
+
+        return Ok(load_catalog()?
+            .into_iter()
+            .filter(|it| matches!(query, Query::CATNR(catnr) if it.get_catnr() == catnr))
+            .map(ElementSet::TLE)
+            .collect());
+    }
+}
+
+#[tokio::test]
+async fn seed_is_idempotent() {
+    let satellite_repository = create_inmemory_repository::<Satellite>();
+    let satellite_service = Arc::new(SatelliteServiceDefault::new(
+        satellite_repository.clone(),
+        create_inmemory_repository::<SatelliteCatalog>(),
+        create_inmemory_repository::<SatelliteInstrument>(),
+        Arc::new(CelestrakServiceMock),
+        Arc::new(ElementSetServiceDefault::new(
+            satellite_repository.clone(),
+            create_inmemory_repository::<SatelliteElementSet>(),
+        )),
+    ));
+    let satellite_instrument_repository = create_inmemory_repository::<SatelliteInstrument>();
+    let oceancolor_mapping_repository = create_inmemory_repository::<OceanColorMapping>();
+    let instrument_service = Arc::new(InstrumentServiceDefault::new(
+        create_inmemory_repository::<Instrument>(),
+        satellite_repository.clone(),
+        satellite_instrument_repository.clone(),
+        create_inmemory_repository::<InstrumentData>(),
+        oceancolor_mapping_repository.clone(),
+    ));
+    let seeder = Seeder::new(
+        satellite_service.clone(),
+        instrument_service.clone(),
+        oceancolor_mapping_repository.clone(),
+    );
+
+    let seed = Seed::from_toml(SEED_TOML).unwrap();
+    assert_eq!(
+        seeder.apply(&seed).await.unwrap(),
+        SeedReport {
+            created: 7,
+            updated: 0
+        }
+    );
+    assert_eq!(seeder.apply(&seed).await.unwrap(), SeedReport::default());
+
+    // the same seed in YAML changes nothing
+    assert_eq!(
+        seeder
+            .apply(&Seed::from_yaml(SEED_YAML).unwrap())
+            .await
+            .unwrap(),
+        SeedReport::default()
+    );
+
+    let satellites = satellite_service.get_all().await.unwrap();
+    assert_eq!(satellites.len(), 2);
+    assert!(satellites.iter().any(|it| it.get_name() == "S3A"));
+    assert_eq!(
+        satellite_instrument_repository
+            .read()
+            .await
+            .get_all()
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // changed values of existing records are updated in place
+    let changed = SEED_TOML
+        .replace("name = \"S3A\"", "name = \"SENTINEL 3A\"")
+        .replace("data_id = 1102", "data_id = 1103");
+    assert_eq!(
+        seeder
+            .apply(&Seed::from_toml(&changed).unwrap())
+            .await
+            .unwrap(),
+        SeedReport {
+            created: 0,
+            updated: 2
+        }
+    );
+
+    let mappings = oceancolor_mapping_repository
+        .read()
+        .await
+        .get_all()
+        .await
+        .unwrap();
+    assert_eq!(mappings.len(), 1);
+    assert_eq!(*mappings[0].get_data_id(), 1103);
+
+    // nothing is written if a seed fails halfway, here on the mapping of an unlinked instrument
+    let failing = format!(
+        "{}\n[[instruments]]\nname = \"VIIRS\"\n\n[[oceancolor]]\ncatnr = 41335\ninstrument = \"VIIRS\"\nsensor_id = 1\ndata_id = 1",
+        SEED_TOML
+    );
+    assert!(seeder
+        .apply(&Seed::from_toml(&failing).unwrap())
+        .await
+        .is_err());
+    assert_eq!(instrument_service.get_all().await.unwrap().len(), 2);
+    let satellites = satellite_service.get_all().await.unwrap();
+    assert!(satellites.iter().any(|it| it.get_name() == "SENTINEL 3A"));
+    let mappings = oceancolor_mapping_repository
+        .read()
+        .await
+        .get_all()
+        .await
+        .unwrap();
+    assert_eq!(*mappings[0].get_data_id(), 1103);
+
+    assert!(Seed::from_toml("[[satellites]]\ncatnr = 1\nunknown = 2").is_err());
+}
@@ -0,0 +1,88 @@
+use chrono::Duration;
+
+use crate::service::celestrak::{CelestrakService, Format, Query};
+use crate::service::spacetrack::CelestrakServiceSpaceTrack;
+use crate::standin::spacetrack::{IDENTITY, PASSWORD};
+
+use super::standin::spawn_standin;
+
+#[tokio::test]
+async fn gp_is_queried_after_login() {
+    let service =
+        CelestrakServiceSpaceTrack::new(&spawn_standin().await, IDENTITY, PASSWORD).unwrap();
+
+    for format in [Format::TLE, Format::JSON, Format::CSV] {
+        let element_sets = service
+            .gp_query(Query::CATNRS(vec![27424, 25994]), format)
+            .await
+            .unwrap();
+        assert_eq!(
+            element_sets
+                .iter()
+                .map(|it| it.get_catnr())
+                .collect::<Vec<_>>(),
+            vec![25994, 27424]
+        );
+    }
+
+    let iss = service
+        .gp_query(Query::NAME(String::from("zarya")), Format::TLE)
+        .await
+        .unwrap();
+    assert_eq!(iss.len(), 1);
+    assert_eq!(iss[0].get_name(), "ISS (ZARYA)");
+
+    let intdes = service
+        .gp_query(Query::INTDES(String::from("2016-011")), Format::JSON)
+        .await
+        .unwrap();
+    assert_eq!(intdes[0].get_catnr(), 41335);
+
+    assert!(service
+        .gp_query(Query::GROUP(String::from("stations")), Format::TLE)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn gp_history_is_filtered_by_epoch() {
+    let service =
+        CelestrakServiceSpaceTrack::new(&spawn_standin().await, IDENTITY, PASSWORD).unwrap();
+
+    let current = service
+        .gp_query(Query::CATNR(25544), Format::JSON)
+        .await
+        .unwrap()
+        .remove(0);
+    let epoch = *current.get_epoch();
+
+    let history = service
+        .gp_history(25544, epoch - Duration::days(30), epoch)
+        .await
+        .unwrap();
+    assert_eq!(
+        history.iter().map(|it| *it.get_epoch()).collect::<Vec<_>>(),
+        vec![epoch - Duration::days(2), epoch - Duration::days(1), epoch]
+    );
+
+    let latest = service
+        .gp_history(
+            25544,
+            epoch - Duration::hours(12),
+            epoch + Duration::hours(12),
+        )
+        .await
+        .unwrap();
+    assert_eq!(latest.len(), 1);
+}
+
+#[tokio::test]
+async fn wrong_credentials_are_rejected() {
+    let service =
+        CelestrakServiceSpaceTrack::new(&spawn_standin().await, IDENTITY, "wrong").unwrap();
+
+    assert!(service
+        .gp_query(Query::CATNR(25544), Format::TLE)
+        .await
+        .is_err());
+}
@@ -0,0 +1,86 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use tokio::net::TcpListener;
+
+use crate::persistence::model::oceancolor::OceanColorMapping;
+use crate::persistence::repository::Id;
+use crate::service::celestrak::{CelestrakService, CelestrakServiceDefault, Format, Query};
+use crate::service::oceancolor::{OceanColorService, OceanColorServiceDefault};
+use crate::standin;
+
+/// Base URL of a stand-in listening on a random port
+pub async fn spawn_standin() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move { standin::serve(listener).await.unwrap() });
+
+    return url;
+}
+
+fn time(hour: u32, min: u32) -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(hour, min, 0)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn celestrak_queries_are_served_from_canned_catalog() {
+    let service = CelestrakServiceDefault::new(&spawn_standin().await);
+
+    for format in [Format::TLE, Format::JSON] {
+        let element_sets = service
+            .gp_query(Query::CATNRS(vec![27424, 25994]), format)
+            .await
+            .unwrap();
+        let mut catnrs = element_sets
+            .iter()
+            .map(|it| it.get_catnr())
+            .collect::<Vec<_>>();
+        catnrs.sort();
+        assert_eq!(catnrs, vec![25994, 27424]);
+    }
+
+    let group = service
+        .gp_query(Query::GROUP(String::from("stations")), Format::TLE)
+        .await
+        .unwrap();
+    assert_eq!(group.len(), 4);
+
+    let iss = service
+        .gp_query(Query::NAME(String::from("zarya")), Format::TLE)
+        .await
+        .unwrap();
+    assert_eq!(iss.len(), 1);
+    assert_eq!(iss[0].get_catnr(), 25544);
+
+    let unknown = service
+        .gp_query(Query::CATNR(1), Format::TLE)
+        .await
+        .unwrap();
+    assert!(unknown.is_empty());
+}
+
+#[tokio::test]
+async fn oceancolor_search_returns_hourly_granules() {
+    let service = OceanColorServiceDefault::new("", &spawn_standin().await);
+
+    // only the mapping's sensor and data ids are sent
+    let mapping = OceanColorMapping::new(serde_json::from_str::<Id>("0").unwrap(), 8, 1102);
+
+    let items = service
+        .search(time(9, 30), time(12, 10), &mapping)
+        .await
+        .unwrap();
+    let times = items
+        .iter()
+        .map(|it| it.get_time().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(times, vec![time(10, 0), time(11, 0), time(12, 0)]);
+
+    let none = service
+        .search(time(9, 10), time(9, 50), &mapping)
+        .await
+        .unwrap();
+    assert!(none.is_empty());
+}
@@ -0,0 +1,171 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::persistence::model::satellite::Satellite;
+use crate::utils::tle::{checksum, parse_3le, TleError, TLE};
+
+const ISS_TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const ISS_TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+/// Replaces the character at `index` and recomputes the checksum.
+fn corrupt(line: &str, index: usize, value: char) -> String {
+    let mut line = line.chars().collect::<Vec<_>>();
+    line[index] = value;
+
+    let mut line = line.into_iter().collect::<String>();
+    let checksum = checksum(&line);
+    line.replace_range(68..69, &checksum.to_string());
+    return line;
+}
+
+#[test]
+fn all_fields_are_parsed() {
+    let tle = TLE::new("ISS (ZARYA)  ", ISS_TLE1, ISS_TLE2).unwrap();
+
+    assert_eq!(tle.get_name(), "ISS (ZARYA)");
+    assert_eq!(tle.get_catnr(), 25544);
+    assert_eq!(tle.get_classification(), 'U');
+    assert_eq!(tle.get_international_designator(), "98067A");
+    assert_eq!(tle.get_element_set_number(), 292);
+    assert_eq!(tle.get_revolution_number(), 56353);
+
+    let epoch =
+        Utc.with_ymd_and_hms(2008, 9, 20, 12, 25, 40).unwrap() + Duration::microseconds(104_192);
+    assert!((*tle.get_epoch() - epoch).num_milliseconds().abs() < 1);
+
+    assert_eq!(tle.get_mean_motion_dot(), -0.00002182);
+    assert_eq!(tle.get_mean_motion_ddot(), 0.0);
+    assert!((tle.get_bstar() + 0.11606e-4).abs() < 1e-15);
+
+    assert_eq!(tle.get_inclination(), 51.6416);
+    assert_eq!(tle.get_right_ascension(), 247.4627);
+    assert_eq!(tle.get_eccentricity(), 0.0006703);
+    assert_eq!(tle.get_argument_of_perigee(), 130.5360);
+    assert_eq!(tle.get_mean_anomaly(), 325.0288);
+    assert_eq!(tle.get_mean_motion(), 15.72125391);
+}
+
+#[test]
+fn trailing_whitespace_is_ignored() {
+    let tle1 = format!("{}  \r", ISS_TLE1);
+    let tle = TLE::new("ISS", &tle1, ISS_TLE2).unwrap();
+
+    assert_eq!(tle.get_tle1(), ISS_TLE1);
+}
+
+#[test]
+fn truncated_line_is_rejected() {
+    assert_eq!(
+        TLE::new("ISS", &ISS_TLE1[..40], ISS_TLE2).unwrap_err(),
+        TleError::LineLength {
+            line: 1,
+            length: 40
+        }
+    );
+    assert_eq!(
+        TLE::new("ISS", ISS_TLE1, "").unwrap_err(),
+        TleError::LineLength { line: 2, length: 0 }
+    );
+}
+
+#[test]
+fn swapped_lines_are_rejected() {
+    assert_eq!(
+        TLE::new("ISS", ISS_TLE2, ISS_TLE1).unwrap_err(),
+        TleError::LineNumber { line: 1 }
+    );
+}
+
+#[test]
+fn checksum_mismatch_is_rejected() {
+    let tle2 = ISS_TLE2.replace("51.6416", "51.6417");
+
+    assert_eq!(
+        TLE::new("ISS", ISS_TLE1, &tle2).unwrap_err(),
+        TleError::Checksum {
+            line: 2,
+            expected: 8,
+            actual: 7
+        }
+    );
+}
+
+#[test]
+fn catalog_number_mismatch_is_rejected() {
+    let tle2 = corrupt(ISS_TLE2, 6, '5');
+
+    assert_eq!(
+        TLE::new("ISS", ISS_TLE1, &tle2).unwrap_err(),
+        TleError::CatalogNumberMismatch {
+            first: 25544,
+            second: 25545
+        }
+    );
+}
+
+#[test]
+fn garbage_in_fields_is_rejected() {
+    let cases = [
+        (
+            corrupt(ISS_TLE1, 3, 'X'),
+            ISS_TLE2.to_string(),
+            "catalog number",
+        ),
+        (
+            corrupt(ISS_TLE1, 7, 'X'),
+            ISS_TLE2.to_string(),
+            "classification",
+        ),
+        (
+            corrupt(ISS_TLE1, 23, 'X'),
+            ISS_TLE2.to_string(),
+            "epoch day",
+        ),
+        (corrupt(ISS_TLE1, 55, 'X'), ISS_TLE2.to_string(), "bstar"),
+        (
+            ISS_TLE1.to_string(),
+            corrupt(ISS_TLE2, 28, 'X'),
+            "eccentricity",
+        ),
+        (
+            ISS_TLE1.to_string(),
+            corrupt(ISS_TLE2, 54, 'X'),
+            "mean motion",
+        ),
+    ];
+
+    for (tle1, tle2, expected) in cases {
+        match TLE::new("ISS", &tle1, &tle2).unwrap_err() {
+            TleError::InvalidField { field, .. } => assert_eq!(field, expected),
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+}
+
+#[test]
+fn satellite_is_created_only_from_valid_element_set() {
+    let satellite = Satellite::new("ISS", ISS_TLE1, ISS_TLE2).unwrap();
+    assert_eq!(satellite.get_catnr(), Some(25544));
+
+    assert!(Satellite::new("ISS", &ISS_TLE1[..7], ISS_TLE2).is_err());
+}
+
+#[test]
+fn named_and_unnamed_element_sets_are_parsed() {
+    let text = format!(
+        "0 ISS (ZARYA)\r\n{}\r\n{}\r\n\n{}\n{}\n",
+        ISS_TLE1, ISS_TLE2, ISS_TLE1, ISS_TLE2
+    );
+
+    let tles = parse_3le(&text).unwrap();
+    assert_eq!(
+        tles.iter()
+            .map(|it| it.get_name().as_str())
+            .collect::<Vec<_>>(),
+        vec!["ISS (ZARYA)", ""]
+    );
+
+    assert_eq!(
+        parse_3le(&format!("ISS\n{}", ISS_TLE1)).unwrap_err(),
+        TleError::LineLength { line: 2, length: 0 }
+    );
+}
@@ -0,0 +1,102 @@
+use chrono::Duration;
+
+use crate::dto::satellite::TrackingResponse;
+use crate::service::ground_station::predict_passes;
+use crate::service::propagation::{doppler_shift, look_angles_at, sample_tracking};
+use crate::utils::geodesy::Geodetic;
+use crate::utils::sgp4::from_tle;
+
+const VANGUARD_TLE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+const VANGUARD_TLE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+const FREQUENCY: f64 = 437.5e6;
+
+fn observer() -> Geodetic {
+    return Geodetic {
+        latitude: 30.0,
+        longitude: 30.0,
+        altitude: 0.2,
+    };
+}
+
+#[test]
+fn doppler_shift_sign_follows_range_rate() {
+    assert!(doppler_shift(FREQUENCY, -5.0) > 0.0);
+    assert!(doppler_shift(FREQUENCY, 5.0) < 0.0);
+    assert_eq!(doppler_shift(FREQUENCY, 0.0), 0.0);
+
+    // 1 km/s is about 3.3 ppm of the carrier
+    assert!((doppler_shift(FREQUENCY, 1.0) + 1459.35).abs() < 0.01);
+}
+
+#[test]
+fn range_rate_is_derivative_of_range() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let time = model.epoch() + Duration::hours(3);
+    let delta = Duration::milliseconds(500);
+
+    let before = look_angles_at(&model, &observer(), time - delta).unwrap();
+    let current = look_angles_at(&model, &observer(), time).unwrap();
+    let after = look_angles_at(&model, &observer(), time + delta).unwrap();
+
+    // central difference over one second
+    let derivative = after.range - before.range;
+    assert!((current.range_rate - derivative).abs() < 1e-3);
+}
+
+#[test]
+fn doppler_shift_changes_sign_at_culmination() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let from = model.epoch();
+    let pass = predict_passes(&model, &observer(), 0.0, from, from + Duration::days(1)).unwrap()[0];
+
+    let points = sample_tracking(
+        &model,
+        &observer(),
+        FREQUENCY,
+        pass.aos.time,
+        pass.los.time,
+        Duration::seconds(10),
+    )
+    .unwrap();
+
+    assert_eq!(points.first().unwrap().time, pass.aos.time);
+    assert_eq!(points.last().unwrap().time, pass.los.time);
+    assert!(points.first().unwrap().doppler_shift > 0.0);
+    assert!(points.last().unwrap().doppler_shift < 0.0);
+
+    for point in &points {
+        assert!(point.look_angles.elevation > -0.1);
+    }
+}
+
+#[test]
+fn tracking_csv_has_row_per_sample() {
+    let model = from_tle(VANGUARD_TLE1, VANGUARD_TLE2).unwrap();
+    let from = model.epoch();
+
+    let points = sample_tracking(
+        &model,
+        &observer(),
+        FREQUENCY,
+        from,
+        from + Duration::minutes(1),
+        Duration::seconds(20),
+    )
+    .unwrap();
+    let rows = points
+        .iter()
+        .map(|it| TrackingResponse::new(it, FREQUENCY))
+        .collect::<Vec<_>>();
+
+    let csv = TrackingResponse::to_csv(&rows);
+    let lines = csv.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        lines[0],
+        "time,azimuth,elevation,range,range_rate,doppler_shift,frequency"
+    );
+    assert_eq!(lines.len(), 5);
+    assert!(lines[1].starts_with("2000-06-27T18:50:19.733Z,"));
+    assert!(lines.iter().all(|line| line.split(',').count() == 7));
+}
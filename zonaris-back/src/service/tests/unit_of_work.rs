@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result};
+
+use crate::persistence::model::instrument::Instrument;
+use crate::persistence::model::satellite::Satellite;
+use crate::persistence::repository::HasId;
+use crate::persistence::unit_of_work::{transaction, UnitOfWork};
+
+use super::backend::each_backend;
+
+const TLE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+const TLE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+fn names(instruments: Vec<Instrument>) -> Vec<String> {
+    let mut names = instruments
+        .iter()
+        .map(|it| it.get_name().clone())
+        .collect::<Vec<_>>();
+    names.sort();
+    return names;
+}
+
+#[tokio::test]
+async fn failed_work_is_rolled_back_in_every_repository() -> Result<()> {
+    return each_backend(|backend| async move {
+        let instrument_repository = backend.repository::<Instrument>();
+        let satellite_repository = backend.repository::<Satellite>();
+
+        let modis = instrument_repository
+            .write()
+            .await
+            .add(Instrument::new("MODIS"))
+            .await?;
+        let olci = instrument_repository
+            .write()
+            .await
+            .add(Instrument::new("OCLI"))
+            .await?;
+
+        let result: Result<()> = transaction(async {
+            let mut instruments = instrument_repository.write().await;
+            instruments.delete(modis).await?;
+            let mut renamed = instruments.get(olci).await?.unwrap();
+            renamed.set_name(String::from("OLCI"));
+            instruments.update(renamed.clone()).await?;
+            renamed.set_name(String::from("OLCI 2"));
+            instruments.update(renamed).await?;
+            let viirs = instruments.add(Instrument::new("VIIRS")).await?;
+            instruments.delete(viirs).await?;
+            instruments.add(Instrument::new("VIIRS")).await?;
+
+            satellite_repository
+                .write()
+                .await
+                .add(Satellite::new("ISS", TLE1, TLE2)?)
+                .await?;
+
+            Err(anyhow!("failed after the writes"))
+        })
+        .await;
+        assert!(result.is_err());
+
+        assert_eq!(
+            names(instrument_repository.read().await.get_all().await?),
+            ["MODIS", "OCLI"]
+        );
+        assert!(satellite_repository
+            .read()
+            .await
+            .get_all()
+            .await?
+            .is_empty());
+        // nothing is left to roll back by later work
+        assert!(UnitOfWork::current().is_none());
+
+        return Ok(());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn nested_work_joins_the_outer_unit() -> Result<()> {
+    return each_backend(|backend| async move {
+        let repository = backend.repository::<Instrument>();
+
+        let result: Result<()> = transaction(async {
+            repository
+                .write()
+                .await
+                .add(Instrument::new("MODIS"))
+                .await?;
+
+            // succeeds on its own, but the outer work fails
+            transaction(async {
+                repository
+                    .write()
+                    .await
+                    .add(Instrument::new("OLCI"))
+                    .await?;
+                Ok(())
+            })
+            .await?;
+
+            Err(anyhow!("outer work failed"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert!(repository.read().await.get_all().await?.is_empty());
+
+        let id = transaction(async {
+            Ok(repository
+                .write()
+                .await
+                .add(Instrument::new("MODIS"))
+                .await?)
+        })
+        .await?;
+        assert_eq!(
+            repository.read().await.get(id).await?.unwrap().get_id(),
+            Some(id)
+        );
+
+        return Ok(());
+    })
+    .await;
+}
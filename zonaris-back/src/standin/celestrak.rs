@@ -0,0 +1,145 @@
+// Celestrak GP query API (https://celestrak.org/NORAD/documentation/gp-data-formats.php)
+// and SATCAT records answering from canned catalogs. Every GROUP and SPECIAL data set is the whole catalog.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use itertools::Itertools;
+
+use crate::utils::{
+    omm::OMM,
+    tle::{self, TLE},
+};
+
+const CATALOG: &str = include_str!("celestrak.txt");
+
+const SATCAT: &str = include_str!("satcat.json");
+
+const GP_PATH: &str = "/NORAD/elements/gp.php";
+const SATCAT_PATH: &str = "/satcat/records.php";
+
+/// Canned element sets, also served by the other stand-ins
+pub fn load_catalog() -> Result<Vec<TLE>> {
+    return Ok(tle::parse_3le(CATALOG)?);
+}
+
+pub fn create_router() -> Result<Router> {
+    let satcat = serde_json::from_str::<Vec<serde_json::Value>>(SATCAT)?;
+
+    let satcat_router = Router::new()
+        .route(SATCAT_PATH, get(satcat_query))
+        .with_state(Arc::new(satcat));
+
+    return Ok(Router::new()
+        .route(GP_PATH, get(gp_query))
+        .with_state(Arc::new(load_catalog()?))
+        .merge(satcat_router));
+}
+
+/// International designator of a TLE (yyNNNppp) for a query one (yyyy-nnn)
+fn short_designator(intdes: &str) -> Option<String> {
+    return Some(format!("{}{}", intdes.get(2..4)?, intdes.get(5..)?));
+}
+
+async fn gp_query(
+    State(catalog): State<Arc<Vec<TLE>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let selected = if let Some(catnrs) = params.get("CATNR") {
+        let catnrs = catnrs
+            .split(',')
+            .filter_map(|it| it.trim().parse::<u32>().ok())
+            .collect::<Vec<_>>();
+        catalog
+            .iter()
+            .filter(|it| catnrs.contains(&it.get_catnr()))
+            .collect::<Vec<_>>()
+    } else if let Some(name) = params.get("NAME") {
+        let name = name.to_uppercase();
+        catalog
+            .iter()
+            .filter(|it| it.get_name().to_uppercase().contains(&name))
+            .collect()
+    } else if let Some(intdes) = params.get("INTDES") {
+        let prefix = short_designator(intdes).unwrap_or_default();
+        catalog
+            .iter()
+            .filter(|it| it.get_international_designator().starts_with(&prefix))
+            .collect()
+    } else if params.contains_key("GROUP") || params.contains_key("SPECIAL") {
+        catalog.iter().collect()
+    } else {
+        return String::from("Invalid query: \"\"").into_response();
+    };
+
+    if selected.is_empty() {
+        return String::from("No GP data found").into_response();
+    }
+
+    return match params.get("FORMAT").map(String::as_str).unwrap_or("TLE") {
+        "TLE" => selected
+            .iter()
+            .map(|it| format!("{}\n{}\n{}", it.get_name(), it.get_tle1(), it.get_tle2()))
+            .join("\n")
+            .into_response(),
+        "JSON" => (
+            [(header::CONTENT_TYPE, "application/json")],
+            serde_json::to_string(&selected.into_iter().map(OMM::from).collect::<Vec<_>>())
+                .unwrap_or_default(),
+        )
+            .into_response(),
+        format => format!("Invalid query: \"FORMAT={}\"", format).into_response(),
+    };
+}
+
+async fn satcat_query(
+    State(satcat): State<Arc<Vec<serde_json::Value>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let field = |record: &serde_json::Value, name: &str| -> String {
+        return match &record[name] {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+    };
+
+    let selected = if let Some(catnrs) = params.get("CATNR") {
+        let catnrs = catnrs.split(',').map(str::trim).collect::<Vec<_>>();
+        satcat
+            .iter()
+            .filter(|it| catnrs.contains(&field(it, "NORAD_CAT_ID").as_str()))
+            .collect::<Vec<_>>()
+    } else if let Some(name) = params.get("NAME") {
+        let name = name.to_uppercase();
+        satcat
+            .iter()
+            .filter(|it| field(it, "OBJECT_NAME").contains(&name))
+            .collect()
+    } else if let Some(intdes) = params.get("INTDES") {
+        satcat
+            .iter()
+            .filter(|it| field(it, "OBJECT_ID").starts_with(intdes.as_str()))
+            .collect()
+    } else if params.contains_key("GROUP") || params.contains_key("SPECIAL") {
+        satcat.iter().collect()
+    } else {
+        return String::from("Invalid query: \"\"").into_response();
+    };
+
+    if selected.is_empty() {
+        return String::from("No SATCAT records found").into_response();
+    }
+
+    return (
+        [(header::CONTENT_TYPE, "application/json")],
+        serde_json::to_string(&selected).unwrap_or_default(),
+    )
+        .into_response();
+}
@@ -0,0 +1,23 @@
+// Local stand-ins for the upstream APIs the jobs depend on, so both jobs can run offline.
+// Serve them on STANDIN_IP and point CELESTRAK_URL, OCEANCOLOR_URL and SPACETRACK_URL at that address.
+
+pub mod celestrak;
+pub mod oceancolor;
+pub mod spacetrack;
+
+use anyhow::Result;
+use axum::Router;
+use tokio::net::TcpListener;
+
+pub fn create_router() -> Result<Router> {
+    return Ok(Router::new()
+        .merge(celestrak::create_router()?)
+        .merge(oceancolor::create_router())
+        .merge(spacetrack::create_router()?));
+}
+
+pub async fn serve(listener: TcpListener) -> Result<()> {
+    axum::serve(listener, create_router()?).await?;
+
+    return Ok(());
+}
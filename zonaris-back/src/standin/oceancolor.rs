@@ -0,0 +1,131 @@
+// OB.DAAC file_search and getfile endpoints (https://oceancolor.gsfc.nasa.gov/data/download_methods/)
+// answering with hourly granules of a synthetic SST4 field.
+
+use std::path::Path;
+
+use anyhow::Result;
+use axum::{
+    extract::Path as UrlPath,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use chrono::{Duration, DurationRound, NaiveDateTime};
+use log::error;
+use serde::Deserialize;
+
+const FILE_SEARCH_PATH: &str = "/api/file_search";
+const GETFILE_PATH: &str = "/cgi/getfile/:name";
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Only the latest granules of a search are returned
+const MAX_GRANULES: usize = 4;
+
+/// Width and height of a granule
+const GRANULE_SIZE: usize = 64;
+const FILL_VALUE: i16 = -32767;
+const SCALE_FACTOR: f32 = 0.005;
+
+pub fn create_router() -> Router {
+    return Router::new()
+        .route(FILE_SEARCH_PATH, post(file_search))
+        .route(GETFILE_PATH, get(getfile));
+}
+
+#[derive(Deserialize)]
+struct FileSearchForm {
+    sensor_id: String,
+    dtid: String,
+    sdate: String,
+    edate: String,
+}
+
+/// Granule names are `<prefix>.<start time>.L2.SST4.nc` like the real ones
+async fn file_search(Form(form): Form<FileSearchForm>) -> Response {
+    let (sdate, edate) = match (
+        NaiveDateTime::parse_from_str(&form.sdate, DATE_FORMAT),
+        NaiveDateTime::parse_from_str(&form.edate, DATE_FORMAT),
+    ) {
+        (Ok(sdate), Ok(edate)) => (sdate, edate),
+        _ => return (StatusCode::BAD_REQUEST, "invalid sdate or edate").into_response(),
+    };
+
+    let mut names = Vec::new();
+    let mut time = edate.duration_trunc(Duration::hours(1)).unwrap_or(edate);
+    while time >= sdate && names.len() < MAX_GRANULES {
+        names.push(format!(
+            "STANDIN_{}_{}.{}.L2.SST4.nc",
+            form.sensor_id,
+            form.dtid,
+            time.format("%Y%m%dT%H%M%S")
+        ));
+        time -= Duration::hours(1);
+    }
+
+    if names.is_empty() {
+        return String::from("No Results Found").into_response();
+    }
+
+    names.reverse();
+    return names.join("\n").into_response();
+}
+
+async fn getfile(UrlPath(name): UrlPath<String>) -> Response {
+    let contents = tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+        let tmpdir = tempfile::tempdir()?;
+        let path = tmpdir.path().join("granule.nc");
+
+        // the pattern is shifted per granule so consecutive images differ
+        let phase = name.bytes().map(f32::from).sum::<f32>();
+        create_granule(&path, phase)?;
+
+        return Ok(std::fs::read(&path)?);
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|it| it);
+
+    return match contents {
+        Ok(contents) => {
+            ([(header::CONTENT_TYPE, "application/x-netcdf")], contents).into_response()
+        }
+        Err(err) => {
+            error!("failed to create granule: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    };
+}
+
+/// NetCDF-4 file with a `geophysical_data/sst4` variable laid out like in the L2 SST4 products,
+/// a disk of sea surface temperature (degrees Celsius) surrounded by fill values.
+pub fn create_granule(path: &Path, phase: f32) -> Result<()> {
+    let center = GRANULE_SIZE as f32 / 2.0;
+
+    let mut values = vec![FILL_VALUE; GRANULE_SIZE * GRANULE_SIZE];
+    for y in 0..GRANULE_SIZE {
+        for x in 0..GRANULE_SIZE {
+            let (dx, dy) = (x as f32 - center, y as f32 - center);
+            if dx.hypot(dy) > center {
+                continue;
+            }
+
+            let temperature = 15.0 + 10.0 * (x as f32 / 8.0 + phase).sin() * (y as f32 / 8.0).cos();
+            values[y * GRANULE_SIZE + x] = (temperature / SCALE_FACTOR) as i16;
+        }
+    }
+
+    let mut file = netcdf::create(path)?;
+    let mut group = file.add_group("geophysical_data")?;
+    group.add_dimension("number_of_lines", GRANULE_SIZE)?;
+    group.add_dimension("pixels_per_line", GRANULE_SIZE)?;
+
+    let mut sst4 = group.add_variable::<i16>("sst4", &["number_of_lines", "pixels_per_line"])?;
+    sst4.put_attribute("_FillValue", FILL_VALUE)?;
+    sst4.put_attribute("scale_factor", SCALE_FACTOR)?;
+    sst4.put_attribute("add_offset", 0.0f32)?;
+    sst4.put_values(&values, (.., ..))?;
+
+    return Ok(());
+}
@@ -0,0 +1,193 @@
+// Space-Track login and `basicspacedata` queries of the `gp` and `gp_history` classes,
+// answering from the canned catalog. The history holds daily element sets up to the canned epoch.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use chrono::{Duration, NaiveDateTime};
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::utils::{omm::OMM, tle::TLE};
+
+pub const IDENTITY: &str = "standin@example.com";
+pub const PASSWORD: &str = "standin";
+
+const LOGIN_PATH: &str = "/ajaxauth/login";
+const QUERY_PATH: &str = "/basicspacedata/query/*query";
+
+const SESSION_COOKIE: &str = "chocolatechip";
+const EPOCH_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Number of element sets in the history of every object
+const HISTORY_LENGTH: i64 = 3;
+
+struct SpaceTrackState {
+    catalog: Vec<TLE>,
+    sessions: Mutex<HashSet<String>>,
+}
+
+pub fn create_router() -> Result<Router> {
+    let state = SpaceTrackState {
+        catalog: super::celestrak::load_catalog()?,
+        sessions: Mutex::new(HashSet::new()),
+    };
+
+    return Ok(Router::new()
+        .route(LOGIN_PATH, post(login))
+        .route(QUERY_PATH, get(query))
+        .with_state(Arc::new(state)));
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+    identity: String,
+    password: String,
+}
+
+async fn login(State(state): State<Arc<SpaceTrackState>>, Form(form): Form<LoginForm>) -> Response {
+    if form.identity != IDENTITY || form.password != PASSWORD {
+        return String::from(r#"{"Login":"Failed"}"#).into_response();
+    }
+
+    let session = {
+        let mut sessions = state.sessions.lock().unwrap();
+        let session = format!("session{}", sessions.len());
+        sessions.insert(session.clone());
+        session
+    };
+
+    return (
+        [(
+            header::SET_COOKIE,
+            format!("{}={}; path=/", SESSION_COOKIE, session),
+        )],
+        String::from(r#""""#),
+    )
+        .into_response();
+}
+
+fn has_session(state: &SpaceTrackState, headers: &HeaderMap) -> bool {
+    let sessions = state.sessions.lock().unwrap();
+
+    return headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|it| it.to_str().ok())
+        .flat_map(|it| it.split(';'))
+        .filter_map(|it| it.trim().strip_prefix(SESSION_COOKIE)?.strip_prefix('='))
+        .any(|it| sessions.contains(it));
+}
+
+/// Copy of the element set with another epoch
+fn with_epoch(omm: &OMM, epoch: NaiveDateTime) -> Option<OMM> {
+    let mut object = serde_json::to_value(omm).ok()?;
+    object["EPOCH"] = serde_json::Value::from(epoch.format("%Y-%m-%dT%H:%M:%S%.6f").to_string());
+
+    return OMM::from_json(&object.to_string()).ok();
+}
+
+/// Range of the `a--b` form
+fn parse_range(value: &str) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let (from, to) = value.split_once("--")?;
+    return Some((
+        NaiveDateTime::parse_from_str(from, EPOCH_FORMAT).ok()?,
+        NaiveDateTime::parse_from_str(to, EPOCH_FORMAT).ok()?,
+    ));
+}
+
+async fn query(
+    State(state): State<Arc<SpaceTrackState>>,
+    headers: HeaderMap,
+    Path(query): Path<String>,
+) -> Response {
+    if !has_session(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    // predicates are pairs of path segments: class/gp/NORAD_CAT_ID/25544/format/json
+    let predicates = query
+        .split('/')
+        .tuples()
+        .map(|(key, value)| (key.to_uppercase(), String::from(value)))
+        .collect::<HashMap<_, _>>();
+
+    let mut omms = state
+        .catalog
+        .iter()
+        .filter(|tle| {
+            return predicates.iter().all(|(key, value)| match key.as_str() {
+                "NORAD_CAT_ID" => value
+                    .split(',')
+                    .any(|it| it.parse::<u32>().ok() == Some(tle.get_catnr())),
+                "OBJECT_NAME" => tle
+                    .get_name()
+                    .to_uppercase()
+                    .contains(&value.trim_start_matches("~~").to_uppercase()),
+                "OBJECT_ID" => OMM::from(*tle)
+                    .get_international_designator()
+                    .starts_with(value.trim_start_matches('^')),
+                _ => true,
+            });
+        })
+        .map(OMM::from)
+        .collect::<Vec<_>>();
+
+    match predicates.get("CLASS").map(String::as_str) {
+        Some("gp") => {}
+        Some("gp_history") => {
+            let range = predicates.get("EPOCH").and_then(|it| parse_range(it));
+
+            omms = omms
+                .iter()
+                .flat_map(|omm| {
+                    (0..HISTORY_LENGTH)
+                        .rev()
+                        .map(|days| omm.get_epoch().naive_utc() - Duration::days(days))
+                        .filter(|epoch| {
+                            range.is_none_or(|(from, to)| from <= *epoch && *epoch <= to)
+                        })
+                        .filter_map(|epoch| with_epoch(omm, epoch))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+        _ => return (StatusCode::BAD_REQUEST, "unsupported class").into_response(),
+    }
+
+    return match predicates.get("FORMAT").map(String::as_str) {
+        Some("json") => (
+            [(header::CONTENT_TYPE, "application/json")],
+            serde_json::to_string(&omms).unwrap_or_default(),
+        )
+            .into_response(),
+        Some("csv") => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for omm in &omms {
+                if writer.serialize(omm).is_err() {
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+            }
+
+            writer.into_inner().unwrap_or_default().into_response()
+        }
+        Some("3le") => state
+            .catalog
+            .iter()
+            .filter(|tle| omms.iter().any(|it| it.get_catnr() == tle.get_catnr()))
+            .map(|it| format!("0 {}\n{}\n{}", it.get_name(), it.get_tle1(), it.get_tle2()))
+            .join("\n")
+            .into_response(),
+        _ => (StatusCode::BAD_REQUEST, "unsupported format").into_response(),
+    };
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::{
+    omm::OMM,
+    tle::{expand_international_designator, TLE},
+};
+
+/// Mean elements of an object in one of the supported encodings.
+/// TLE can't represent catalog numbers above 339999 (Alpha-5), OMM has no such limit.
+#[derive(Clone, Debug)]
+pub enum ElementSet {
+    TLE(TLE),
+    OMM(OMM),
+}
+
+impl ElementSet {
+    pub fn get_name(&self) -> &String {
+        return match self {
+            ElementSet::TLE(tle) => tle.get_name(),
+            ElementSet::OMM(omm) => omm.get_name(),
+        };
+    }
+
+    pub fn get_catnr(&self) -> u32 {
+        return match self {
+            ElementSet::TLE(tle) => tle.get_catnr(),
+            ElementSet::OMM(omm) => omm.get_catnr(),
+        };
+    }
+
+    /// International designator in the OMM form (yyyy-nnnppp)
+    pub fn get_international_designator(&self) -> String {
+        return match self {
+            ElementSet::TLE(tle) => {
+                expand_international_designator(tle.get_international_designator())
+            }
+            ElementSet::OMM(omm) => omm.get_international_designator().clone(),
+        };
+    }
+
+    pub fn get_epoch(&self) -> &DateTime<Utc> {
+        return match self {
+            ElementSet::TLE(tle) => tle.get_epoch(),
+            ElementSet::OMM(omm) => omm.get_epoch(),
+        };
+    }
+
+    pub fn to_omm(&self) -> OMM {
+        return match self {
+            ElementSet::TLE(tle) => OMM::from(tle),
+            ElementSet::OMM(omm) => omm.clone(),
+        };
+    }
+}
+
+impl From<TLE> for ElementSet {
+    fn from(tle: TLE) -> Self {
+        return ElementSet::TLE(tle);
+    }
+}
+
+impl From<OMM> for ElementSet {
+    fn from(omm: OMM) -> Self {
+        return ElementSet::OMM(omm);
+    }
+}
+
+/// Columns an element set is persisted in: either both lines of a TLE or an OMM as JSON.
+#[derive(Clone, Default)]
+pub struct StoredElementSet {
+    pub tle1: Option<String>,
+    pub tle2: Option<String>,
+    pub omm: Option<String>,
+}
+
+impl StoredElementSet {
+    /// None if nothing is stored
+    pub fn decode(&self, name: &str) -> Result<Option<ElementSet>> {
+        if let Some(omm) = &self.omm {
+            return Ok(Some(ElementSet::OMM(OMM::from_json(omm)?)));
+        }
+
+        return match (&self.tle1, &self.tle2) {
+            (Some(tle1), Some(tle2)) => Ok(Some(ElementSet::TLE(TLE::new(name, tle1, tle2)?))),
+            _ => Ok(None),
+        };
+    }
+}
+
+impl From<&ElementSet> for StoredElementSet {
+    fn from(element_set: &ElementSet) -> Self {
+        return match element_set {
+            ElementSet::TLE(tle) => Self {
+                tle1: Some(tle.get_tle1().clone()),
+                tle2: Some(tle.get_tle2().clone()),
+                omm: None,
+            },
+            ElementSet::OMM(omm) => Self {
+                tle1: None,
+                tle2: None,
+                omm: Some(omm.to_json()),
+            },
+        };
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+
+use super::sgp4::{gstime, julian_date, TemeState};
+
+// WGS-84 ellipsoid (EPSG:4326)
+const WGS84_A: f64 = 6378.137; // km
+const WGS84_F: f64 = 1.0 / 298.257223563;
+const EARTH_ROTATION_RATE: f64 = 7.292115e-5; // rad/s
+
+/// Geodetic coordinates on the WGS-84 ellipsoid. Latitude and longitude are in degrees, altitude in kilometers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geodetic {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: f64,
+}
+
+/// Earth-centered, Earth-fixed position (km) and velocity (km/s).
+#[derive(Clone, Copy, Debug)]
+pub struct EcefState {
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+}
+
+/// Rotates a TEME state into the Earth-fixed frame using Greenwich mean sidereal time.
+/// Polar motion is neglected, which is well below the accuracy of SGP4 itself.
+pub fn teme_to_ecef(state: &TemeState, time: &DateTime<Utc>) -> EcefState {
+    let gmst = gstime(julian_date(time));
+    let (sin_g, cos_g) = gmst.sin_cos();
+
+    let [x, y, z] = state.position;
+    let position = [cos_g * x + sin_g * y, -sin_g * x + cos_g * y, z];
+
+    let [vx, vy, vz] = state.velocity;
+    let velocity = [
+        cos_g * vx + sin_g * vy + EARTH_ROTATION_RATE * position[1],
+        -sin_g * vx + cos_g * vy - EARTH_ROTATION_RATE * position[0],
+        vz,
+    ];
+
+    return EcefState { position, velocity };
+}
+
+pub fn ecef_to_geodetic(position: &[f64; 3]) -> Geodetic {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let [x, y, z] = *position;
+
+    let longitude = y.atan2(x);
+    let p = (x * x + y * y).sqrt();
+
+    let mut latitude = z.atan2(p * (1.0 - e2));
+    let mut altitude = 0.0;
+
+    for _ in 0..10 {
+        let sin_lat = latitude.sin();
+        let n = WGS84_A / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+
+        altitude = if latitude.cos().abs() > 1e-10 {
+            p / latitude.cos() - n
+        } else {
+            z.abs() - n * (1.0 - e2)
+        };
+
+        let next = z.atan2(p * (1.0 - e2 * n / (n + altitude)));
+        let converged = (next - latitude).abs() < 1e-12;
+        latitude = next;
+
+        if converged {
+            break;
+        }
+    }
+
+    return Geodetic {
+        latitude: latitude.to_degrees(),
+        longitude: longitude.to_degrees(),
+        altitude,
+    };
+}
+
+pub fn teme_to_geodetic(state: &TemeState, time: &DateTime<Utc>) -> Geodetic {
+    return ecef_to_geodetic(&teme_to_ecef(state, time).position);
+}
+
+pub fn geodetic_to_ecef(geodetic: &Geodetic) -> [f64; 3] {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let latitude = geodetic.latitude.to_radians();
+    let longitude = geodetic.longitude.to_radians();
+
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lon, cos_lon) = longitude.sin_cos();
+    let n = WGS84_A / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+
+    return [
+        (n + geodetic.altitude) * cos_lat * cos_lon,
+        (n + geodetic.altitude) * cos_lat * sin_lon,
+        (n * (1.0 - e2) + geodetic.altitude) * sin_lat,
+    ];
+}
+
+/// Topocentric coordinates of a satellite as seen by an observer.
+/// Azimuth (clockwise from north) and elevation are in degrees, range in km and range rate in km/s.
+#[derive(Clone, Copy, Debug)]
+pub struct LookAngles {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub range: f64,
+    pub range_rate: f64,
+}
+
+pub fn look_angles(observer: &Geodetic, satellite: &EcefState) -> LookAngles {
+    let observer_position = geodetic_to_ecef(observer);
+    let rho = [
+        satellite.position[0] - observer_position[0],
+        satellite.position[1] - observer_position[1],
+        satellite.position[2] - observer_position[2],
+    ];
+
+    let (sin_lat, cos_lat) = observer.latitude.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = observer.longitude.to_radians().sin_cos();
+
+    let south = sin_lat * cos_lon * rho[0] + sin_lat * sin_lon * rho[1] - cos_lat * rho[2];
+    let east = -sin_lon * rho[0] + cos_lon * rho[1];
+    let zenith = cos_lat * cos_lon * rho[0] + cos_lat * sin_lon * rho[1] + sin_lat * rho[2];
+
+    let range = (rho[0] * rho[0] + rho[1] * rho[1] + rho[2] * rho[2]).sqrt();
+    let range_rate = (rho[0] * satellite.velocity[0]
+        + rho[1] * satellite.velocity[1]
+        + rho[2] * satellite.velocity[2])
+        / range;
+
+    let mut azimuth = east.atan2(-south).to_degrees();
+    if azimuth < 0.0 {
+        azimuth += 360.0;
+    }
+
+    return LookAngles {
+        azimuth,
+        elevation: (zenith / range).asin().to_degrees(),
+        range,
+        range_rate,
+    };
+}
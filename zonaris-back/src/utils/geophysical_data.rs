@@ -67,6 +67,7 @@ impl GeophysicalData {
         });
     }
 
+    #[allow(dead_code)]
     fn apply_bias(&self, bias: &GeophysicalData) -> GeophysicalData {
         let height = self.height;
         let width = self.width;
@@ -1,2 +1,8 @@
-pub mod struct_utils;
+pub mod element_set;
+pub mod geodesy;
 pub mod geophysical_data;
+pub mod omm;
+pub mod satcat;
+pub mod sgp4;
+pub mod struct_utils;
+pub mod tle;
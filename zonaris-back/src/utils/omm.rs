@@ -0,0 +1,287 @@
+// CCSDS Orbit Mean-Elements Message (CCSDS 502.0-B-3) with SGP4 mean elements,
+// as distributed by Celestrak and Space-Track in the JSON, XML and CSV encodings.
+// https://celestrak.org/NORAD/documentation/gp-data-formats.php
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::events::Event;
+use serde::{Serialize, Serializer};
+use table_macro::Property;
+use thiserror::Error;
+
+use super::tle::{expand_international_designator, TLE};
+
+const EPOCH_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+#[derive(Error, Debug)]
+pub enum OmmError {
+    #[error("missing {0}")]
+    MissingField(&'static str),
+    #[error("invalid {field} '{value}'")]
+    InvalidField { field: &'static str, value: String },
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid xml: {0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("invalid csv: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+fn serialize_epoch<S: Serializer>(epoch: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    return serializer.serialize_str(&epoch.format("%Y-%m-%dT%H:%M:%S%.6f").to_string());
+}
+
+/// Mean elements of a single object. Angles are in degrees, mean motion in revolutions per day.
+/// Serializes to the same JSON layout it is parsed from.
+#[derive(Clone, Debug, Serialize, Property)]
+pub struct OMM {
+    #[getter]
+    #[serde(rename = "OBJECT_NAME")]
+    name: String,
+    /// International designator (yyyy-nnnppp)
+    #[getter]
+    #[serde(rename = "OBJECT_ID")]
+    international_designator: String,
+    #[getter]
+    #[serde(rename = "EPOCH", serialize_with = "serialize_epoch")]
+    epoch: DateTime<Utc>,
+
+    #[getter]
+    #[serde(rename = "MEAN_MOTION")]
+    mean_motion: f64,
+    #[getter]
+    #[serde(rename = "ECCENTRICITY")]
+    eccentricity: f64,
+    #[getter]
+    #[serde(rename = "INCLINATION")]
+    inclination: f64,
+    #[getter]
+    #[serde(rename = "RA_OF_ASC_NODE")]
+    right_ascension: f64,
+    #[getter]
+    #[serde(rename = "ARG_OF_PERICENTER")]
+    argument_of_perigee: f64,
+    #[getter]
+    #[serde(rename = "MEAN_ANOMALY")]
+    mean_anomaly: f64,
+
+    #[getter]
+    #[serde(rename = "EPHEMERIS_TYPE")]
+    ephemeris_type: u8,
+    #[getter]
+    #[serde(rename = "CLASSIFICATION_TYPE")]
+    classification: char,
+    /// Up to 9 digits
+    #[getter]
+    #[serde(rename = "NORAD_CAT_ID")]
+    catnr: u32,
+    #[getter]
+    #[serde(rename = "ELEMENT_SET_NO")]
+    element_set_number: u32,
+    #[getter]
+    #[serde(rename = "REV_AT_EPOCH")]
+    revolution_number: u32,
+    #[getter]
+    #[serde(rename = "BSTAR")]
+    bstar: f64,
+    #[getter]
+    #[serde(rename = "MEAN_MOTION_DOT")]
+    mean_motion_dot: f64,
+    #[getter]
+    #[serde(rename = "MEAN_MOTION_DDOT")]
+    mean_motion_ddot: f64,
+}
+
+struct Fields<'a>(&'a HashMap<String, String>);
+
+impl<'a> Fields<'a> {
+    fn str(&self, field: &'static str) -> Result<&'a str, OmmError> {
+        return self
+            .0
+            .get(field)
+            .map(|it| it.trim())
+            .filter(|it| !it.is_empty())
+            .ok_or(OmmError::MissingField(field));
+    }
+
+    fn parse<T: std::str::FromStr>(&self, field: &'static str) -> Result<T, OmmError> {
+        let value = self.str(field)?;
+        return value.parse::<T>().map_err(|_| OmmError::InvalidField {
+            field,
+            value: String::from(value),
+        });
+    }
+
+    /// Optional TLE-related fields default to their most common values
+    fn parse_or<T: std::str::FromStr>(
+        &self,
+        field: &'static str,
+        default: T,
+    ) -> Result<T, OmmError> {
+        return match self.str(field) {
+            Ok(_) => self.parse(field),
+            Err(OmmError::MissingField(_)) => Ok(default),
+            Err(error) => Err(error),
+        };
+    }
+
+    fn epoch(&self) -> Result<DateTime<Utc>, OmmError> {
+        let value = self.str("EPOCH")?;
+        return NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), EPOCH_FORMAT)
+            .map(|it| it.and_utc())
+            .map_err(|_| OmmError::InvalidField {
+                field: "EPOCH",
+                value: String::from(value),
+            });
+    }
+}
+
+impl OMM {
+    /// Builds an OMM from CCSDS keyword/value pairs, e.g. a row of the CSV encoding.
+    pub fn from_fields(fields: &HashMap<String, String>) -> Result<Self, OmmError> {
+        let fields = Fields(fields);
+
+        let eccentricity = fields.parse::<f64>("ECCENTRICITY")?;
+        if !(0.0..1.0).contains(&eccentricity) {
+            return Err(OmmError::InvalidField {
+                field: "ECCENTRICITY",
+                value: eccentricity.to_string(),
+            });
+        }
+
+        return Ok(Self {
+            name: String::from(fields.str("OBJECT_NAME")?),
+            international_designator: String::from(fields.str("OBJECT_ID").unwrap_or("")),
+            epoch: fields.epoch()?,
+
+            mean_motion: fields.parse("MEAN_MOTION")?,
+            eccentricity,
+            inclination: fields.parse("INCLINATION")?,
+            right_ascension: fields.parse("RA_OF_ASC_NODE")?,
+            argument_of_perigee: fields.parse("ARG_OF_PERICENTER")?,
+            mean_anomaly: fields.parse("MEAN_ANOMALY")?,
+
+            ephemeris_type: fields.parse_or("EPHEMERIS_TYPE", 0)?,
+            classification: fields.parse_or("CLASSIFICATION_TYPE", 'U')?,
+            catnr: fields.parse("NORAD_CAT_ID")?,
+            element_set_number: fields.parse_or("ELEMENT_SET_NO", 999)?,
+            revolution_number: fields.parse_or("REV_AT_EPOCH", 0)?,
+            bstar: fields.parse_or("BSTAR", 0.0)?,
+            mean_motion_dot: fields.parse_or("MEAN_MOTION_DOT", 0.0)?,
+            mean_motion_ddot: fields.parse_or("MEAN_MOTION_DDOT", 0.0)?,
+        });
+    }
+
+    pub fn to_json(&self) -> String {
+        // serialization of plain numbers and strings can't fail
+        return serde_json::to_string(self).unwrap_or_default();
+    }
+
+    /// Single object, as produced by `to_json`
+    pub fn from_json(text: &str) -> Result<Self, OmmError> {
+        let object = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(text)?;
+        return OMM::from_fields(&json_fields(object));
+    }
+}
+
+impl From<&TLE> for OMM {
+    fn from(tle: &TLE) -> Self {
+        return Self {
+            name: tle.get_name().clone(),
+            international_designator: expand_international_designator(
+                tle.get_international_designator(),
+            ),
+            epoch: *tle.get_epoch(),
+
+            mean_motion: tle.get_mean_motion(),
+            eccentricity: tle.get_eccentricity(),
+            inclination: tle.get_inclination(),
+            right_ascension: tle.get_right_ascension(),
+            argument_of_perigee: tle.get_argument_of_perigee(),
+            mean_anomaly: tle.get_mean_anomaly(),
+
+            ephemeris_type: 0,
+            classification: tle.get_classification(),
+            catnr: tle.get_catnr(),
+            element_set_number: tle.get_element_set_number(),
+            revolution_number: tle.get_revolution_number(),
+            bstar: tle.get_bstar(),
+            mean_motion_dot: tle.get_mean_motion_dot(),
+            mean_motion_ddot: tle.get_mean_motion_ddot(),
+        };
+    }
+}
+
+/// Celestrak encodes numbers as JSON numbers, Space-Track as strings
+fn json_fields(object: serde_json::Map<String, serde_json::Value>) -> HashMap<String, String> {
+    return object
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::String(value) => Some((key, value)),
+            serde_json::Value::Number(value) => Some((key, value.to_string())),
+            _ => None,
+        })
+        .collect();
+}
+
+/// Array of objects with CCSDS keywords as keys
+pub fn parse_json(text: &str) -> Result<Vec<OMM>, OmmError> {
+    return serde_json::from_str::<Vec<serde_json::Map<String, serde_json::Value>>>(text)?
+        .into_iter()
+        .map(|object| OMM::from_fields(&json_fields(object)))
+        .collect();
+}
+
+/// NDM container with one `omm` element per object. Keywords are looked up by name
+/// regardless of the block (`metadata`, `meanElements`, `tleParameters`) they are in.
+pub fn parse_xml(text: &str) -> Result<Vec<OMM>, OmmError> {
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.trim_text(true);
+
+    let mut result = Vec::new();
+    let mut fields: Option<HashMap<String, String>> = None;
+    let mut current: Option<String> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                if name == "omm" {
+                    fields = Some(HashMap::new());
+                } else {
+                    current = Some(name);
+                }
+            }
+            Event::Text(text) => {
+                if let (Some(fields), Some(name)) = (fields.as_mut(), current.as_ref()) {
+                    fields.insert(name.clone(), text.unescape()?.to_string());
+                }
+            }
+            Event::End(element) => {
+                if element.local_name().as_ref() == b"omm" {
+                    if let Some(fields) = fields.take() {
+                        result.push(OMM::from_fields(&fields)?);
+                    }
+                }
+                current = None;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    return Ok(result);
+}
+
+/// Header row with CCSDS keywords followed by one row per object
+pub fn parse_csv(text: &str) -> Result<Vec<OMM>, OmmError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    return reader
+        .deserialize::<HashMap<String, String>>()
+        .map(|row| OMM::from_fields(&row?))
+        .collect();
+}
@@ -0,0 +1,148 @@
+// Celestrak satellite catalog (SATCAT) records in the JSON and CSV encodings.
+// https://celestrak.org/satcat/satcat-format.php
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use table_macro::Property;
+use thiserror::Error;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Error, Debug)]
+pub enum SatcatError {
+    #[error("missing {0}")]
+    MissingField(&'static str),
+    #[error("invalid {field} '{value}'")]
+    InvalidField { field: &'static str, value: String },
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid csv: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+/// Catalog entry of an object. Empty strings and None stand for values which are not provided.
+#[derive(Clone, Debug, Property)]
+pub struct SatcatRecord {
+    #[getter]
+    name: String,
+    #[getter]
+    catnr: u32,
+    /// International designator (yyyy-nnnppp)
+    #[getter]
+    international_designator: String,
+    /// PAY (payload), R/B (rocket body), DEB (debris) or UNK (unknown)
+    #[getter]
+    object_type: String,
+    /// Operational status code, e.g. `+` operational, `-` nonoperational, `D` decayed
+    #[getter]
+    operational_status: String,
+    /// Owner or country code, e.g. `US`, `ESA`
+    #[getter]
+    owner: String,
+    #[getter]
+    launch_date: Option<NaiveDate>,
+    /// Launch site code, e.g. `AFWTR`
+    #[getter]
+    launch_site: String,
+    #[getter]
+    decay_date: Option<NaiveDate>,
+    /// Radar cross section, m^2
+    #[getter]
+    rcs: Option<f64>,
+}
+
+struct Fields<'a>(&'a HashMap<String, String>);
+
+impl<'a> Fields<'a> {
+    fn str(&self, field: &'static str) -> Option<&'a str> {
+        return self
+            .0
+            .get(field)
+            .map(|it| it.trim())
+            .filter(|it| !it.is_empty());
+    }
+
+    fn string(&self, field: &'static str) -> String {
+        return String::from(self.str(field).unwrap_or(""));
+    }
+
+    fn parse<T: std::str::FromStr>(&self, field: &'static str) -> Result<Option<T>, SatcatError> {
+        return self
+            .str(field)
+            .map(|value| {
+                value.parse::<T>().map_err(|_| SatcatError::InvalidField {
+                    field,
+                    value: String::from(value),
+                })
+            })
+            .transpose();
+    }
+
+    fn date(&self, field: &'static str) -> Result<Option<NaiveDate>, SatcatError> {
+        return self
+            .str(field)
+            .map(|value| {
+                NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|_| {
+                    SatcatError::InvalidField {
+                        field,
+                        value: String::from(value),
+                    }
+                })
+            })
+            .transpose();
+    }
+}
+
+impl SatcatRecord {
+    /// Builds a record from SATCAT keyword/value pairs, e.g. a row of the CSV encoding.
+    pub fn from_fields(fields: &HashMap<String, String>) -> Result<Self, SatcatError> {
+        let fields = Fields(fields);
+
+        return Ok(Self {
+            name: fields.string("OBJECT_NAME"),
+            catnr: fields
+                .parse("NORAD_CAT_ID")?
+                .ok_or(SatcatError::MissingField("NORAD_CAT_ID"))?,
+            international_designator: fields.string("OBJECT_ID"),
+            object_type: fields.string("OBJECT_TYPE"),
+            operational_status: fields.string("OPS_STATUS_CODE"),
+            owner: fields.string("OWNER"),
+            launch_date: fields.date("LAUNCH_DATE")?,
+            launch_site: fields.string("LAUNCH_SITE"),
+            decay_date: fields.date("DECAY_DATE")?,
+            rcs: fields.parse("RCS")?,
+        });
+    }
+}
+
+/// Array of objects with SATCAT keywords as keys, missing values are null
+pub fn parse_json(text: &str) -> Result<Vec<SatcatRecord>, SatcatError> {
+    return serde_json::from_str::<Vec<serde_json::Map<String, serde_json::Value>>>(text)?
+        .into_iter()
+        .map(|object| {
+            let fields = object
+                .into_iter()
+                .filter_map(|(key, value)| match value {
+                    serde_json::Value::String(value) => Some((key, value)),
+                    serde_json::Value::Number(value) => Some((key, value.to_string())),
+                    _ => None,
+                })
+                .collect();
+
+            return SatcatRecord::from_fields(&fields);
+        })
+        .collect();
+}
+
+/// Header row with SATCAT keywords followed by one row per object
+pub fn parse_csv(text: &str) -> Result<Vec<SatcatRecord>, SatcatError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    return reader
+        .deserialize::<HashMap<String, String>>()
+        .map(|row| SatcatRecord::from_fields(&row?))
+        .collect();
+}
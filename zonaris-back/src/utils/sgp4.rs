@@ -0,0 +1,1265 @@
+// SGP4/SDP4 orbit propagator.
+// Port of the reference implementation by David Vallado (Revisiting Spacetrack Report #3, AIAA 2006-6753)
+// using WGS-72 gravity constants (the ones the element sets are generated with) and the "improved" operation mode.
+// The comparisons and constants are written as in the reference to keep the port checkable against it.
+#![allow(clippy::manual_range_contains, clippy::excessive_precision)]
+
+use std::f64::consts::PI;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use super::{element_set::ElementSet, omm::OMM, tle::TLE};
+
+const TWO_PI: f64 = 2.0 * PI;
+const X2O3: f64 = 2.0 / 3.0;
+const DEG2RAD: f64 = PI / 180.0;
+const MINUTES_PER_DAY: f64 = 1440.0;
+
+// WGS-72
+const MU: f64 = 398600.8; // km^3 / s^2
+pub const EARTH_RADIUS_KM: f64 = 6378.135;
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+
+fn xke() -> f64 {
+    return 60.0 / (EARTH_RADIUS_KM * EARTH_RADIUS_KM * EARTH_RADIUS_KM / MU).sqrt();
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum Sgp4Error {
+    #[error("mean eccentricity is out of range")]
+    MeanEccentricity,
+    #[error("mean motion is less than zero")]
+    MeanMotion,
+    #[error("perturbed eccentricity is out of range")]
+    PerturbedEccentricity,
+    #[error("semi-latus rectum is less than zero")]
+    SemiLatusRectum,
+    #[error("satellite has decayed")]
+    Decayed,
+}
+
+/// Mean orbital elements required by SGP4. Angles are in radians, mean motion in radians per minute.
+#[derive(Clone, Debug)]
+pub struct Elements {
+    pub epoch: DateTime<Utc>,
+    pub bstar: f64,
+    pub inclination: f64,
+    pub right_ascension: f64,
+    pub eccentricity: f64,
+    pub argument_of_perigee: f64,
+    pub mean_anomaly: f64,
+    pub mean_motion: f64,
+}
+
+impl From<&TLE> for Elements {
+    fn from(tle: &TLE) -> Self {
+        return Self {
+            epoch: *tle.get_epoch(),
+            bstar: tle.get_bstar(),
+            inclination: tle.get_inclination() * DEG2RAD,
+            right_ascension: tle.get_right_ascension() * DEG2RAD,
+            eccentricity: tle.get_eccentricity(),
+            argument_of_perigee: tle.get_argument_of_perigee() * DEG2RAD,
+            mean_anomaly: tle.get_mean_anomaly() * DEG2RAD,
+            mean_motion: tle.get_mean_motion() * TWO_PI / MINUTES_PER_DAY,
+        };
+    }
+}
+
+impl From<&OMM> for Elements {
+    fn from(omm: &OMM) -> Self {
+        return Self {
+            epoch: *omm.get_epoch(),
+            bstar: omm.get_bstar(),
+            inclination: omm.get_inclination() * DEG2RAD,
+            right_ascension: omm.get_right_ascension() * DEG2RAD,
+            eccentricity: omm.get_eccentricity(),
+            argument_of_perigee: omm.get_argument_of_perigee() * DEG2RAD,
+            mean_anomaly: omm.get_mean_anomaly() * DEG2RAD,
+            mean_motion: omm.get_mean_motion() * TWO_PI / MINUTES_PER_DAY,
+        };
+    }
+}
+
+impl From<&ElementSet> for Elements {
+    fn from(element_set: &ElementSet) -> Self {
+        return match element_set {
+            ElementSet::TLE(tle) => Elements::from(tle),
+            ElementSet::OMM(omm) => Elements::from(omm),
+        };
+    }
+}
+
+/// Julian date of the given instant (UTC is used in place of UT1).
+pub fn julian_date(time: &DateTime<Utc>) -> f64 {
+    let seconds = time.timestamp() as f64 + time.timestamp_subsec_nanos() as f64 * 1e-9;
+    return seconds / 86400.0 + 2440587.5;
+}
+
+/// Greenwich mean sidereal time in radians (IAU-82).
+pub fn gstime(jdut1: f64) -> f64 {
+    let tut1 = (jdut1 - 2451545.0) / 36525.0;
+    let mut temp = -6.2e-6 * tut1 * tut1 * tut1
+        + 0.093104 * tut1 * tut1
+        + (876600.0 * 3600.0 + 8640184.812866) * tut1
+        + 67310.54841;
+    temp = (temp * DEG2RAD / 240.0) % TWO_PI;
+
+    if temp < 0.0 {
+        temp += TWO_PI;
+    }
+
+    return temp;
+}
+
+/// Position (km) and velocity (km/s) in the True Equator Mean Equinox frame.
+#[derive(Clone, Copy, Debug)]
+pub struct TemeState {
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+}
+
+struct MeanElements {
+    em: f64,
+    argpm: f64,
+    inclm: f64,
+    mm: f64,
+    nodem: f64,
+    nm: f64,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Method {
+    NearEarth,
+    DeepSpace,
+}
+
+#[derive(Clone, Default)]
+struct DeepSpace {
+    e3: f64,
+    ee2: f64,
+    se2: f64,
+    se3: f64,
+    sgh2: f64,
+    sgh3: f64,
+    sgh4: f64,
+    sh2: f64,
+    sh3: f64,
+    si2: f64,
+    si3: f64,
+    sl2: f64,
+    sl3: f64,
+    sl4: f64,
+    xgh2: f64,
+    xgh3: f64,
+    xgh4: f64,
+    xh2: f64,
+    xh3: f64,
+    xi2: f64,
+    xi3: f64,
+    xl2: f64,
+    xl3: f64,
+    xl4: f64,
+    zmol: f64,
+    zmos: f64,
+
+    irez: i32,
+    d2201: f64,
+    d2211: f64,
+    d3210: f64,
+    d3222: f64,
+    d4410: f64,
+    d4422: f64,
+    d5220: f64,
+    d5232: f64,
+    d5421: f64,
+    d5433: f64,
+    dedt: f64,
+    didt: f64,
+    dmdt: f64,
+    dnodt: f64,
+    domdt: f64,
+    del1: f64,
+    del2: f64,
+    del3: f64,
+    xfact: f64,
+    xlamo: f64,
+}
+
+struct DeepSpaceCommon {
+    sinim: f64,
+    cosim: f64,
+    emsq: f64,
+    s1: f64,
+    s2: f64,
+    s3: f64,
+    s4: f64,
+    s5: f64,
+    ss1: f64,
+    ss2: f64,
+    ss3: f64,
+    ss4: f64,
+    ss5: f64,
+    sz1: f64,
+    sz3: f64,
+    sz11: f64,
+    sz13: f64,
+    sz21: f64,
+    sz23: f64,
+    sz31: f64,
+    sz33: f64,
+    z1: f64,
+    z3: f64,
+    z11: f64,
+    z13: f64,
+    z21: f64,
+    z23: f64,
+    z31: f64,
+    z33: f64,
+}
+
+/// Initialized SGP4 model of a single element set.
+#[derive(Clone)]
+pub struct Sgp4 {
+    epoch: DateTime<Utc>,
+    method: Method,
+    isimp: bool,
+
+    bstar: f64,
+    ecco: f64,
+    argpo: f64,
+    inclo: f64,
+    mo: f64,
+    nodeo: f64,
+    no_unkozai: f64,
+
+    aycof: f64,
+    con41: f64,
+    cc1: f64,
+    cc4: f64,
+    cc5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    eta: f64,
+    argpdot: f64,
+    omgcof: f64,
+    sinmao: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    mdot: f64,
+    nodedot: f64,
+    xlcof: f64,
+    xmcof: f64,
+    nodecf: f64,
+    gsto: f64,
+
+    deep_space: DeepSpace,
+}
+
+impl Sgp4 {
+    pub fn new(elements: &Elements) -> Result<Self, Sgp4Error> {
+        let xke = xke();
+        let j3oj2 = J3 / J2;
+        let temp4 = 1.5e-12;
+
+        let ss = 78.0 / EARTH_RADIUS_KM + 1.0;
+        let qzms2t = ((120.0 - 78.0) / EARTH_RADIUS_KM).powi(4);
+
+        let ecco = elements.eccentricity;
+        let inclo = elements.inclination;
+        let no_kozai = elements.mean_motion;
+
+        // initl: un-kozai the mean motion
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = inclo.cos();
+        let cosio2 = cosio * cosio;
+
+        let ak = (xke / no_kozai).powf(X2O3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let mut del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        del = d1 / (adel * adel);
+        let no_unkozai = no_kozai / (1.0 + del);
+
+        let ao = (xke / no_unkozai).powf(X2O3);
+        let sinio = inclo.sin();
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+
+        let epoch = julian_date(&elements.epoch) - 2433281.5;
+        let gsto = gstime(epoch + 2433281.5);
+
+        if !(omeosq >= 0.0 || no_unkozai >= 0.0) {
+            return Err(Sgp4Error::MeanEccentricity);
+        }
+
+        let mut isimp = rp < 220.0 / EARTH_RADIUS_KM + 1.0;
+
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (rp - 1.0) * EARTH_RADIUS_KM;
+
+        // for perigees below 156 km, s and qoms2t are altered
+        if perige < 156.0 {
+            sfour = perige - 78.0;
+            if perige < 98.0 {
+                sfour = 20.0;
+            }
+            qzms24 = ((120.0 - sfour) / EARTH_RADIUS_KM).powi(4);
+            sfour = sfour / EARTH_RADIUS_KM + 1.0;
+        }
+
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no_unkozai
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let cc1 = elements.bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 {
+            -2.0 * coef * tsi * j3oj2 * no_unkozai * sinio / ecco
+        } else {
+            0.0
+        };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0
+            * no_unkozai
+            * coef1
+            * ao
+            * omeosq
+            * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75
+                            * x1mth2
+                            * (2.0 * etasq - eeta * (1.0 + etasq))
+                            * (2.0 * elements.argument_of_perigee).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no_unkozai;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no_unkozai;
+        let mdot = no_unkozai
+            + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1
+            + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let xpidot = argpdot + nodedot;
+        let omgcof = elements.bstar * cc3 * elements.argument_of_perigee.cos();
+        let xmcof = if ecco > 1.0e-4 {
+            -X2O3 * coef * elements.bstar / eeta
+        } else {
+            0.0
+        };
+        let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+        let t2cof = 1.5 * cc1;
+        let xlcof = if (cosio + 1.0).abs() > 1.5e-12 {
+            -0.25 * j3oj2 * sinio * (3.0 + 5.0 * cosio) / (1.0 + cosio)
+        } else {
+            -0.25 * j3oj2 * sinio * (3.0 + 5.0 * cosio) / temp4
+        };
+        let aycof = -0.5 * j3oj2 * sinio;
+        let delmo = (1.0 + eta * elements.mean_anomaly.cos()).powi(3);
+        let sinmao = elements.mean_anomaly.sin();
+        let x7thm1 = 7.0 * cosio2 - 1.0;
+
+        let mut model = Self {
+            epoch: elements.epoch,
+            method: Method::NearEarth,
+            isimp,
+
+            bstar: elements.bstar,
+            ecco,
+            argpo: elements.argument_of_perigee,
+            inclo,
+            mo: elements.mean_anomaly,
+            nodeo: elements.right_ascension,
+            no_unkozai,
+
+            aycof,
+            con41,
+            cc1,
+            cc4,
+            cc5,
+            d2: 0.0,
+            d3: 0.0,
+            d4: 0.0,
+            delmo,
+            eta,
+            argpdot,
+            omgcof,
+            sinmao,
+            t2cof,
+            t3cof: 0.0,
+            t4cof: 0.0,
+            t5cof: 0.0,
+            x1mth2,
+            x7thm1,
+            mdot,
+            nodedot,
+            xlcof,
+            xmcof,
+            nodecf,
+            gsto,
+
+            deep_space: DeepSpace::default(),
+        };
+
+        // deep space initialization for orbits with period >= 225 minutes
+        if TWO_PI / no_unkozai >= 225.0 {
+            model.method = Method::DeepSpace;
+            isimp = true;
+            model.isimp = isimp;
+
+            let common = model.dscom(epoch, 0.0);
+            model.dsinit(&common, xpidot, eccsq);
+        }
+
+        if !isimp {
+            let cc1sq = cc1 * cc1;
+            let d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = d2 * tsi * cc1 / 3.0;
+            let d3 = (17.0 * ao + sfour) * temp;
+            let d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+
+            model.d2 = d2;
+            model.d3 = d3;
+            model.d4 = d4;
+            model.t3cof = d2 + 2.0 * cc1sq;
+            model.t4cof = 0.25 * (3.0 * d3 + cc1 * (12.0 * d2 + 10.0 * cc1sq));
+            model.t5cof = 0.2
+                * (3.0 * d4 + 12.0 * cc1 * d3 + 6.0 * d2 * d2 + 15.0 * cc1sq * (2.0 * d2 + cc1sq));
+        }
+
+        model.propagate(0.0)?;
+
+        return Ok(model);
+    }
+
+    pub fn epoch(&self) -> DateTime<Utc> {
+        return self.epoch;
+    }
+
+    pub fn is_deep_space(&self) -> bool {
+        return self.method == Method::DeepSpace;
+    }
+
+    /// Propagates the element set to the given instant.
+    pub fn propagate_to(&self, time: &DateTime<Utc>) -> Result<TemeState, Sgp4Error> {
+        let tsince = (*time - self.epoch)
+            .num_nanoseconds()
+            .map(|it| it as f64 / 60e9)
+            .unwrap_or_else(|| (*time - self.epoch).num_seconds() as f64 / 60.0);
+
+        return self.propagate(tsince);
+    }
+
+    /// Propagates the element set `tsince` minutes from its epoch.
+    pub fn propagate(&self, tsince: f64) -> Result<TemeState, Sgp4Error> {
+        let xke = xke();
+        let j3oj2 = J3 / J2;
+        let temp4 = 1.5e-12;
+        let vkmpersec = EARTH_RADIUS_KM * xke / 60.0;
+
+        let t = tsince;
+
+        // update for secular gravity and atmospheric drag
+        let xmdf = self.mo + self.mdot * t;
+        let argpdf = self.argpo + self.argpdot * t;
+        let nodedf = self.nodeo + self.nodedot * t;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let t2 = t * t;
+        let nodem = nodedf + self.nodecf * t2;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = self.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delmtemp = 1.0 + self.eta * xmdf.cos();
+            let delm = self.xmcof * (delmtemp * delmtemp * delmtemp - self.delmo);
+            let temp = delomg + delm;
+            mm = xmdf + temp;
+            argpm = argpdf - temp;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa = tempa - self.d2 * t2 - self.d3 * t3 - self.d4 * t4;
+            tempe += self.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let mut mean = MeanElements {
+            em: self.ecco,
+            argpm,
+            inclm: self.inclo,
+            mm,
+            nodem,
+            nm: self.no_unkozai,
+        };
+
+        if self.method == Method::DeepSpace {
+            self.dspace(t, &mut mean);
+        }
+
+        let MeanElements {
+            mut em,
+            mut argpm,
+            inclm,
+            mut mm,
+            mut nodem,
+            mut nm,
+        } = mean;
+
+        if nm <= 0.0 {
+            return Err(Sgp4Error::MeanMotion);
+        }
+
+        let am = (xke / nm).powf(X2O3) * tempa * tempa;
+        nm = xke / am.powf(1.5);
+        em -= tempe;
+
+        if em >= 1.0 || em < -0.001 {
+            return Err(Sgp4Error::MeanEccentricity);
+        }
+
+        if em < 1.0e-6 {
+            em = 1.0e-6;
+        }
+
+        mm += self.no_unkozai * templ;
+        let mut xlm = mm + argpm + nodem;
+
+        nodem %= TWO_PI;
+        argpm %= TWO_PI;
+        xlm %= TWO_PI;
+        mm = (xlm - argpm - nodem) % TWO_PI;
+
+        let sinim = inclm.sin();
+        let cosim = inclm.cos();
+
+        // compute extra mid-time lunar-solar periodics
+        let mut ep = em;
+        let mut xincp = inclm;
+        let mut argpp = argpm;
+        let mut nodep = nodem;
+        let mut mp = mm;
+        let mut sinip = sinim;
+        let mut cosip = cosim;
+
+        let mut aycof = self.aycof;
+        let mut xlcof = self.xlcof;
+
+        if self.method == Method::DeepSpace {
+            self.dpper(t, &mut ep, &mut xincp, &mut nodep, &mut argpp, &mut mp);
+
+            if xincp < 0.0 {
+                xincp = -xincp;
+                nodep += PI;
+                argpp -= PI;
+            }
+
+            if ep < 0.0 || ep > 1.0 {
+                return Err(Sgp4Error::PerturbedEccentricity);
+            }
+
+            sinip = xincp.sin();
+            cosip = xincp.cos();
+            aycof = -0.5 * j3oj2 * sinip;
+
+            xlcof = if (cosip + 1.0).abs() > 1.5e-12 {
+                -0.25 * j3oj2 * sinip * (3.0 + 5.0 * cosip) / (1.0 + cosip)
+            } else {
+                -0.25 * j3oj2 * sinip * (3.0 + 5.0 * cosip) / temp4
+            };
+        }
+
+        // long period periodics
+        let axnl = ep * argpp.cos();
+        let temp = 1.0 / (am * (1.0 - ep * ep));
+        let aynl = ep * argpp.sin() + temp * aycof;
+        let xl = mp + argpp + nodep + temp * xlcof * axnl;
+
+        // solve kepler's equation
+        let u = (xl - nodep) % TWO_PI;
+        let mut eo1 = u;
+        let mut tem5: f64 = 9999.9;
+        let mut ktr = 1;
+        let mut sineo1 = 0.0;
+        let mut coseo1 = 0.0;
+
+        while tem5.abs() >= 1.0e-12 && ktr <= 10 {
+            sineo1 = eo1.sin();
+            coseo1 = eo1.cos();
+            tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+            tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+
+            if tem5.abs() >= 0.95 {
+                tem5 = if tem5 > 0.0 { 0.95 } else { -0.95 };
+            }
+
+            eo1 += tem5;
+            ktr += 1;
+        }
+
+        // short period preliminary quantities
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+
+        if pl < 0.0 {
+            return Err(Sgp4Error::SemiLatusRectum);
+        }
+
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let mut su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        let (con41, x1mth2, x7thm1) = if self.method == Method::DeepSpace {
+            let cosisq = cosip * cosip;
+            (3.0 * cosisq - 1.0, 1.0 - cosisq, 7.0 * cosisq - 1.0)
+        } else {
+            (self.con41, self.x1mth2, self.x7thm1)
+        };
+
+        // update for short period periodics
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        su -= 0.25 * temp2 * x7thm1 * sin2u;
+        let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+        let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / xke;
+        let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / xke;
+
+        // orientation vectors
+        let sinsu = su.sin();
+        let cossu = su.cos();
+        let snod = xnode.sin();
+        let cnod = xnode.cos();
+        let sini = xinc.sin();
+        let cosi = xinc.cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let ux = xmx * sinsu + cnod * cossu;
+        let uy = xmy * sinsu + snod * cossu;
+        let uz = sini * sinsu;
+        let vx = xmx * cossu - cnod * sinsu;
+        let vy = xmy * cossu - snod * sinsu;
+        let vz = sini * cossu;
+
+        if mrt < 1.0 {
+            return Err(Sgp4Error::Decayed);
+        }
+
+        return Ok(TemeState {
+            position: [
+                mrt * ux * EARTH_RADIUS_KM,
+                mrt * uy * EARTH_RADIUS_KM,
+                mrt * uz * EARTH_RADIUS_KM,
+            ],
+            velocity: [
+                (mvt * ux + rvdot * vx) * vkmpersec,
+                (mvt * uy + rvdot * vy) * vkmpersec,
+                (mvt * uz + rvdot * vz) * vkmpersec,
+            ],
+        });
+    }
+
+    /// Deep space common terms shared between initialization and lunar-solar periodics.
+    fn dscom(&mut self, epoch: f64, tc: f64) -> DeepSpaceCommon {
+        let zes = 0.01675;
+        let zel = 0.05490;
+        let c1ss = 2.9864797e-6;
+        let c1l = 4.7968065e-7;
+        let zsinis = 0.39785416;
+        let zcosis = 0.91744867;
+        let zcosgs = 0.1945905;
+        let zsings = -0.98088458;
+
+        let nm = self.no_unkozai;
+        let em = self.ecco;
+        let snodm = self.nodeo.sin();
+        let cnodm = self.nodeo.cos();
+        let sinomm = self.argpo.sin();
+        let cosomm = self.argpo.cos();
+        let sinim = self.inclo.sin();
+        let cosim = self.inclo.cos();
+        let emsq = em * em;
+        let betasq = 1.0 - emsq;
+        let rtemsq = betasq.sqrt();
+
+        // initialize lunar solar terms
+        let day = epoch + 18261.5 + tc / 1440.0;
+        let xnodce = (4.5236020 - 9.2422029e-4 * day) % TWO_PI;
+        let stem = xnodce.sin();
+        let ctem = xnodce.cos();
+        let zcosil = 0.91375164 - 0.03568096 * ctem;
+        let zsinil = (1.0 - zcosil * zcosil).sqrt();
+        let zsinhl = 0.089683511 * stem / zsinil;
+        let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+        let gam = 5.8351514 + 0.0019443680 * day;
+        let zx = 0.39785416 * stem / zsinil;
+        let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+        let zx = gam + zx.atan2(zy) - xnodce;
+        let zcosgl = zx.cos();
+        let zsingl = zx.sin();
+
+        // do solar terms
+        let mut zcosg = zcosgs;
+        let mut zsing = zsings;
+        let mut zcosi = zcosis;
+        let mut zsini = zsinis;
+        let mut zcosh = cnodm;
+        let mut zsinh = snodm;
+        let mut cc = c1ss;
+        let xnoi = 1.0 / nm;
+
+        let mut solar = [0.0; 19];
+        let mut lunar = [0.0; 19];
+
+        for lsflg in 1..=2 {
+            let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+            let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+            let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+            let a8 = zsing * zsini;
+            let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+            let a10 = zcosg * zsini;
+            let a2 = cosim * a7 + sinim * a8;
+            let a4 = cosim * a9 + sinim * a10;
+            let a5 = -sinim * a7 + cosim * a8;
+            let a6 = -sinim * a9 + cosim * a10;
+
+            let x1 = a1 * cosomm + a2 * sinomm;
+            let x2 = a3 * cosomm + a4 * sinomm;
+            let x3 = -a1 * sinomm + a2 * cosomm;
+            let x4 = -a3 * sinomm + a4 * cosomm;
+            let x5 = a5 * sinomm;
+            let x6 = a6 * sinomm;
+            let x7 = a5 * cosomm;
+            let x8 = a6 * cosomm;
+
+            let z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+            let z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+            let z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+            let mut z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+            let mut z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+            let mut z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+            let z11 = -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5);
+            let z12 = -6.0 * (a1 * a6 + a3 * a5)
+                + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5));
+            let z13 = -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6);
+            let z21 = 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7);
+            let z22 = 6.0 * (a4 * a5 + a2 * a6)
+                + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8));
+            let z23 = 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8);
+            z1 = z1 + z1 + betasq * z31;
+            z2 = z2 + z2 + betasq * z32;
+            z3 = z3 + z3 + betasq * z33;
+            let s3 = cc * xnoi;
+            let s2 = -0.5 * s3 / rtemsq;
+            let s4 = s3 * rtemsq;
+            let s1 = -15.0 * em * s4;
+            let s5 = x1 * x3 + x2 * x4;
+            let s6 = x2 * x3 + x1 * x4;
+            let s7 = x2 * x4 - x1 * x3;
+
+            let terms = [
+                s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33,
+            ];
+
+            if lsflg == 1 {
+                solar = terms;
+
+                zcosg = zcosgl;
+                zsing = zsingl;
+                zcosi = zcosil;
+                zsini = zsinil;
+                zcosh = zcoshl * cnodm + zsinhl * snodm;
+                zsinh = snodm * zcoshl - cnodm * zsinhl;
+                cc = c1l;
+            } else {
+                lunar = terms;
+            }
+        }
+
+        let [ss1, ss2, ss3, ss4, ss5, ss6, ss7, sz1, sz2, sz3, sz11, sz12, sz13, sz21, sz22, sz23, sz31, sz32, sz33] =
+            solar;
+        let [s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33] =
+            lunar;
+
+        let ds = &mut self.deep_space;
+        ds.zmol = (4.7199672 + 0.22997150 * day - gam) % TWO_PI;
+        ds.zmos = (6.2565837 + 0.017201977 * day) % TWO_PI;
+
+        // do solar terms
+        ds.se2 = 2.0 * ss1 * ss6;
+        ds.se3 = 2.0 * ss1 * ss7;
+        ds.si2 = 2.0 * ss2 * sz12;
+        ds.si3 = 2.0 * ss2 * (sz13 - sz11);
+        ds.sl2 = -2.0 * ss3 * sz2;
+        ds.sl3 = -2.0 * ss3 * (sz3 - sz1);
+        ds.sl4 = -2.0 * ss3 * (-21.0 - 9.0 * emsq) * zes;
+        ds.sgh2 = 2.0 * ss4 * sz32;
+        ds.sgh3 = 2.0 * ss4 * (sz33 - sz31);
+        ds.sgh4 = -18.0 * ss4 * zes;
+        ds.sh2 = -2.0 * ss2 * sz22;
+        ds.sh3 = -2.0 * ss2 * (sz23 - sz21);
+
+        // do lunar terms
+        ds.ee2 = 2.0 * s1 * s6;
+        ds.e3 = 2.0 * s1 * s7;
+        ds.xi2 = 2.0 * s2 * z12;
+        ds.xi3 = 2.0 * s2 * (z13 - z11);
+        ds.xl2 = -2.0 * s3 * z2;
+        ds.xl3 = -2.0 * s3 * (z3 - z1);
+        ds.xl4 = -2.0 * s3 * (-21.0 - 9.0 * emsq) * zel;
+        ds.xgh2 = 2.0 * s4 * z32;
+        ds.xgh3 = 2.0 * s4 * (z33 - z31);
+        ds.xgh4 = -18.0 * s4 * zel;
+        ds.xh2 = -2.0 * s2 * z22;
+        ds.xh3 = -2.0 * s2 * (z23 - z21);
+
+        return DeepSpaceCommon {
+            sinim,
+            cosim,
+            emsq,
+            s1,
+            s2,
+            s3,
+            s4,
+            s5,
+            ss1,
+            ss2,
+            ss3,
+            ss4,
+            ss5,
+            sz1,
+            sz3,
+            sz11,
+            sz13,
+            sz21,
+            sz23,
+            sz31,
+            sz33,
+            z1,
+            z3,
+            z11,
+            z13,
+            z21,
+            z23,
+            z31,
+            z33,
+        };
+    }
+
+    /// Deep space contributions to mean elements for perturbing third body and resonance terms.
+    fn dsinit(&mut self, c: &DeepSpaceCommon, xpidot: f64, eccsq: f64) {
+        let q22 = 1.7891679e-6;
+        let q31 = 2.1460748e-6;
+        let q33 = 2.2123015e-7;
+        let root22 = 1.7891679e-6;
+        let root44 = 7.3636953e-9;
+        let root54 = 2.1765803e-9;
+        let rptim = 4.37526908801129966e-3;
+        let root32 = 3.7393792e-7;
+        let root52 = 1.1428639e-7;
+        let znl = 1.5835218e-4;
+        let zns = 1.19459e-5;
+
+        let nm = self.no_unkozai;
+        let em = self.ecco;
+        let inclm = self.inclo;
+        let sinim = c.sinim;
+        let cosim = c.cosim;
+        let emsq = c.emsq;
+
+        let ds = &mut self.deep_space;
+
+        // deep space initialization
+        ds.irez = 0;
+        if nm < 0.0052359877 && nm > 0.0034906585 {
+            ds.irez = 1;
+        }
+        if (8.26e-3..=9.24e-3).contains(&nm) && em >= 0.5 {
+            ds.irez = 2;
+        }
+
+        // do solar terms
+        let ses = c.ss1 * zns * c.ss5;
+        let sis = c.ss2 * zns * (c.sz11 + c.sz13);
+        let sls = -zns * c.ss3 * (c.sz1 + c.sz3 - 14.0 - 6.0 * emsq);
+        let sghs = c.ss4 * zns * (c.sz31 + c.sz33 - 6.0);
+        let mut shs = -zns * c.ss2 * (c.sz21 + c.sz23);
+
+        if inclm < 5.2359877e-2 || inclm > PI - 5.2359877e-2 {
+            shs = 0.0;
+        }
+        if sinim != 0.0 {
+            shs /= sinim;
+        }
+        let sgs = sghs - cosim * shs;
+
+        // do lunar terms
+        ds.dedt = ses + c.s1 * znl * c.s5;
+        ds.didt = sis + c.s2 * znl * (c.z11 + c.z13);
+        ds.dmdt = sls - znl * c.s3 * (c.z1 + c.z3 - 14.0 - 6.0 * emsq);
+        let sghl = c.s4 * znl * (c.z31 + c.z33 - 6.0);
+        let mut shll = -znl * c.s2 * (c.z21 + c.z23);
+
+        if inclm < 5.2359877e-2 || inclm > PI - 5.2359877e-2 {
+            shll = 0.0;
+        }
+
+        ds.domdt = sgs + sghl;
+        ds.dnodt = shs;
+
+        if sinim != 0.0 {
+            ds.domdt -= cosim / sinim * shll;
+            ds.dnodt += shll / sinim;
+        }
+
+        // calculate deep space resonance effects
+        let theta = self.gsto % TWO_PI;
+
+        if ds.irez == 0 {
+            return;
+        }
+
+        let aonv = (nm / xke()).powf(X2O3);
+
+        // geopotential resonance for 12 hour orbits
+        if ds.irez == 2 {
+            let cosisq = cosim * cosim;
+            let em = self.ecco;
+            let emsq = eccsq;
+            let eoc = em * emsq;
+            let g201 = -0.306 - (em - 0.64) * 0.440;
+
+            let (g211, g310, g322, g410, g422, g520);
+            if em <= 0.65 {
+                g211 = 3.616 - 13.2470 * em + 16.2900 * emsq;
+                g310 = -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc;
+                g322 = -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc;
+                g410 = -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc;
+                g422 = -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc;
+                g520 = -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc;
+            } else {
+                g211 = -72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc;
+                g310 = -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc;
+                g322 = -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc;
+                g410 = -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc;
+                g422 = -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc;
+                g520 = if em > 0.715 {
+                    -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+                } else {
+                    1464.74 - 4664.75 * em + 3763.64 * emsq
+                };
+            }
+
+            let (g533, g521, g532);
+            if em < 0.7 {
+                g533 = -919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc;
+                g521 = -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc;
+                g532 = -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc;
+            } else {
+                g533 = -37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc;
+                g521 = -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc;
+                g532 = -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc;
+            }
+
+            let sini2 = sinim * sinim;
+            let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+            let f221 = 1.5 * sini2;
+            let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+            let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+            let f441 = 35.0 * sini2 * f220;
+            let f442 = 39.3750 * sini2 * sini2;
+            let f522 = 9.84375
+                * sinim
+                * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq)
+                    + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+            let f523 = sinim
+                * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+                    + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+            let f542 = 29.53125
+                * sinim
+                * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+            let f543 = 29.53125
+                * sinim
+                * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+
+            let xno2 = nm * nm;
+            let ainv2 = aonv * aonv;
+            let mut temp1 = 3.0 * xno2 * ainv2;
+            let mut temp = temp1 * root22;
+            ds.d2201 = temp * f220 * g201;
+            ds.d2211 = temp * f221 * g211;
+            temp1 *= aonv;
+            temp = temp1 * root32;
+            ds.d3210 = temp * f321 * g310;
+            ds.d3222 = temp * f322 * g322;
+            temp1 *= aonv;
+            temp = 2.0 * temp1 * root44;
+            ds.d4410 = temp * f441 * g410;
+            ds.d4422 = temp * f442 * g422;
+            temp1 *= aonv;
+            temp = temp1 * root52;
+            ds.d5220 = temp * f522 * g520;
+            ds.d5232 = temp * f523 * g532;
+            temp = 2.0 * temp1 * root54;
+            ds.d5421 = temp * f542 * g521;
+            ds.d5433 = temp * f543 * g533;
+            ds.xlamo = (self.mo + self.nodeo + self.nodeo - theta - theta) % TWO_PI;
+            ds.xfact =
+                self.mdot + ds.dmdt + 2.0 * (self.nodedot + ds.dnodt - rptim) - self.no_unkozai;
+        }
+
+        // synchronous resonance terms
+        if ds.irez == 1 {
+            let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+            let g310 = 1.0 + 2.0 * emsq;
+            let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+            let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+            let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+            let f330 = 1.0 + cosim;
+            let f330 = 1.875 * f330 * f330 * f330;
+            let del1 = 3.0 * nm * nm * aonv * aonv;
+            ds.del2 = 2.0 * del1 * f220 * g200 * q22;
+            ds.del3 = 3.0 * del1 * f330 * g300 * q33 * aonv;
+            ds.del1 = del1 * f311 * g310 * q31 * aonv;
+            ds.xlamo = (self.mo + self.nodeo + self.argpo - theta) % TWO_PI;
+            ds.xfact = self.mdot + xpidot - rptim + ds.dmdt + ds.domdt + ds.dnodt - self.no_unkozai;
+        }
+    }
+
+    /// Deep space secular effects and resonance integration.
+    fn dspace(&self, t: f64, mean: &mut MeanElements) {
+        let fasx2 = 0.13130908;
+        let fasx4 = 2.8843198;
+        let fasx6 = 0.37448087;
+        let g22 = 5.7686396;
+        let g32 = 0.95240898;
+        let g44 = 1.8014998;
+        let g52 = 1.0508330;
+        let g54 = 4.4108898;
+        let rptim = 4.37526908801129966e-3;
+        let stepp = 720.0;
+        let stepn = -720.0;
+        let step2 = 259200.0;
+
+        let ds = &self.deep_space;
+
+        // calculate deep space resonance effects
+        let theta = (self.gsto + t * rptim) % TWO_PI;
+        mean.em += ds.dedt * t;
+        mean.inclm += ds.didt * t;
+        mean.argpm += ds.domdt * t;
+        mean.nodem += ds.dnodt * t;
+        mean.mm += ds.dmdt * t;
+
+        if ds.irez == 0 {
+            return;
+        }
+
+        // the integrator always restarts from epoch, which keeps propagation free of side effects
+        let mut atime = 0.0;
+        let mut xni = self.no_unkozai;
+        let mut xli = ds.xlamo;
+        let delt = if t > 0.0 { stepp } else { stepn };
+
+        let ft;
+        let mut xndt;
+        let mut xldot;
+        let mut xnddt;
+
+        loop {
+            if ds.irez != 2 {
+                // near-synchronous resonance terms
+                xndt = ds.del1 * (xli - fasx2).sin()
+                    + ds.del2 * (2.0 * (xli - fasx4)).sin()
+                    + ds.del3 * (3.0 * (xli - fasx6)).sin();
+                xldot = xni + ds.xfact;
+                xnddt = ds.del1 * (xli - fasx2).cos()
+                    + 2.0 * ds.del2 * (2.0 * (xli - fasx4)).cos()
+                    + 3.0 * ds.del3 * (3.0 * (xli - fasx6)).cos();
+                xnddt *= xldot;
+            } else {
+                // near-half-day resonance terms
+                let xomi = self.argpo + self.argpdot * atime;
+                let x2omi = xomi + xomi;
+                let x2li = xli + xli;
+                xndt = ds.d2201 * (x2omi + xli - g22).sin()
+                    + ds.d2211 * (xli - g22).sin()
+                    + ds.d3210 * (xomi + xli - g32).sin()
+                    + ds.d3222 * (-xomi + xli - g32).sin()
+                    + ds.d4410 * (x2omi + x2li - g44).sin()
+                    + ds.d4422 * (x2li - g44).sin()
+                    + ds.d5220 * (xomi + xli - g52).sin()
+                    + ds.d5232 * (-xomi + xli - g52).sin()
+                    + ds.d5421 * (xomi + x2li - g54).sin()
+                    + ds.d5433 * (-xomi + x2li - g54).sin();
+                xldot = xni + ds.xfact;
+                xnddt = ds.d2201 * (x2omi + xli - g22).cos()
+                    + ds.d2211 * (xli - g22).cos()
+                    + ds.d3210 * (xomi + xli - g32).cos()
+                    + ds.d3222 * (-xomi + xli - g32).cos()
+                    + ds.d5220 * (xomi + xli - g52).cos()
+                    + ds.d5232 * (-xomi + xli - g52).cos()
+                    + 2.0
+                        * (ds.d4410 * (x2omi + x2li - g44).cos()
+                            + ds.d4422 * (x2li - g44).cos()
+                            + ds.d5421 * (xomi + x2li - g54).cos()
+                            + ds.d5433 * (-xomi + x2li - g54).cos());
+                xnddt *= xldot;
+            }
+
+            if (t - atime).abs() >= stepp {
+                xli += xldot * delt + xndt * step2;
+                xni += xndt * delt + xnddt * step2;
+                atime += delt;
+            } else {
+                ft = t - atime;
+                break;
+            }
+        }
+
+        mean.nm = xni + xndt * ft + xnddt * ft * ft * 0.5;
+        let xl = xli + xldot * ft + xnddt * ft * ft * 0.5;
+
+        if ds.irez != 1 {
+            mean.mm = xl - 2.0 * mean.nodem + 2.0 * theta;
+        } else {
+            mean.mm = xl - mean.nodem - mean.argpm + theta;
+        }
+    }
+
+    /// Lunar-solar periodics.
+    fn dpper(
+        &self,
+        t: f64,
+        ep: &mut f64,
+        inclp: &mut f64,
+        nodep: &mut f64,
+        argpp: &mut f64,
+        mp: &mut f64,
+    ) {
+        let zns = 1.19459e-5;
+        let zes = 0.01675;
+        let znl = 1.5835218e-4;
+        let zel = 0.05490;
+
+        let ds = &self.deep_space;
+
+        // calculate time varying periodics
+        let zm = ds.zmos + zns * t;
+        let zf = zm + 2.0 * zes * zm.sin();
+        let sinzf = zf.sin();
+        let f2 = 0.5 * sinzf * sinzf - 0.25;
+        let f3 = -0.5 * sinzf * zf.cos();
+        let ses = ds.se2 * f2 + ds.se3 * f3;
+        let sis = ds.si2 * f2 + ds.si3 * f3;
+        let sls = ds.sl2 * f2 + ds.sl3 * f3 + ds.sl4 * sinzf;
+        let sghs = ds.sgh2 * f2 + ds.sgh3 * f3 + ds.sgh4 * sinzf;
+        let shs = ds.sh2 * f2 + ds.sh3 * f3;
+
+        let zm = ds.zmol + znl * t;
+        let zf = zm + 2.0 * zel * zm.sin();
+        let sinzf = zf.sin();
+        let f2 = 0.5 * sinzf * sinzf - 0.25;
+        let f3 = -0.5 * sinzf * zf.cos();
+        let sel = ds.ee2 * f2 + ds.e3 * f3;
+        let sil = ds.xi2 * f2 + ds.xi3 * f3;
+        let sll = ds.xl2 * f2 + ds.xl3 * f3 + ds.xl4 * sinzf;
+        let sghl = ds.xgh2 * f2 + ds.xgh3 * f3 + ds.xgh4 * sinzf;
+        let shll = ds.xh2 * f2 + ds.xh3 * f3;
+
+        let pe = ses + sel;
+        let pinc = sis + sil;
+        let pl = sls + sll;
+        let mut pgh = sghs + sghl;
+        let mut ph = shs + shll;
+
+        *inclp += pinc;
+        *ep += pe;
+        let sinip = inclp.sin();
+        let cosip = inclp.cos();
+
+        if *inclp >= 0.2 {
+            // apply periodics directly
+            ph /= sinip;
+            pgh -= cosip * ph;
+            *argpp += pgh;
+            *nodep += ph;
+            *mp += pl;
+        } else {
+            // apply periodics with lyddane modification
+            let sinop = nodep.sin();
+            let cosop = nodep.cos();
+            let mut alfdp = sinip * sinop;
+            let mut betdp = sinip * cosop;
+            let dalf = ph * cosop + pinc * cosip * sinop;
+            let dbet = -ph * sinop + pinc * cosip * cosop;
+            alfdp += dalf;
+            betdp += dbet;
+            *nodep %= TWO_PI;
+
+            let mut xls = *mp + *argpp + cosip * *nodep;
+            let dls = pl + pgh - pinc * *nodep * sinip;
+            xls += dls;
+            let xnoh = *nodep;
+            *nodep = alfdp.atan2(betdp);
+
+            if (xnoh - *nodep).abs() > PI {
+                if *nodep < xnoh {
+                    *nodep += TWO_PI;
+                } else {
+                    *nodep -= TWO_PI;
+                }
+            }
+
+            *mp += pl;
+            *argpp = xls - *mp - cosip * *nodep;
+        }
+    }
+}
+
+/// Convenience wrapper: parses a two-line element set and initializes the model.
+pub fn from_tle(tle1: &str, tle2: &str) -> Result<Sgp4> {
+    let tle = TLE::new("", tle1, tle2)?;
+    return Ok(Sgp4::new(&Elements::from(&tle))?);
+}
@@ -0,0 +1,336 @@
+// Two-line element set in the NORAD format.
+// https://celestrak.org/NORAD/documentation/tle-fmt.php
+
+use std::ops::Range;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use table_macro::Property;
+use thiserror::Error;
+
+const LINE_LENGTH: usize = 69;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum TleError {
+    #[error("line {line} should be {LINE_LENGTH} characters long, got {length}")]
+    LineLength { line: u8, length: usize },
+    #[error("line {line} should start with '{line} '")]
+    LineNumber { line: u8 },
+    #[error("checksum of line {line} is {actual}, expected {expected}")]
+    Checksum {
+        line: u8,
+        expected: u32,
+        actual: u32,
+    },
+    #[error("catalog numbers of line 1 ({first}) and line 2 ({second}) differ")]
+    CatalogNumberMismatch { first: u32, second: u32 },
+    #[error("invalid {field} '{value}' in line {line}")]
+    InvalidField {
+        line: u8,
+        field: &'static str,
+        value: String,
+    },
+}
+
+/// Parsed and validated element set. Angles are in degrees, mean motion in revolutions per day.
+#[derive(Clone, Debug, Property)]
+pub struct TLE {
+    #[getter]
+    name: String,
+    #[getter]
+    tle1: String,
+    #[getter]
+    tle2: String,
+
+    #[getter]
+    catnr: u32,
+    #[getter]
+    classification: char,
+    /// International designator (yyNNNppp), empty for analyst objects
+    #[getter]
+    international_designator: String,
+    #[getter]
+    epoch: DateTime<Utc>,
+    /// First derivative of mean motion divided by two, rev/day^2
+    #[getter]
+    mean_motion_dot: f64,
+    /// Second derivative of mean motion divided by six, rev/day^3
+    #[getter]
+    mean_motion_ddot: f64,
+    /// Drag term, 1/earth radii
+    #[getter]
+    bstar: f64,
+    #[getter]
+    element_set_number: u32,
+
+    #[getter]
+    inclination: f64,
+    #[getter]
+    right_ascension: f64,
+    #[getter]
+    eccentricity: f64,
+    #[getter]
+    argument_of_perigee: f64,
+    #[getter]
+    mean_anomaly: f64,
+    #[getter]
+    mean_motion: f64,
+    #[getter]
+    revolution_number: u32,
+}
+
+/// Modulo 10 sum of the digits of the first 68 characters, minus signs count as 1.
+pub fn checksum(line: &str) -> u32 {
+    return line
+        .chars()
+        .take(LINE_LENGTH - 1)
+        .map(|it| match it {
+            '-' => 1,
+            _ => it.to_digit(10).unwrap_or(0),
+        })
+        .sum::<u32>()
+        % 10;
+}
+
+/// Parses a catalog number of a TLE line, either 5 digits or Alpha-5 (letter for the ten-thousands, I and O skipped).
+pub fn parse_catnr(value: &str) -> Option<u32> {
+    let value = value.trim_start();
+    let first = value.chars().next()?;
+    let rest = value.get(1..)?;
+
+    if first.is_ascii_uppercase() {
+        if first == 'I'
+            || first == 'O'
+            || rest.len() != 4
+            || !rest.chars().all(|it| it.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let skipped = match first {
+            'A'..='H' => 0,
+            'J'..='N' => 1,
+            _ => 2,
+        };
+        let ten_thousands = first as u32 - 'A' as u32 + 10 - skipped;
+
+        return Some(ten_thousands * 10_000 + rest.parse::<u32>().ok()?);
+    }
+
+    if !value.chars().all(|it| it.is_ascii_digit()) {
+        return None;
+    }
+
+    return value.parse::<u32>().ok();
+}
+
+/// Converts a two-digit TLE epoch year and fractional day of year into UTC time.
+pub fn epoch_from_tle(year: i32, day_of_year: f64) -> Option<DateTime<Utc>> {
+    let year = if year < 57 { 2000 + year } else { 1900 + year };
+    let start = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single()?;
+
+    let nanoseconds = ((day_of_year - 1.0) * 86_400e9).round() as i64;
+    return Some(start + Duration::nanoseconds(nanoseconds));
+}
+
+/// Converts an international designator of a TLE (yyNNNppp) into the OMM form (yyyy-nnnppp).
+pub fn expand_international_designator(designator: &str) -> String {
+    return match (
+        designator.get(0..2).and_then(|it| it.parse::<i32>().ok()),
+        designator.get(2..),
+    ) {
+        (Some(year), Some(rest)) => {
+            let year = if year < 57 { 2000 + year } else { 1900 + year };
+            format!("{}-{}", year, rest)
+        }
+        _ => String::from(designator),
+    };
+}
+
+/// Parses consecutive element sets, each optionally preceded by a name line (3LE or 2LE).
+/// The "0 " prefix of name lines in the Space-Track 3LE format is dropped.
+pub fn parse_3le(text: &str) -> Result<Vec<TLE>, TleError> {
+    let lines = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|it| !it.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut result = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let name = if lines[index].starts_with("1 ") {
+            ""
+        } else {
+            index += 1;
+            let name = lines[index - 1];
+            name.strip_prefix("0 ").unwrap_or(name)
+        };
+
+        // a missing line is reported as a line of zero length
+        let tle1 = lines.get(index).copied().unwrap_or("");
+        let tle2 = lines.get(index + 1).copied().unwrap_or("");
+        result.push(TLE::new(name, tle1, tle2)?);
+
+        index += 2;
+    }
+
+    return Ok(result);
+}
+
+struct Line<'a> {
+    number: u8,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn new(number: u8, text: &'a str) -> Result<Self, TleError> {
+        let text = text.trim_end();
+
+        if text.len() != LINE_LENGTH || !text.is_ascii() {
+            return Err(TleError::LineLength {
+                line: number,
+                length: text.chars().count(),
+            });
+        }
+
+        if !text.starts_with(&format!("{} ", number)) {
+            return Err(TleError::LineNumber { line: number });
+        }
+
+        let expected = checksum(text);
+        let actual = Line::digit(text, LINE_LENGTH - 1);
+        if actual != Some(expected) {
+            return Err(TleError::Checksum {
+                line: number,
+                expected,
+                actual: actual.unwrap_or(0),
+            });
+        }
+
+        return Ok(Self { number, text });
+    }
+
+    fn digit(text: &str, index: usize) -> Option<u32> {
+        return text.chars().nth(index)?.to_digit(10);
+    }
+
+    fn error(&self, field: &'static str, range: Range<usize>) -> TleError {
+        return TleError::InvalidField {
+            line: self.number,
+            field,
+            value: String::from(&self.text[range]),
+        };
+    }
+
+    fn str(&self, range: Range<usize>) -> &'a str {
+        return self.text[range].trim();
+    }
+
+    fn parse<T: std::str::FromStr>(
+        &self,
+        field: &'static str,
+        range: Range<usize>,
+    ) -> Result<T, TleError> {
+        return self
+            .str(range.clone())
+            .parse::<T>()
+            .map_err(|_| self.error(field, range));
+    }
+
+    fn parse_catnr(&self) -> Result<u32, TleError> {
+        return parse_catnr(&self.text[2..7]).ok_or_else(|| self.error("catalog number", 2..7));
+    }
+
+    /// Value with an assumed leading decimal point, e.g. eccentricity `1859667` is `0.1859667`.
+    fn parse_decimal(&self, field: &'static str, range: Range<usize>) -> Result<f64, TleError> {
+        let value = self.str(range.clone());
+        if value.is_empty() || !value.chars().all(|it| it.is_ascii_digit()) {
+            return Err(self.error(field, range));
+        }
+
+        return format!("0.{}", value)
+            .parse::<f64>()
+            .map_err(|_| self.error(field, range));
+    }
+
+    /// Value in the "assumed decimal point" exponential notation, e.g. ` 28098-4` is `0.28098e-4`.
+    fn parse_exponential(&self, field: &'static str, range: Range<usize>) -> Result<f64, TleError> {
+        let value = self.str(range.clone());
+        if value.len() < 3 {
+            return Err(self.error(field, range));
+        }
+
+        let (mantissa, exponent) = value.split_at(value.len() - 2);
+        let (sign, mantissa) = match mantissa.strip_prefix('-') {
+            Some(mantissa) => (-1.0, mantissa),
+            None => (1.0, mantissa.trim_start_matches('+')),
+        };
+
+        let mantissa = mantissa.trim();
+        if mantissa.is_empty() || !mantissa.chars().all(|it| it.is_ascii_digit()) {
+            return Err(self.error(field, range));
+        }
+
+        let mantissa = format!("0.{}", mantissa)
+            .parse::<f64>()
+            .map_err(|_| self.error(field, range.clone()))?;
+        let exponent = exponent
+            .parse::<i32>()
+            .map_err(|_| self.error(field, range))?;
+
+        return Ok(sign * mantissa * 10f64.powi(exponent));
+    }
+}
+
+impl TLE {
+    pub fn new(name: &str, tle1: &str, tle2: &str) -> Result<Self, TleError> {
+        let first = Line::new(1, tle1)?;
+        let second = Line::new(2, tle2)?;
+
+        let catnr = first.parse_catnr()?;
+        let second_catnr = second.parse_catnr()?;
+        if catnr != second_catnr {
+            return Err(TleError::CatalogNumberMismatch {
+                first: catnr,
+                second: second_catnr,
+            });
+        }
+
+        let classification = first.text[7..8]
+            .chars()
+            .next()
+            .filter(|it| matches!(it, 'U' | 'C' | 'S'))
+            .ok_or_else(|| first.error("classification", 7..8))?;
+
+        let year = first.parse::<i32>("epoch year", 18..20)?;
+        let day_of_year = first.parse::<f64>("epoch day", 20..32)?;
+        if !(1.0..367.0).contains(&day_of_year) {
+            return Err(first.error("epoch day", 20..32));
+        }
+        let epoch =
+            epoch_from_tle(year, day_of_year).ok_or_else(|| first.error("epoch", 18..32))?;
+
+        return Ok(Self {
+            name: String::from(name.trim()),
+            tle1: String::from(first.text),
+            tle2: String::from(second.text),
+
+            catnr,
+            classification,
+            international_designator: String::from(first.str(9..17)),
+            epoch,
+            mean_motion_dot: first.parse::<f64>("mean motion first derivative", 33..43)?,
+            mean_motion_ddot: first.parse_exponential("mean motion second derivative", 44..52)?,
+            bstar: first.parse_exponential("bstar", 53..61)?,
+            element_set_number: first.parse::<u32>("element set number", 64..68)?,
+
+            inclination: second.parse::<f64>("inclination", 8..16)?,
+            right_ascension: second.parse::<f64>("right ascension", 17..25)?,
+            eccentricity: second.parse_decimal("eccentricity", 26..33)?,
+            argument_of_perigee: second.parse::<f64>("argument of perigee", 34..42)?,
+            mean_anomaly: second.parse::<f64>("mean anomaly", 43..51)?,
+            mean_motion: second.parse::<f64>("mean motion", 52..63)?,
+            revolution_number: second.parse::<u32>("revolution number", 63..68)?,
+        });
+    }
+}
@@ -2,11 +2,24 @@ import { HOST } from './constants.api';
 
 const SATELLITES_GET = `${HOST}/satellite/all`;
 
+export type SatelliteCatalogResponse = {
+    international_designator: string;
+    owner: string;
+    launch_date: string | null;
+    launch_site: string;
+    object_type: string;
+    rcs: number | null;
+    operational_status: string;
+    decay_date: string | null;
+};
+
 export type SatelliteResponse = {
     id: number;
     name: string;
-    tle1: string;
-    tle2: string;
+    catnr: number | null;
+    tle1: string | null;
+    tle2: string | null;
+    catalog: SatelliteCatalogResponse | null;
 };
 
 export function getSatellites(): Promise<[SatelliteResponse]> {